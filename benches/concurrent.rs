@@ -0,0 +1,72 @@
+//! Throughput of the concurrent stacks under contention.
+//!
+//! Run with `cargo bench --bench concurrent`, adding `--features crossbeam-epoch` to compare the
+//! hazard pointer reclamation of `ConcurrentStack` with the epoch-based reclamation of
+//! `EpochStack`.
+use hay::ConcurrentStack;
+#[cfg(feature = "crossbeam-epoch")]
+use hay::EpochStack;
+use std::{
+    hint::black_box,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The number of push/pop pairs each thread performs.
+const PAIRS_PER_THREAD: usize = 200_000;
+
+/// Runs `pair` on `threads` threads at once, returning how long it took.
+fn time_threads(threads: usize, pair: impl Fn(usize) + Sync) -> Duration {
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for i in 0..PAIRS_PER_THREAD {
+                    pair(i);
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+/// Prints the throughput of one benchmark run.
+fn report(name: &str, threads: usize, elapsed: Duration) {
+    let operations = (2 * threads * PAIRS_PER_THREAD) as f64;
+    println!(
+        "{name:>16} {threads:>2} threads: {:>8.2} Mop/s",
+        operations / elapsed.as_secs_f64() / 1e6
+    );
+}
+
+fn main() {
+    let max_threads = thread::available_parallelism().map_or(4, usize::from);
+    let mut threads = 1;
+    while threads <= max_threads {
+        let stack = ConcurrentStack::new();
+        let elapsed = time_threads(threads, |i| {
+            stack.push(i);
+            black_box(stack.pop());
+        });
+        report("ConcurrentStack", threads, elapsed);
+
+        #[cfg(feature = "crossbeam-epoch")]
+        {
+            let stack = EpochStack::new();
+            let elapsed = time_threads(threads, |i| {
+                stack.push(i);
+                black_box(stack.pop());
+            });
+            report("EpochStack", threads, elapsed);
+        }
+
+        let stack = Mutex::new(Vec::new());
+        let elapsed = time_threads(threads, |i| {
+            stack.lock().unwrap().push(i);
+            black_box(stack.lock().unwrap().pop());
+        });
+        report("Mutex<Vec>", threads, elapsed);
+        threads *= 2;
+    }
+}
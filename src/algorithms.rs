@@ -0,0 +1,455 @@
+//! Algorithms built on a [Stack].
+//!
+//! The nearest greater and smaller element searches keep a monotonic stack of indices, which
+//! finds the answer for every element of a slice in linear time.
+//! # Example
+//! ```
+//! use hay::algorithms;
+//! let temperatures = [73, 74, 75, 71, 69, 72, 76, 73];
+//! let warmer = algorithms::next_greater(&temperatures);
+//! assert_eq!(
+//!     warmer,
+//!     [Some(1), Some(2), Some(6), Some(5), Some(5), Some(6), None, None],
+//! );
+//! ```
+use crate::{
+    traverse::{self, Event, VisitedSet},
+    Stack,
+};
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use core::{
+    cmp::Ordering,
+    fmt::{self, Debug, Display},
+};
+
+/// Returns the index of the next element greater than each element, or [None] if there is none.
+/// # Example
+/// ```
+/// use hay::algorithms;
+/// assert_eq!(algorithms::next_greater(&[2, 1, 3]), [Some(2), Some(2), None]);
+/// ```
+#[must_use]
+pub fn next_greater<T: PartialOrd>(values: &[T]) -> Vec<Option<usize>> {
+    let mut out = vec![None; values.len()];
+    next_greater_into(values, &mut out);
+    out
+}
+
+/// Writes the index of the next element greater than each element into `out`, or [None] if there
+/// is none.
+/// # Panics
+/// Panics if `out` isn't as long as `values`.
+#[inline(always)]
+pub fn next_greater_into<T: PartialOrd>(values: &[T], out: &mut [Option<usize>]) {
+    resolve_next(values, out, |later, earlier| later > earlier);
+}
+
+/// Returns the index of the next element smaller than each element, or [None] if there is none.
+/// # Example
+/// ```
+/// use hay::algorithms;
+/// assert_eq!(algorithms::next_smaller(&[2, 3, 1]), [Some(2), Some(2), None]);
+/// ```
+#[must_use]
+pub fn next_smaller<T: PartialOrd>(values: &[T]) -> Vec<Option<usize>> {
+    let mut out = vec![None; values.len()];
+    next_smaller_into(values, &mut out);
+    out
+}
+
+/// Writes the index of the next element smaller than each element into `out`, or [None] if there
+/// is none.
+/// # Panics
+/// Panics if `out` isn't as long as `values`.
+#[inline(always)]
+pub fn next_smaller_into<T: PartialOrd>(values: &[T], out: &mut [Option<usize>]) {
+    resolve_next(values, out, |later, earlier| later < earlier);
+}
+
+/// Returns the index of the previous element greater than each element, or [None] if there is
+/// none.
+/// # Example
+/// ```
+/// use hay::algorithms;
+/// assert_eq!(algorithms::previous_greater(&[3, 1, 2]), [None, Some(0), Some(0)]);
+/// ```
+#[must_use]
+pub fn previous_greater<T: PartialOrd>(values: &[T]) -> Vec<Option<usize>> {
+    let mut out = vec![None; values.len()];
+    previous_greater_into(values, &mut out);
+    out
+}
+
+/// Writes the index of the previous element greater than each element into `out`, or [None] if
+/// there is none.
+/// # Panics
+/// Panics if `out` isn't as long as `values`.
+#[inline(always)]
+pub fn previous_greater_into<T: PartialOrd>(values: &[T], out: &mut [Option<usize>]) {
+    resolve_previous(values, out, |earlier, current| earlier > current);
+}
+
+/// Returns the index of the previous element smaller than each element, or [None] if there is
+/// none.
+/// # Example
+/// ```
+/// use hay::algorithms;
+/// assert_eq!(algorithms::previous_smaller(&[1, 3, 2]), [None, Some(0), Some(0)]);
+/// ```
+#[must_use]
+pub fn previous_smaller<T: PartialOrd>(values: &[T]) -> Vec<Option<usize>> {
+    let mut out = vec![None; values.len()];
+    previous_smaller_into(values, &mut out);
+    out
+}
+
+/// Writes the index of the previous element smaller than each element into `out`, or [None] if
+/// there is none.
+/// # Panics
+/// Panics if `out` isn't as long as `values`.
+/// # Example
+/// ```
+/// use hay::algorithms;
+/// let mut out = [None; 3];
+/// algorithms::previous_smaller_into(&[1, 3, 2], &mut out);
+/// assert_eq!(out, [None, Some(0), Some(0)]);
+/// ```
+#[inline(always)]
+pub fn previous_smaller_into<T: PartialOrd>(values: &[T], out: &mut [Option<usize>]) {
+    resolve_previous(values, out, |earlier, current| earlier < current);
+}
+
+/// Writes the index of the next element which `beats` each element into `out`, keeping the
+/// indices still waiting for one on a stack.
+fn resolve_next<T>(values: &[T], out: &mut [Option<usize>], beats: impl Fn(&T, &T) -> bool) {
+    assert_eq!(
+        values.len(),
+        out.len(),
+        "the output must be as long as the values"
+    );
+    let mut waiting = Stack::new();
+    for (index, value) in values.iter().enumerate() {
+        while let Some(earlier) = waiting
+            .vec
+            .pop_if(|&mut earlier| beats(value, &values[earlier]))
+        {
+            out[earlier] = Some(index);
+        }
+        waiting.push(index);
+    }
+    for earlier in waiting.vec {
+        out[earlier] = None;
+    }
+}
+
+/// Writes the index of the previous element which `beats` each element into `out`, keeping the
+/// indices which could still be one on a stack.
+fn resolve_previous<T>(values: &[T], out: &mut [Option<usize>], beats: impl Fn(&T, &T) -> bool) {
+    assert_eq!(
+        values.len(),
+        out.len(),
+        "the output must be as long as the values"
+    );
+    let mut candidates: Stack<usize> = Stack::new();
+    for (index, value) in values.iter().enumerate() {
+        while candidates
+            .top()
+            .is_some_and(|&earlier| !beats(&values[earlier], value))
+        {
+            candidates.pop();
+        }
+        out[index] = candidates.top().copied();
+        candidates.push(index);
+    }
+}
+
+/// An axis-aligned rectangle, found by [largest_rectangle] or [maximal_rectangle].
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Rectangle {
+    /// The index of the leftmost column covered.
+    pub x: usize,
+    /// The index of the row covered by the top edge, which is always zero in a histogram.
+    pub y: usize,
+    /// The number of columns covered.
+    pub width: usize,
+    /// The number of rows covered, or the height of the bars in a histogram.
+    pub height: usize,
+}
+impl Rectangle {
+    /// Returns the area of the rectangle, saturating at `usize::MAX`.
+    #[inline(always)]
+    pub const fn area(&self) -> usize {
+        self.width.saturating_mul(self.height)
+    }
+}
+
+/// Returns the largest rectangle fitting under a histogram with bars of the given heights, or
+/// [None] if every bar is empty.
+///
+/// The leftmost one is returned if several share the largest area.
+/// # Example
+/// ```
+/// use hay::algorithms::{self, Rectangle};
+/// assert_eq!(
+///     algorithms::largest_rectangle(&[2, 1, 5, 6, 2, 3]),
+///     Some(Rectangle { x: 2, y: 0, width: 2, height: 5 }),
+/// );
+/// assert_eq!(algorithms::largest_rectangle(&[0, 0]), None);
+/// ```
+#[must_use]
+pub fn largest_rectangle(heights: &[usize]) -> Option<Rectangle> {
+    largest_in(heights, &mut Stack::new())
+}
+
+/// Returns the largest rectangle of `true` cells in a matrix, given as a slice of rows, or [None]
+/// if every cell is `false`.
+///
+/// Each row is treated as the bottom of a histogram of the `true` cells stacked above it, so
+/// finding the rectangle takes time linear in the number of cells.
+/// # Panics
+/// Panics if the rows aren't all the same length.
+/// # Example
+/// ```
+/// use hay::algorithms::{self, Rectangle};
+/// let matrix = [
+///     [true, false, true, false, false],
+///     [true, false, true, true, true],
+///     [true, true, true, true, true],
+///     [true, false, false, true, false],
+/// ];
+/// assert_eq!(
+///     algorithms::maximal_rectangle(&matrix),
+///     Some(Rectangle { x: 2, y: 1, width: 3, height: 2 }),
+/// );
+/// ```
+#[must_use]
+pub fn maximal_rectangle<R: AsRef<[bool]>>(matrix: &[R]) -> Option<Rectangle> {
+    let columns = matrix.first().map_or(0, |row| row.as_ref().len());
+    let mut heights = vec![0; columns];
+    let mut bars = Stack::new();
+    let mut best: Option<Rectangle> = None;
+    for (y, row) in matrix.iter().enumerate() {
+        let row = row.as_ref();
+        assert_eq!(row.len(), columns, "every row must be the same length");
+        for (height, &cell) in heights.iter_mut().zip(row) {
+            *height = if cell { *height + 1 } else { 0 };
+        }
+        if let Some(mut rectangle) = largest_in(&heights, &mut bars) {
+            rectangle.y = y + 1 - rectangle.height;
+            if best.is_none_or(|best| rectangle.area() > best.area()) {
+                best = Some(rectangle);
+            }
+        }
+    }
+    best
+}
+
+/// Returns the largest rectangle under a histogram, keeping the bars which could still extend
+/// to the right on `bars` as pairs of where they start and their height.
+fn largest_in(heights: &[usize], bars: &mut Stack<(usize, usize)>) -> Option<Rectangle> {
+    bars.clear();
+    let mut best: Option<Rectangle> = None;
+    // Ending with an empty bar, so every bar left is measured.
+    for (x, &height) in heights.iter().chain([&0]).enumerate() {
+        let mut start = x;
+        while let Some((bar_start, bar_height)) =
+            bars.vec.pop_if(|&mut (_, bar_height)| bar_height > height)
+        {
+            let rectangle = Rectangle {
+                x: bar_start,
+                y: 0,
+                width: x - bar_start,
+                height: bar_height,
+            };
+            if best.is_none_or(|best| {
+                rectangle.area() > best.area()
+                    || rectangle.area() == best.area() && rectangle.x < best.x
+            }) {
+                best = Some(rectangle);
+            }
+            start = bar_start;
+        }
+        if bars
+            .top()
+            .is_none_or(|&(_, bar_height)| bar_height < height)
+        {
+            bars.push((start, height));
+        }
+    }
+    best
+}
+
+/// The error returned when [topo_sort] finds a cycle of dependencies.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Cycle<N> {
+    path: Vec<N>,
+}
+impl<N> Cycle<N> {
+    /// Returns the nodes in the cycle, where each depends on the next and the last depends on the
+    /// first.
+    #[inline(always)]
+    pub fn path(&self) -> &[N] {
+        &self.path
+    }
+
+    /// Consumes the error, returning the nodes in the cycle.
+    #[inline(always)]
+    pub fn into_path(self) -> Vec<N> {
+        self.path
+    }
+}
+impl<N: Debug> Display for Cycle<N> {
+    /// Formats the error, listing the nodes in the cycle.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("dependency cycle: ")?;
+        for node in &self.path {
+            write!(f, "{node:?} -> ")?;
+        }
+        match self.path.first() {
+            Some(first) => write!(f, "{first:?}"),
+            None => Ok(()),
+        }
+    }
+}
+impl<N: Debug> core::error::Error for Cycle<N> {}
+
+/// The nodes a topological sort has discovered, and whether they have been finished, noting the
+/// first node found to depend on itself.
+struct Marks<N> {
+    finished: BTreeMap<N, bool>,
+    cycle: Option<N>,
+}
+impl<N: Ord + Clone> VisitedSet<N> for Marks<N> {
+    /// Marks `node` as discovered, noting a cycle if it was discovered but not yet finished.
+    fn insert(&mut self, node: &N) -> bool {
+        match self.finished.get(node) {
+            Some(true) => false,
+            Some(false) => {
+                self.cycle.get_or_insert_with(|| node.clone());
+                false
+            }
+            None => {
+                self.finished.insert(node.clone(), false);
+                true
+            }
+        }
+    }
+}
+
+/// Sorts `nodes` and everything they depend on so that every node comes after its dependencies,
+/// with a depth-first search.
+///
+/// Nodes with no order between them are kept in the order they are reached.
+/// # Errors
+/// Returns a [Cycle] if nodes depend on each other in a loop, holding the path of the loop taken
+/// from the search's stack.
+/// # Example
+/// ```
+/// use hay::algorithms;
+/// let dependencies = |&crate_: &&str| match crate_ {
+///     "app" => vec!["http", "log"],
+///     "http" => vec!["io", "log"],
+///     _ => vec![],
+/// };
+/// assert_eq!(
+///     algorithms::topo_sort(["app"], dependencies),
+///     Ok(vec!["io", "log", "http", "app"]),
+/// );
+/// let cyclic = |&node: &u8| vec![(node + 1) % 3];
+/// let cycle = algorithms::topo_sort([0], cyclic).unwrap_err();
+/// assert_eq!(cycle.path(), [0, 1, 2]);
+/// let branching = |&node: &u8| match node {
+///     0 => vec![1],
+///     1 => vec![2, 3],
+///     3 => vec![1],
+///     _ => vec![],
+/// };
+/// let cycle = algorithms::topo_sort([0], branching).unwrap_err();
+/// assert_eq!(cycle.path(), [1, 3]);
+/// ```
+pub fn topo_sort<N, I, F>(
+    nodes: impl IntoIterator<Item = N>,
+    dependencies: F,
+) -> Result<Vec<N>, Cycle<N>>
+where
+    N: Ord + Clone,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    let marks = Marks {
+        finished: BTreeMap::new(),
+        cycle: None,
+    };
+    let mut search = traverse::dfs_with(nodes, dependencies, marks);
+    let mut sorted = Vec::new();
+    while let Some(event) = search.next() {
+        if let Some(node) = search.visited_mut().cycle.take() {
+            // The cycle was closed by a dependency of the node on top of the stack, which has
+            // since had the next node discovered above it or been finished and popped.
+            let mut path: Vec<N> = search.path().cloned().collect();
+            match event {
+                Event::Discover(_) => drop(path.pop()),
+                Event::Finish(node) => path.push(node),
+            }
+            let start = path.iter().position(|open| *open == node).unwrap_or(0);
+            path.drain(..start);
+            return Err(Cycle { path });
+        }
+        if let Event::Finish(node) = event {
+            search.visited_mut().finished.insert(node.clone(), true);
+            sorted.push(node);
+        }
+    }
+    Ok(sorted)
+}
+
+/// Sorts a stack with the greatest element on top, using only pushes and pops and one auxiliary
+/// stack.
+///
+/// Each element is popped and pushed onto the auxiliary stack, after moving back every greater
+/// element above where it belongs, so sorting takes quadratic time. The sort isn't stable.
+/// # Panics
+/// Panics if the new capacity of either stack exceeds `isize::MAX`.
+/// # Example
+/// ```
+/// use hay::{algorithms, Stack};
+/// let mut stack = Stack::new();
+/// stack.extend([3, 1, 4, 1, 5]);
+/// algorithms::sort_with_aux(&mut stack);
+/// assert_eq!(*stack, [1, 1, 3, 4, 5]);
+/// assert_eq!(stack.top(), Some(&5));
+/// ```
+#[inline(always)]
+pub fn sort_with_aux<T: Ord>(stack: &mut Stack<T>) {
+    sort_with_aux_by(stack, T::cmp);
+}
+
+/// Sorts a stack with the greatest element by `compare` on top, using only pushes and pops and
+/// one auxiliary stack.
+///
+/// See [sort_with_aux].
+/// # Panics
+/// Panics if the new capacity of either stack exceeds `isize::MAX`.
+/// # Example
+/// ```
+/// use hay::{algorithms, Stack};
+/// let mut stack = Stack::new();
+/// stack.extend(["ccc", "a", "bb"]);
+/// // Keeping the shortest string on top.
+/// algorithms::sort_with_aux_by(&mut stack, |a, b| b.len().cmp(&a.len()));
+/// assert_eq!(*stack, ["ccc", "bb", "a"]);
+/// ```
+pub fn sort_with_aux_by<T>(stack: &mut Stack<T>, mut compare: impl FnMut(&T, &T) -> Ordering) {
+    // Keeping the auxiliary stack sorted with its greatest element on top.
+    let mut aux = Stack::new();
+    while let Some(value) = stack.pop() {
+        while let Some(greater) = aux
+            .vec
+            .pop_if(|top| compare(top, &value) == Ordering::Greater)
+        {
+            stack.push(greater);
+        }
+        aux.push(value);
+    }
+    *stack = aux;
+}
@@ -0,0 +1,697 @@
+use crate::Stack;
+use alloc::{
+    alloc::{Allocator, Global},
+    collections::TryReserveError,
+    vec::{self, Vec},
+};
+use core::{
+    borrow::{Borrow, BorrowMut},
+    iter,
+    ops::{Bound, Deref, DerefMut, RangeBounds},
+    slice,
+};
+#[cfg(feature = "serde")]
+use core::{fmt, marker::PhantomData};
+#[cfg(feature = "serde")]
+use serde::{
+    de::{SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// A growable and shrinkable stack array type, allocated in a custom allocator.
+///
+/// This mirrors [Stack], but keeps its elements in any [Allocator], so it can live in arenas,
+/// fixed pools, or instrumented allocators. It is kept separate from [Stack] since allocators
+/// are only available on nightly, and an allocator parameter on [Stack] would have to be
+/// threaded through every type and trait built on it. Stacks in the global allocator convert to
+/// and from [Stack] without reallocating.
+/// # Example
+/// ```
+/// #![feature(allocator_api)]
+/// use hay::AllocStack;
+/// use std::alloc::System;
+/// let mut stack = AllocStack::new_in(System);
+/// stack.push(1);
+/// stack.push(2);
+/// assert_eq!(stack.pop(), Some(2));
+/// assert_eq!(stack.pop(), Some(1));
+/// assert_eq!(stack.pop(), None);
+/// ```
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AllocStack<T, A: Allocator = Global> {
+    vec: Vec<T, A>,
+}
+impl<T, A: Allocator> AllocStack<T, A> {
+    /// Constructs a new, empty `AllocStack<T, A>` in the given allocator.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let stack: AllocStack<i32, _> = AllocStack::new_in(System);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new_in(alloc: A) -> Self {
+        Self {
+            vec: Vec::new_in(alloc),
+        }
+    }
+
+    /// Constructs a new, empty `AllocStack<T, A>` in the given allocator, with room for at least
+    /// `capacity` elements.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let stack: AllocStack<i32, _> = AllocStack::with_capacity_in(16, System);
+    /// assert!(stack.capacity() >= 16);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            vec: Vec::with_capacity_in(capacity, alloc),
+        }
+    }
+
+    /// Returns a reference to the allocator the stack lives in.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let stack: AllocStack<i32, _> = AllocStack::new_in(System);
+    /// let _: &System = stack.allocator();
+    /// ```
+    #[inline(always)]
+    pub fn allocator(&self) -> &A {
+        self.vec.allocator()
+    }
+
+    /// Returns the top element in the stack.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// stack.push(1);
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub const fn top(&self) -> Option<&T> {
+        self.vec.as_slice().last()
+    }
+
+    /// Returns a mutable reference to the top element in the stack.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// stack.push(1);
+    /// *stack.top_mut().unwrap() = 2;
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    #[inline(always)]
+    pub const fn top_mut(&mut self) -> Option<&mut T> {
+        self.vec.as_mut_slice().last_mut()
+    }
+
+    /// Splits the stack into slices of the top `depth` elements and the rest, without copying.
+    ///
+    /// See [Stack::split_at_depth].
+    /// # Panics
+    /// Panics if `depth` exceeds the length of the stack.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// stack.extend([1, 2, 3, 4]);
+    /// let (frame, rest) = stack.split_at_depth(3);
+    /// assert_eq!(frame, [2, 3, 4]);
+    /// assert_eq!(rest, [1]);
+    /// ```
+    #[inline(always)]
+    pub const fn split_at_depth(&self, depth: usize) -> (&[T], &[T]) {
+        let len = self.vec.len();
+        assert!(depth <= len, "depth exceeds the length of the stack");
+        let (rest, top) = self.vec.as_slice().split_at(len - depth);
+        (top, rest)
+    }
+
+    /// Splits the stack into mutable slices of the top `depth` elements and the rest, without
+    /// copying.
+    ///
+    /// See [Stack::split_at_depth_mut].
+    /// # Panics
+    /// Panics if `depth` exceeds the length of the stack.
+    #[inline(always)]
+    pub const fn split_at_depth_mut(&mut self, depth: usize) -> (&mut [T], &mut [T]) {
+        let len = self.vec.len();
+        assert!(depth <= len, "depth exceeds the length of the stack");
+        let (rest, top) = self.vec.as_mut_slice().split_at_mut(len - depth);
+        (top, rest)
+    }
+
+    /// Returns an iterator over chunks of `size` elements, starting from the top of the stack.
+    ///
+    /// See [Stack::chunks_from_top].
+    /// # Panics
+    /// Panics if `size` is zero.
+    #[inline(always)]
+    pub fn chunks_from_top(&self, size: usize) -> slice::RChunks<'_, T> {
+        self.vec.rchunks(size)
+    }
+
+    /// Returns an iterator over mutable chunks of `size` elements, starting from the top of the
+    /// stack.
+    ///
+    /// See [Stack::chunks_from_top_mut].
+    /// # Panics
+    /// Panics if `size` is zero.
+    #[inline(always)]
+    pub fn chunks_from_top_mut(&mut self, size: usize) -> slice::RChunksMut<'_, T> {
+        self.vec.rchunks_mut(size)
+    }
+
+    /// Returns an iterator over the elements from the top of the stack down, each paired with
+    /// its depth, where the top is at depth zero.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// stack.extend(['a', 'b']);
+    /// assert!(stack.enumerate_from_top().eq([(0, &'b'), (1, &'a')]));
+    /// ```
+    #[inline(always)]
+    pub fn enumerate_from_top(&self) -> iter::Enumerate<iter::Rev<slice::Iter<'_, T>>> {
+        self.vec.iter().rev().enumerate()
+    }
+
+    /// Appends an element to the top of the stack.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// stack.push(1);
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn push(&mut self, value: T) {
+        self.vec.push(value);
+    }
+
+    /// Appends an element to the top of the stack, and returns a mutable reference to it.
+    ///
+    /// See [Stack::push_mut].
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    #[inline(always)]
+    pub fn push_mut(&mut self, value: T) -> &mut T {
+        let index = self.vec.len();
+        self.vec.push(value);
+        &mut self.vec[index]
+    }
+
+    /// Appends an element to the top of the stack, or hands it back along with the error if the
+    /// stack had to grow but the allocator couldn't provide the memory.
+    ///
+    /// See [Stack::try_push].
+    /// # Errors
+    /// Returns the [TryReserveError] and `value` if the memory couldn't be allocated, or the new
+    /// capacity would exceed `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// assert!(stack.try_push(1).is_ok());
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn try_push(&mut self, value: T) -> Result<(), (TryReserveError, T)> {
+        if let Err(error) = self.vec.try_reserve(1) {
+            return Err((error, value));
+        }
+        self.vec.push(value);
+        Ok(())
+    }
+
+    /// Pushes every element of an iterator onto the stack, in order, and returns how many were
+    /// pushed, reserving room for as many as the iterator reports up front.
+    ///
+    /// See [Stack::push_iter].
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn push_iter<I>(&mut self, iter: I) -> usize
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let start = self.vec.len();
+        self.vec.reserve(iter.len());
+        self.vec.extend(iter);
+        self.vec.len() - start
+    }
+
+    /// Pushes the values of an iterator of results onto the stack, in order, until the first
+    /// error, and returns how many were pushed.
+    ///
+    /// See [Stack::try_extend].
+    /// # Errors
+    /// Returns the first error along with the number of values pushed before it.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// let parsed = "1 2 x 4".split(' ').map(str::parse::<u8>);
+    /// let (_, pushed) = stack.try_extend(parsed).unwrap_err();
+    /// assert_eq!(pushed, 2);
+    /// assert_eq!(*stack, [1, 2]);
+    /// ```
+    pub fn try_extend<E>(
+        &mut self,
+        iter: impl IntoIterator<Item = Result<T, E>>,
+    ) -> Result<usize, (E, usize)> {
+        let start = self.vec.len();
+        let mut error = None;
+        self.vec.extend(
+            iter.into_iter()
+                .map_while(|result| result.map_err(|first| error = Some(first)).ok()),
+        );
+        let pushed = self.vec.len() - start;
+        match error {
+            Some(error) => Err((error, pushed)),
+            None => Ok(pushed),
+        }
+    }
+
+    /// Pushes the values of an iterator of results onto the stack, in order, and returns how
+    /// many were pushed, or removes them all again on the first error.
+    ///
+    /// See [Stack::try_extend_or_rollback].
+    /// # Errors
+    /// Returns the first error, after truncating the stack back to the length it had.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn try_extend_or_rollback<E>(
+        &mut self,
+        iter: impl IntoIterator<Item = Result<T, E>>,
+    ) -> Result<usize, E> {
+        let start = self.vec.len();
+        self.try_extend(iter).map_err(|(error, _)| {
+            self.vec.truncate(start);
+            error
+        })
+    }
+
+    /// Pushes clones of the elements at the depths in `depths` onto the stack, in the order they
+    /// are in, without a temporary buffer.
+    ///
+    /// See [Stack::extend_from_within].
+    /// # Panics
+    /// Panics if the range of depths is decreasing or exceeds the length of the stack, or if the
+    /// new capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// stack.extend(['a', 'b', 'c']);
+    /// stack.extend_from_within(..2);
+    /// assert_eq!(*stack, ['a', 'b', 'c', 'b', 'c']);
+    /// ```
+    pub fn extend_from_within(&mut self, depths: impl RangeBounds<usize>)
+    where
+        T: Clone,
+    {
+        let len = self.vec.len();
+        let shallowest = match depths.start_bound() {
+            Bound::Included(&depth) => depth,
+            Bound::Excluded(&depth) => depth.checked_add(1).expect("depth overflowed"),
+            Bound::Unbounded => 0,
+        };
+        let deepest = match depths.end_bound() {
+            Bound::Included(&depth) => depth.checked_add(1).expect("depth overflowed"),
+            Bound::Excluded(&depth) => depth,
+            Bound::Unbounded => len,
+        };
+        assert!(
+            shallowest <= deepest && deepest <= len,
+            "depths {shallowest}..{deepest} are out of range for a stack of length {len}"
+        );
+        self.vec.extend_from_within(len - deepest..len - shallowest);
+    }
+
+    /// Replaces the contents of the stack with clones of the elements of `slice`, from the bottom
+    /// up, reusing its allocation.
+    ///
+    /// See [Stack::assign_from_slice].
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn assign_from_slice(&mut self, slice: &[T])
+    where
+        T: Clone,
+    {
+        self.vec.truncate(slice.len());
+        let (assigned, pushed) = slice.split_at(self.vec.len());
+        self.vec.clone_from_slice(assigned);
+        self.vec.extend_from_slice(pushed);
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+
+    /// Removes the element at `depth` and returns it, moving the top element into its place, or
+    /// returns [None] if the stack is no deeper than `depth`.
+    ///
+    /// See [Stack::pop_at_swap].
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// stack.extend(['a', 'b', 'c', 'd']);
+    /// assert_eq!(stack.pop_at_swap(2), Some('b'));
+    /// assert_eq!(*stack, ['a', 'd', 'c']);
+    /// ```
+    #[inline(always)]
+    pub fn pop_at_swap(&mut self, depth: usize) -> Option<T> {
+        let index = self.vec.len().checked_sub(depth)?.checked_sub(1)?;
+        Some(self.vec.swap_remove(index))
+    }
+
+    /// Clears the stack, popping all values.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the stack.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// stack.push(1);
+    /// stack.clear();
+    /// assert!(stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+
+    /// Returns the number of elements in the stack.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// stack.push(1);
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns the number of elements the stack can hold without reallocating.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let stack: AllocStack<i32, _> = AllocStack::with_capacity_in(4, System);
+    /// assert!(stack.capacity() >= 4);
+    /// ```
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns the number of bytes the stack has allocated in its allocator, including the spare
+    /// capacity.
+    ///
+    /// See [Stack::allocated_bytes].
+    #[inline(always)]
+    pub const fn allocated_bytes(&self) -> usize {
+        self.vec.capacity() * core::mem::size_of::<T>()
+    }
+
+    /// Extracts a vector containing the entire stack.
+    #[inline(always)]
+    pub const fn as_vec(&self) -> &Vec<T, A> {
+        &self.vec
+    }
+
+    /// Extracts a mutable vector containing the entire stack.
+    #[inline(always)]
+    pub fn as_mut_vec(&mut self) -> &mut Vec<T, A> {
+        &mut self.vec
+    }
+}
+impl<T> Default for AllocStack<T> {
+    /// Creates an empty `AllocStack<T>` in the global allocator.
+    #[inline(always)]
+    fn default() -> Self {
+        Self { vec: Vec::new() }
+    }
+}
+impl<T, A: Allocator> Deref for AllocStack<T, A> {
+    /// The resulting type when dereferencing `AllocStack<T, A>`.
+    type Target = [T];
+
+    /// Dereferences an `AllocStack<T, A>`.
+    fn deref(&self) -> &Self::Target {
+        &self.vec
+    }
+}
+impl<T, A: Allocator> DerefMut for AllocStack<T, A> {
+    /// Mutably dereferences an `AllocStack<T, A>`.
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.vec
+    }
+}
+impl<T, A: Allocator> AsRef<[T]> for AllocStack<T, A> {
+    /// Views the elements of an `AllocStack<T, A>`, from its bottom to its top.
+    #[inline(always)]
+    fn as_ref(&self) -> &[T] {
+        &self.vec
+    }
+}
+impl<T, A: Allocator> AsMut<[T]> for AllocStack<T, A> {
+    /// Mutably views the elements of an `AllocStack<T, A>`, from its bottom to its top.
+    #[inline(always)]
+    fn as_mut(&mut self) -> &mut [T] {
+        &mut self.vec
+    }
+}
+impl<T, A: Allocator> Borrow<[T]> for AllocStack<T, A> {
+    /// Borrows the elements of an `AllocStack<T, A>`, from its bottom to its top.
+    #[inline(always)]
+    fn borrow(&self) -> &[T] {
+        &self.vec
+    }
+}
+impl<T, A: Allocator> BorrowMut<[T]> for AllocStack<T, A> {
+    /// Mutably borrows the elements of an `AllocStack<T, A>`, from its bottom to its top.
+    #[inline(always)]
+    fn borrow_mut(&mut self) -> &mut [T] {
+        &mut self.vec
+    }
+}
+impl<T, A: Allocator> IntoIterator for AllocStack<T, A> {
+    /// The type of the elements being iterated over.
+    type Item = T;
+    /// The iterator consuming the stack.
+    type IntoIter = vec::IntoIter<T, A>;
+
+    /// Consumes the stack, returning an iterator over its elements from its bottom to its top.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// stack.extend([1, 2, 3]);
+    /// assert!(stack.into_iter().eq([1, 2, 3]));
+    /// ```
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
+impl<'a, T, A: Allocator> IntoIterator for &'a AllocStack<T, A> {
+    /// The type of the elements being iterated over.
+    type Item = &'a T;
+    /// The iterator borrowing the stack.
+    type IntoIter = slice::Iter<'a, T>;
+
+    /// Returns an iterator over the elements of the stack, from its bottom to its top.
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.iter()
+    }
+}
+impl<'a, T, A: Allocator> IntoIterator for &'a mut AllocStack<T, A> {
+    /// The type of the elements being iterated over.
+    type Item = &'a mut T;
+    /// The iterator mutably borrowing the stack.
+    type IntoIter = slice::IterMut<'a, T>;
+
+    /// Returns an iterator over the elements of the stack mutably, from its bottom to its top.
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.iter_mut()
+    }
+}
+impl<T> FromIterator<T> for AllocStack<T> {
+    /// Collects an iterator into a stack in the global allocator, pushing its elements in order.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// let mut stack: AllocStack<i32> = (1..=3).collect();
+    /// assert_eq!(stack.pop(), Some(3));
+    /// ```
+    #[inline(always)]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            vec: Vec::from_iter(iter),
+        }
+    }
+}
+impl<T, A: Allocator> Extend<T> for AllocStack<T, A> {
+    /// Pushes a collection of values onto a stack.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        // Moving all new values onto the stack.
+        self.vec.extend(iter);
+    }
+}
+impl<'a, T: 'a + Copy, A: Allocator> Extend<&'a T> for AllocStack<T, A> {
+    /// Pushes a collection of values onto a stack.
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        // Copying all new values onto the stack.
+        self.vec.extend(iter);
+    }
+}
+#[cfg(feature = "serde")]
+impl<T: Serialize, A: Allocator> Serialize for AllocStack<T, A> {
+    /// Serializes a stack as a sequence, from its bottom to its top, in the same format as a
+    /// [Stack].
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack = AllocStack::new_in(System);
+    /// stack.extend([1, 2]);
+    /// assert_eq!(serde_json::to_string(&stack).unwrap(), "[1,2]");
+    /// ```
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.vec)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>, A: Allocator + Default> Deserialize<'de> for AllocStack<T, A> {
+    /// Deserializes a stack from a sequence, from its bottom to its top, into the default
+    /// instance of its allocator.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use hay::AllocStack;
+    /// use std::alloc::System;
+    /// let mut stack: AllocStack<i32, System> = serde_json::from_str("[1,2]").unwrap();
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Visits a sequence, pushing its elements onto a stack in the allocator.
+        struct SeqVisitor<T, A>(PhantomData<(T, A)>);
+        impl<'de, T: Deserialize<'de>, A: Allocator + Default> Visitor<'de> for SeqVisitor<T, A> {
+            type Value = AllocStack<T, A>;
+
+            /// Describes the sequence expected.
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a sequence")
+            }
+
+            /// Pushes every element of the sequence, reserving room for a bounded number of
+            /// them up front so a lying length can't exhaust the allocator.
+            fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
+                let capacity = seq.size_hint().unwrap_or(0).min(4096);
+                let mut stack = AllocStack::with_capacity_in(capacity, A::default());
+                while let Some(value) = seq.next_element()? {
+                    stack.push(value);
+                }
+                Ok(stack)
+            }
+        }
+        deserializer.deserialize_seq(SeqVisitor(PhantomData))
+    }
+}
+impl<T> From<Stack<T>> for AllocStack<T> {
+    /// Converts a stack in the global allocator, without reallocating.
+    #[inline(always)]
+    fn from(stack: Stack<T>) -> Self {
+        Self { vec: stack.vec }
+    }
+}
+impl<T> From<AllocStack<T>> for Stack<T> {
+    /// Converts a stack in the global allocator, without reallocating.
+    #[inline(always)]
+    fn from(stack: AllocStack<T>) -> Self {
+        Self { vec: stack.vec }
+    }
+}
@@ -0,0 +1,219 @@
+use crate::Stack;
+use alloc::vec::Vec;
+use core::{
+    cell::RefCell,
+    fmt::{self, Debug},
+    mem,
+};
+
+/// The capacity of the first segment an [Arena] allocates, with each after it twice as large.
+const FIRST_SEGMENT: usize = 16;
+
+/// A position in an [Arena], returned by [Arena::marker], which [Arena::release_to] frees
+/// everything allocated after.
+///
+/// Later markers compare greater than earlier ones.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Marker {
+    segment: usize,
+    len: usize,
+}
+
+/// The segments of an [Arena].
+struct Segments<T> {
+    /// The segments holding values, where only the last may have room left.
+    used: Stack<Vec<T>>,
+    /// Empty segments, released to be reused.
+    free: Stack<Vec<T>>,
+}
+
+/// A typed bump arena, which frees its values in the reverse order they were allocated in.
+///
+/// Values are allocated by bumping the length of a segment, and new segments are only added
+/// when the last one is full, so allocation rarely touches the allocator and allocated values
+/// never move. Everything allocated since a [Marker] is dropped at once by
+/// [Arena::release_to], and the segments freed are kept to be reused, which suits allocations
+/// made per frame of a game or per call of an interpreter.
+/// # Example
+/// ```
+/// use hay::Arena;
+/// let mut arena = Arena::new();
+/// let frame = arena.marker();
+/// let a = arena.alloc(1);
+/// let b = arena.alloc(2);
+/// *a += *b;
+/// assert_eq!(*a, 3);
+/// assert_eq!(arena.len(), 2);
+/// arena.release_to(frame);
+/// assert!(arena.is_empty());
+/// ```
+pub struct Arena<T> {
+    segments: RefCell<Segments<T>>,
+}
+impl<T> Arena<T> {
+    /// Constructs a new, empty `Arena<T>`.
+    ///
+    /// The arena will not allocate until values are allocated in it.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            segments: RefCell::new(Segments {
+                used: Stack::new(),
+                free: Stack::new(),
+            }),
+        }
+    }
+
+    /// Moves a value into the arena, returning a mutable reference to it which lasts until the
+    /// arena is next released to a marker or cleared.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::Arena;
+    /// let arena = Arena::new();
+    /// let names: Vec<&mut String> = (0..100).map(|i| arena.alloc(i.to_string())).collect();
+    /// assert_eq!(names[42], "42");
+    /// ```
+    // Each call lends out a different value, so the mutable references never alias.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc(&self, value: T) -> &mut T {
+        let mut segments = self.segments.borrow_mut();
+        let Segments { used, free } = &mut *segments;
+        if used
+            .top()
+            .is_none_or(|segment| segment.len() == segment.capacity())
+        {
+            let segment = free.pop().unwrap_or_else(|| {
+                let capacity = used
+                    .top()
+                    .map_or(FIRST_SEGMENT, |segment| segment.capacity() * 2);
+                Vec::with_capacity(capacity)
+            });
+            used.push(segment);
+        }
+        let segment = used.top_mut().expect("a segment with room was just pushed");
+        segment.push(value);
+        // Going through a raw pointer to the buffer, since referencing the segment as a slice
+        // would invalidate the references already lent out.
+        // SAFETY: The segment was just pushed to, so its last slot holds a value, and a segment
+        // is never pushed to once full, so its values never move. They are only dropped through a
+        // mutable reference to the arena, which the borrow of the value prevents until it ends,
+        // and each value is only lent out once.
+        unsafe { &mut *segment.as_mut_ptr().add(segment.len() - 1) }
+    }
+
+    /// Returns a marker at the current position of the arena.
+    #[inline(always)]
+    pub fn marker(&self) -> Marker {
+        let used = &self.segments.borrow().used;
+        Marker {
+            segment: used.len().saturating_sub(1),
+            len: used.top().map_or(0, Vec::len),
+        }
+    }
+
+    /// Drops every value allocated since `marker` was taken, in the reverse order they were
+    /// allocated in, keeping their segments to be reused.
+    ///
+    /// Nothing is dropped if the arena was already released to before the marker.
+    /// # Panics
+    /// Panics if the new capacity of the free segments exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::Arena;
+    /// let mut arena = Arena::new();
+    /// arena.alloc('a');
+    /// let marker = arena.marker();
+    /// for _ in 0..1000 {
+    ///     arena.alloc('b');
+    /// }
+    /// arena.release_to(marker);
+    /// assert_eq!(arena.len(), 1);
+    /// ```
+    pub fn release_to(&mut self, marker: Marker) {
+        let Segments { used, free } = self.segments.get_mut();
+        while used.len() > marker.segment + 1 {
+            let mut segment = used
+                .pop()
+                .expect("the arena has more segments than the marker");
+            while segment.pop().is_some() {}
+            free.push(segment);
+        }
+        if used.len() == marker.segment + 1 {
+            if let Some(segment) = used.top_mut() {
+                while segment.len() > marker.len {
+                    segment.pop();
+                }
+            }
+        }
+    }
+
+    /// Drops every value in the arena, in the reverse order they were allocated in, keeping
+    /// their segments to be reused.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.release_to(Marker { segment: 0, len: 0 });
+    }
+
+    /// Returns the number of values in the arena.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.segments.borrow().used.iter().map(Vec::len).sum()
+    }
+
+    /// Returns the number of bytes the arena has allocated on the heap, counting every segment,
+    /// including the free ones kept to be reused, and the stacks keeping track of them.
+    /// # Example
+    /// ```
+    /// use hay::Arena;
+    /// let mut arena = Arena::<u64>::new();
+    /// let marker = arena.marker();
+    /// arena.alloc(1);
+    /// let allocated = arena.allocated_bytes();
+    /// assert!(allocated >= 16 * 8);
+    /// arena.release_to(marker);
+    /// assert!(arena.is_empty());
+    /// assert_eq!(arena.allocated_bytes(), allocated);
+    /// ```
+    pub fn allocated_bytes(&self) -> usize {
+        let Segments { used, free } = &*self.segments.borrow();
+        let segments: usize = used
+            .iter()
+            .chain(free.iter())
+            .map(|segment| segment.capacity() * mem::size_of::<T>())
+            .sum();
+        segments + used.allocated_bytes() + free.allocated_bytes()
+    }
+
+    /// Returns `true` if the arena contains no values.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.segments.borrow().used.iter().all(Vec::is_empty)
+    }
+}
+impl<T> Debug for Arena<T> {
+    /// Formats an `Arena<T>` without its values, which may be mutably borrowed.
+    /// # Example
+    /// ```
+    /// use hay::Arena;
+    /// let arena = Arena::new();
+    /// let a = arena.alloc(1);
+    /// assert_eq!(format!("{arena:?}"), "Arena { len: 1, segments: 1, .. }");
+    /// *a = 2;
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Arena")
+            .field("len", &self.len())
+            .field("segments", &self.segments.borrow().used.len())
+            .finish_non_exhaustive()
+    }
+}
+impl<T> Default for Arena<T> {
+    /// Constructs a new, empty `Arena<T>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
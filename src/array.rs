@@ -0,0 +1,488 @@
+use crate::Stack;
+#[cfg(feature = "arbitrary")]
+use arbitrary::{Arbitrary, Unstructured};
+use core::{
+    fmt::{self, Debug, Display},
+    hash::{Hash, Hasher},
+    mem::{ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
+
+/// The error returned when pushing onto a full fixed-capacity stack, handing the value back.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Full<T>(pub T);
+impl<T> Full<T> {
+    /// Returns the value which couldn't be pushed.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let mut stack: ArrayStack<i32, 0> = ArrayStack::new();
+    /// assert_eq!(stack.try_push(1).unwrap_err().into_inner(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+impl<T> Display for Full<T> {
+    /// Formats the error without the value.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("stack is full")
+    }
+}
+impl<T: Debug> core::error::Error for Full<T> {}
+
+/// A stack holding at most `N` elements inline, without allocating.
+///
+/// This suits `no_std` targets without a heap, where the capacity is known ahead of time. Every
+/// constructor is `const`, and an empty stack is all zeroes, so a `static` one is placed in
+/// `.bss` without any lazy initialization. See [SpinStack](crate::SpinStack) for sharing one.
+/// # Example
+/// ```
+/// use hay::{ArrayStack, Full};
+/// let mut stack: ArrayStack<i32, 2> = ArrayStack::new();
+/// stack.push(1);
+/// stack.push(2);
+/// assert_eq!(stack.try_push(3), Err(Full(3)));
+/// assert_eq!(stack.pop(), Some(2));
+/// assert_eq!(stack.pop(), Some(1));
+/// assert_eq!(stack.pop(), None);
+/// ```
+/// Stacks can also be built at compile time.
+/// ```
+/// use hay::ArrayStack;
+/// const SQUARES: ArrayStack<u32, 8> = {
+///     let mut stack = ArrayStack::new();
+///     let mut i = 0;
+///     while !stack.is_full() {
+///         stack.push(i * i);
+///         i += 1;
+///     }
+///     stack
+/// };
+/// assert_eq!(SQUARES.top(), Some(&49));
+/// ```
+pub struct ArrayStack<T, const N: usize> {
+    slots: [MaybeUninit<T>; N],
+    len: usize,
+}
+impl<T, const N: usize> ArrayStack<T, N> {
+    /// Constructs a new, empty `ArrayStack<T, N>`.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let stack: ArrayStack<i32, 16> = ArrayStack::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    /// Constructs a full `ArrayStack<T, N>` from an array, with its last element on top.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// static PRIMES: ArrayStack<u8, 3> = ArrayStack::from_array([2, 3, 5]);
+    /// assert_eq!(PRIMES.top(), Some(&5));
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn from_array(array: [T; N]) -> Self {
+        let array = ManuallyDrop::new(array);
+        Self {
+            // SAFETY: `MaybeUninit<T>` has the same layout as `T`, and the array is forgotten so
+            // its elements are only owned by the stack.
+            slots: unsafe { ptr::read((&raw const array).cast()) },
+            len: N,
+        }
+    }
+
+    /// Returns the maximum number of elements the stack holds.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let stack: ArrayStack<i32, 16> = ArrayStack::new();
+    /// assert_eq!(stack.capacity(), 16);
+    /// ```
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns a reference to the top element in the stack.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let mut stack: ArrayStack<i32, 2> = ArrayStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub const fn top(&self) -> Option<&T> {
+        self.as_slice().last()
+    }
+
+    /// Returns a mutable reference to the top element in the stack.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let mut stack: ArrayStack<i32, 2> = ArrayStack::new();
+    /// stack.push(1);
+    /// *stack.top_mut().unwrap() = 2;
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    #[inline(always)]
+    pub const fn top_mut(&mut self) -> Option<&mut T> {
+        self.as_mut_slice().last_mut()
+    }
+
+    /// Appends an element to the top of the stack.
+    /// # Panics
+    /// Panics if the stack is full.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let mut stack: ArrayStack<i32, 2> = ArrayStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub const fn push(&mut self, value: T) {
+        assert!(!self.is_full(), "pushed onto a full `ArrayStack`");
+        self.slots[self.len].write(value);
+        self.len += 1;
+    }
+
+    /// Appends an element to the top of the stack, or hands it back if the stack is full.
+    /// # Errors
+    /// Returns [Full] with the value if the stack is full.
+    /// # Example
+    /// ```
+    /// use hay::{ArrayStack, Full};
+    /// let mut stack: ArrayStack<i32, 1> = ArrayStack::new();
+    /// assert_eq!(stack.try_push(1), Ok(()));
+    /// assert_eq!(stack.try_push(2), Err(Full(2)));
+    /// ```
+    #[inline(always)]
+    pub const fn try_push(&mut self, value: T) -> Result<(), Full<T>> {
+        if self.len >= N {
+            return Err(Full(value));
+        }
+        self.slots[self.len].write(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let mut stack: ArrayStack<i32, 2> = ArrayStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    #[inline(always)]
+    pub const fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: The slot was below the length, so it holds a value, and it is now above it so
+        // it won't be read again.
+        Some(unsafe { self.slots[self.len].assume_init_read() })
+    }
+
+    /// Clears the stack, popping all values.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let mut stack: ArrayStack<i32, 2> = ArrayStack::new();
+    /// stack.push(1);
+    /// stack.clear();
+    /// assert!(stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        let elements: *mut [T] = self.as_mut_slice();
+        // Forgetting the elements first, so a panicking destructor can't drop them twice.
+        self.len = 0;
+        // SAFETY: The elements were below the length, so they hold values, and they are now
+        // above it so they won't be read again.
+        unsafe { ptr::drop_in_place(elements) };
+    }
+
+    /// Returns the number of elements in the stack.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let mut stack: ArrayStack<i32, 2> = ArrayStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let mut stack: ArrayStack<i32, 2> = ArrayStack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the next push will fail.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let mut stack: ArrayStack<i32, 1> = ArrayStack::new();
+    /// assert!(!stack.is_full());
+    /// stack.push(1);
+    /// assert!(stack.is_full());
+    /// ```
+    #[inline(always)]
+    pub const fn is_full(&self) -> bool {
+        self.len >= N
+    }
+
+    /// Extracts a slice of the elements, from the bottom of the stack up.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let mut stack: ArrayStack<i32, 2> = ArrayStack::new();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// assert_eq!(stack.as_slice(), [1, 2]);
+    /// ```
+    #[inline(always)]
+    pub const fn as_slice(&self) -> &[T] {
+        // SAFETY: Every slot below the length holds a value.
+        unsafe { slice::from_raw_parts(self.slots.as_ptr().cast(), self.len) }
+    }
+
+    /// Extracts a mutable slice of the elements, from the bottom of the stack up.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let mut stack: ArrayStack<i32, 2> = ArrayStack::new();
+    /// stack.push(1);
+    /// stack.as_mut_slice()[0] = 2;
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    #[inline(always)]
+    pub const fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: Every slot below the length holds a value.
+        unsafe { slice::from_raw_parts_mut(self.slots.as_mut_ptr().cast(), self.len) }
+    }
+}
+impl<T, const N: usize> Default for ArrayStack<T, N> {
+    /// Constructs a new, empty `ArrayStack<T, N>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, const N: usize> Drop for ArrayStack<T, N> {
+    /// Drops every element left on the stack.
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+impl<T: Clone, const N: usize> Clone for ArrayStack<T, N> {
+    /// Clones every element into a new stack.
+    fn clone(&self) -> Self {
+        let mut stack = Self::new();
+        stack.extend(self.iter().cloned());
+        stack
+    }
+}
+impl<T: Debug, const N: usize> Debug for ArrayStack<T, N> {
+    /// Formats the elements, from the bottom of the stack up.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+#[cfg(feature = "defmt")]
+impl<T: defmt::Format, const N: usize> defmt::Format for ArrayStack<T, N> {
+    /// Formats a stack as its length followed by its elements, from its bottom to its top.
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=[?]}", self.as_slice());
+    }
+}
+impl<T: Hash, const N: usize> Hash for ArrayStack<T, N> {
+    /// Hashes the elements, from the bottom of the stack up.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+impl<T: PartialEq, const N: usize> PartialEq for ArrayStack<T, N> {
+    /// Compares the elements, from the bottom of the stack up.
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+impl<T: Eq, const N: usize> Eq for ArrayStack<T, N> {}
+impl<T: PartialOrd, const N: usize> PartialOrd for ArrayStack<T, N> {
+    /// Compares the elements lexicographically, from the bottom of the stack up.
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+impl<T: Ord, const N: usize> Ord for ArrayStack<T, N> {
+    /// Compares the elements lexicographically, from the bottom of the stack up.
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+impl<T, const N: usize> Deref for ArrayStack<T, N> {
+    /// The resulting type when dereferencing `ArrayStack<T, N>`.
+    type Target = [T];
+
+    /// Dereferences an `ArrayStack<T, N>`.
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+impl<T, const N: usize> DerefMut for ArrayStack<T, N> {
+    /// Mutably dereferences an `ArrayStack<T, N>`.
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
+    }
+}
+impl<T, const N: usize> Extend<T> for ArrayStack<T, N> {
+    /// Pushes a collection of values onto a stack.
+    /// # Panics
+    /// Panics if the stack fills up.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+impl<'a, T: 'a + Copy, const N: usize> Extend<&'a T> for ArrayStack<T, N> {
+    /// Pushes a collection of values onto a stack.
+    /// # Panics
+    /// Panics if the stack fills up.
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+#[cfg(feature = "arbitrary")]
+impl<'a, T: Arbitrary<'a>, const N: usize> Arbitrary<'a> for ArrayStack<T, N> {
+    /// Generates an arbitrary stack, filled from its bottom to its top until it is full.
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut stack = Self::new();
+        for value in u.arbitrary_iter()? {
+            if stack.try_push(value?).is_err() {
+                break;
+            }
+        }
+        Ok(stack)
+    }
+
+    /// Returns the number of bytes an arbitrary stack takes.
+    #[inline(always)]
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+impl<T, const N: usize> From<ArrayStack<T, N>> for Stack<T> {
+    /// Moves the elements of a fixed-capacity stack onto the heap, keeping their order.
+    /// # Example
+    /// ```
+    /// use hay::{ArrayStack, Stack};
+    /// let mut array: ArrayStack<i32, 2> = ArrayStack::new();
+    /// array.push(1);
+    /// array.push(2);
+    /// let mut stack = Stack::from(array);
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    fn from(stack: ArrayStack<T, N>) -> Self {
+        let stack = ManuallyDrop::new(stack);
+        let mut vec = alloc::vec::Vec::with_capacity(stack.len);
+        // SAFETY: The elements are moved out in a single copy, and the stack they came from is
+        // never dropped.
+        unsafe {
+            ptr::copy_nonoverlapping(stack.slots.as_ptr().cast(), vec.as_mut_ptr(), stack.len);
+            vec.set_len(stack.len);
+        }
+        Self { vec }
+    }
+}
+#[cfg(feature = "heapless")]
+impl<T, const N: usize> From<heapless::Vec<T, N>> for ArrayStack<T, N> {
+    /// Converts a `heapless::Vec` into a stack with its last element on top, moving the elements
+    /// in a single copy.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let vec: heapless::Vec<i32, 2> = heapless::Vec::from_slice(&[1, 2]).unwrap();
+    /// let mut stack = ArrayStack::from(vec);
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    fn from(mut vec: heapless::Vec<T, N>) -> Self {
+        let mut stack = Self::new();
+        // SAFETY: The elements are moved out in a single copy, and the vector forgets them.
+        unsafe {
+            ptr::copy_nonoverlapping(vec.as_ptr(), stack.slots.as_mut_ptr().cast(), vec.len());
+            stack.len = vec.len();
+            vec.set_len(0);
+        }
+        stack
+    }
+}
+#[cfg(feature = "heapless")]
+impl<T, const N: usize> From<ArrayStack<T, N>> for heapless::Vec<T, N> {
+    /// Converts a stack into a `heapless::Vec` with its top last, moving the elements in a single
+    /// copy.
+    /// # Example
+    /// ```
+    /// use hay::ArrayStack;
+    /// let mut stack: ArrayStack<i32, 2> = ArrayStack::new();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// let vec = heapless::Vec::from(stack);
+    /// assert_eq!(vec, [1, 2]);
+    /// ```
+    fn from(stack: ArrayStack<T, N>) -> Self {
+        let stack = ManuallyDrop::new(stack);
+        let mut vec = Self::new();
+        // SAFETY: The elements are moved out in a single copy, and the stack they came from is
+        // never dropped.
+        unsafe {
+            ptr::copy_nonoverlapping(stack.slots.as_ptr().cast(), vec.as_mut_ptr(), stack.len);
+            vec.set_len(stack.len);
+        }
+        vec
+    }
+}
+#[cfg(feature = "heapless")]
+impl<T, const N: usize> From<heapless::Vec<T, N>> for Stack<T> {
+    /// Moves the elements of a `heapless::Vec` onto the heap, with its last element on top.
+    ///
+    /// This never fails, so `Stack::try_from` is available too.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let vec: heapless::Vec<i32, 2> = heapless::Vec::from_slice(&[1, 2]).unwrap();
+    /// let mut stack = Stack::try_from(vec).unwrap();
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    #[inline(always)]
+    fn from(vec: heapless::Vec<T, N>) -> Self {
+        ArrayStack::from(vec).into()
+    }
+}
@@ -0,0 +1,186 @@
+//! Builds syntax trees from expressions in postfix notation, keeping the partial trees on a
+//! [Stack].
+//!
+//! Operands become leaves, and each operator takes as many of the trees on top of the stack as
+//! its arity to become their parent. The shape of the tree is up to the [NodeBuilder], so any
+//! syntax tree type can be built, such as from the output of
+//! [infix::to_postfix](crate::infix::to_postfix).
+//! # Example
+//! ```
+//! use hay::{
+//!     ast::{self, NodeBuilder},
+//!     eval::{Arithmetic, Token},
+//! };
+//! use std::vec::Drain;
+//! #[derive(Debug, PartialEq)]
+//! enum Expr {
+//!     Number(i32),
+//!     Apply(Arithmetic, Vec<Expr>),
+//! }
+//! struct Builder;
+//! impl NodeBuilder<i32, Arithmetic> for Builder {
+//!     type Node = Expr;
+//!
+//!     fn arity(&mut self, operator: &Arithmetic) -> usize {
+//!         match operator {
+//!             Arithmetic::Neg => 1,
+//!             _ => 2,
+//!         }
+//!     }
+//!
+//!     fn leaf(&mut self, value: i32) -> Expr {
+//!         Expr::Number(value)
+//!     }
+//!
+//!     fn node(&mut self, operator: Arithmetic, children: Drain<'_, Expr>) -> Expr {
+//!         Expr::Apply(operator, children.collect())
+//!     }
+//! }
+//! // 1 - -2
+//! let tokens = [
+//!     Token::Operand(1),
+//!     Token::Operand(2),
+//!     Token::Operator(Arithmetic::Neg),
+//!     Token::Operator(Arithmetic::Sub),
+//! ];
+//! assert_eq!(
+//!     ast::build(&mut Builder, tokens),
+//!     Ok(Expr::Apply(
+//!         Arithmetic::Sub,
+//!         vec![
+//!             Expr::Number(1),
+//!             Expr::Apply(Arithmetic::Neg, vec![Expr::Number(2)]),
+//!         ],
+//!     )),
+//! );
+//! ```
+use crate::{eval::Token, Stack};
+use alloc::vec::Drain;
+use core::fmt::{self, Display};
+
+/// A builder of the nodes of a syntax tree, from the tokens of a postfix expression.
+pub trait NodeBuilder<V, O> {
+    /// The type of the nodes of the tree.
+    type Node;
+
+    /// Returns the number of children the node of an operator takes.
+    fn arity(&mut self, operator: &O) -> usize;
+
+    /// Builds a leaf from an operand.
+    fn leaf(&mut self, value: V) -> Self::Node;
+
+    /// Builds the node of an operator, from its children in the order they were built.
+    ///
+    /// There are always as many children as [NodeBuilder::arity] returned for the operator.
+    fn node(&mut self, operator: O, children: Drain<'_, Self::Node>) -> Self::Node;
+}
+
+/// The error returned when a postfix expression doesn't form a single tree.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The expression held no tokens.
+    Empty,
+    /// An operator had fewer trees on the stack than it takes children.
+    Underflow {
+        /// The index of the operator's token.
+        position: usize,
+        /// The number of children the operator takes.
+        arity: usize,
+        /// The number of trees on the stack.
+        len: usize,
+    },
+    /// More than one tree was left on the stack once every token was built.
+    Leftover {
+        /// The number of trees left on the stack.
+        len: usize,
+    },
+}
+impl Display for Error {
+    /// Formats the error.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("expression is empty"),
+            Self::Underflow {
+                position,
+                arity,
+                len,
+            } => write!(
+                f,
+                "operator at {position} takes {arity} children, but only {len} trees are on the \
+                 stack"
+            ),
+            Self::Leftover { len } => write!(f, "expression left {len} trees on the stack"),
+        }
+    }
+}
+impl core::error::Error for Error {}
+
+/// Builds the syntax tree of a postfix expression, returning its root.
+/// # Errors
+/// Returns an [Error] if the expression is empty, if an operator has too few children, or if
+/// more than one tree is left.
+/// # Example
+/// ```
+/// use hay::{
+///     ast::{self, Error, NodeBuilder},
+///     eval::Token,
+/// };
+/// use std::vec::Drain;
+/// // Rendering the expression in infix notation instead of building a tree.
+/// struct Render;
+/// impl NodeBuilder<char, char> for Render {
+///     type Node = String;
+///
+///     fn arity(&mut self, _: &char) -> usize {
+///         2
+///     }
+///
+///     fn leaf(&mut self, value: char) -> String {
+///         value.to_string()
+///     }
+///
+///     fn node(&mut self, operator: char, mut children: Drain<'_, String>) -> String {
+///         let (left, right) = (children.next().unwrap(), children.next().unwrap());
+///         format!("({left} {operator} {right})")
+///     }
+/// }
+/// let tokens = "ab+c*".chars().map(|c| match c {
+///     '+' | '*' => Token::Operator(c),
+///     _ => Token::Operand(c),
+/// });
+/// assert_eq!(ast::build(&mut Render, tokens), Ok("((a + b) * c)".to_string()));
+/// assert_eq!(
+///     ast::build(&mut Render, [Token::Operand('a'), Token::Operator('+')]),
+///     Err(Error::Underflow { position: 1, arity: 2, len: 1 }),
+/// );
+/// ```
+pub fn build<V, O, B: NodeBuilder<V, O> + ?Sized>(
+    builder: &mut B,
+    tokens: impl IntoIterator<Item = Token<V, O>>,
+) -> Result<B::Node, Error> {
+    let mut stack = Stack::new();
+    for (position, token) in tokens.into_iter().enumerate() {
+        let node = match token {
+            Token::Operand(value) => builder.leaf(value),
+            Token::Operator(operator) => {
+                let arity = builder.arity(&operator);
+                let len = stack.len();
+                let Some(start) = len.checked_sub(arity) else {
+                    return Err(Error::Underflow {
+                        position,
+                        arity,
+                        len,
+                    });
+                };
+                builder.node(operator, stack.vec.drain(start..))
+            }
+        };
+        stack.push(node);
+    }
+    match stack.len() {
+        0 => Err(Error::Empty),
+        1 => Ok(stack.pop().unwrap()),
+        len => Err(Error::Leftover { len }),
+    }
+}
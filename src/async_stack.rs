@@ -0,0 +1,517 @@
+use crate::{
+    spin::{SpinGuard, SpinMutex},
+    Stack,
+};
+use alloc::collections::VecDeque;
+#[cfg(feature = "futures-core")]
+use alloc::sync::Arc;
+use core::{
+    fmt::{self, Debug},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+#[cfg(feature = "futures-core")]
+use futures_core::Stream;
+
+/// Tasks waiting on an [AsyncStack], oldest first, each with the id it registered under.
+struct Waiters {
+    list: VecDeque<(usize, Waker)>,
+    next_id: usize,
+}
+impl Waiters {
+    /// Constructs a new, empty `Waiters`.
+    #[inline(always)]
+    const fn new() -> Self {
+        Self {
+            list: VecDeque::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Registers the waker of a pending task, updating it if the task is already registered.
+    fn register(&mut self, id: &mut Option<usize>, waker: &Waker) {
+        if let Some(id) = *id {
+            if let Some((_, registered)) = self.list.iter_mut().find(|(other, _)| *other == id) {
+                if !registered.will_wake(waker) {
+                    registered.clone_from(waker);
+                }
+                return;
+            }
+        }
+        let new = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.list.push_back((new, waker.clone()));
+        *id = Some(new);
+    }
+
+    /// Unregisters a task, returning `true` if it was still waiting rather than already woken.
+    fn unregister(&mut self, id: usize) -> bool {
+        match self.list.iter().position(|&(other, _)| other == id) {
+            Some(index) => {
+                self.list.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Wakers taken out of an [AsyncStack], to be woken once its lock is released.
+struct Wakeups {
+    pop: Option<Waker>,
+    watchers: VecDeque<(usize, Waker)>,
+}
+impl Wakeups {
+    /// Wakes every task taken out of the stack.
+    fn wake(self) {
+        if let Some(waker) = self.pop {
+            waker.wake();
+        }
+        for (_, waker) in self.watchers {
+            waker.wake();
+        }
+    }
+}
+
+/// The state of an [AsyncStack], kept behind its lock.
+struct State<T> {
+    stack: Stack<T>,
+    pops: Waiters,
+    /// Watches waiting for the length of the stack to change.
+    watchers: Waiters,
+    closed: bool,
+}
+impl<T> State<T> {
+    /// Takes the tasks to wake now that the length of the stack changed, including one waiting pop
+    /// if `pushed` is `true`.
+    #[inline(always)]
+    fn changed(&mut self, pushed: bool) -> Wakeups {
+        Wakeups {
+            pop: pushed
+                .then(|| self.pops.list.pop_front())
+                .flatten()
+                .map(|(_, waker)| waker),
+            watchers: core::mem::take(&mut self.watchers.list),
+        }
+    }
+}
+
+/// A stack whose pops can be awaited until an element is pushed, without depending on any
+/// particular async runtime.
+///
+/// Waiting pops are woken one at a time as elements are pushed, and all of them resolve to
+/// [None] once the stack is [closed](AsyncStack::close) and empty.
+/// # Example
+/// ```
+/// use hay::AsyncStack;
+/// let stack = AsyncStack::new();
+/// stack.push(1);
+/// stack.push(2);
+/// futures::executor::block_on(async {
+///     assert_eq!(stack.pop().await, Some(2));
+///     assert_eq!(stack.pop().await, Some(1));
+///     stack.close();
+///     assert_eq!(stack.pop().await, None);
+/// });
+/// ```
+pub struct AsyncStack<T> {
+    state: SpinMutex<State<T>>,
+}
+impl<T> AsyncStack<T> {
+    /// Constructs a new, empty `AsyncStack<T>`.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::AsyncStack;
+    /// let stack: AsyncStack<i32> = AsyncStack::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            state: SpinMutex::new(State {
+                stack: Stack::new(),
+                pops: Waiters::new(),
+                watchers: Waiters::new(),
+                closed: false,
+            }),
+        }
+    }
+
+    /// Locks the state of the stack.
+    #[inline(always)]
+    fn lock(&self) -> SpinGuard<'_, State<T>> {
+        self.state.lock()
+    }
+
+    /// Appends an element to the top of the stack, waking up a pop waiting for it.
+    ///
+    /// Elements can still be pushed after the stack is closed, and are popped as usual.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::AsyncStack;
+    /// let stack = AsyncStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.try_pop(), Some(1));
+    /// ```
+    pub fn push(&self, value: T) {
+        let mut state = self.lock();
+        state.stack.push(value);
+        let wakeups = state.changed(true);
+        // Waking outside the lock, since the waker may run arbitrary code.
+        drop(state);
+        wakeups.wake();
+    }
+
+    /// Returns a future which removes the element at the top of the stack once there is one.
+    ///
+    /// The future resolves to [None] if the stack is closed and empty.
+    /// # Example
+    /// ```
+    /// use hay::AsyncStack;
+    /// let stack = AsyncStack::new();
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| stack.push(1));
+    ///     assert_eq!(futures::executor::block_on(stack.pop()), Some(1));
+    /// });
+    /// ```
+    #[inline(always)]
+    pub fn pop(&self) -> Pop<'_, T> {
+        Pop {
+            stack: self,
+            waiter: None,
+        }
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    ///
+    /// This never waits for an element.
+    /// # Example
+    /// ```
+    /// use hay::AsyncStack;
+    /// let stack = AsyncStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.try_pop(), Some(1));
+    /// assert_eq!(stack.try_pop(), None);
+    /// ```
+    pub fn try_pop(&self) -> Option<T> {
+        let mut state = self.lock();
+        let value = state.stack.pop()?;
+        let wakeups = state.changed(false);
+        drop(state);
+        wakeups.wake();
+        Some(value)
+    }
+
+    /// Polls for the element at the top of the stack on behalf of a waiting pop.
+    fn poll_pop(&self, cx: &mut Context<'_>, waiter: &mut Option<usize>) -> Poll<Option<T>> {
+        let mut state = self.lock();
+        let value = state.stack.pop();
+        if value.is_none() && !state.closed {
+            state.pops.register(waiter, cx.waker());
+            return Poll::Pending;
+        }
+        if let Some(id) = waiter.take() {
+            state.pops.unregister(id);
+        }
+        let wakeups = state.changed(false);
+        drop(state);
+        wakeups.wake();
+        Poll::Ready(value)
+    }
+
+    /// Unregisters a pop which stopped waiting, passing its wake-up on to another pop if it was
+    /// already woken.
+    fn cancel_pop(&self, waiter: Option<usize>) {
+        let Some(id) = waiter else {
+            return;
+        };
+        let mut state = self.lock();
+        if state.pops.unregister(id) || state.stack.is_empty() {
+            return;
+        }
+        let waiter = state.pops.list.pop_front();
+        drop(state);
+        if let Some((_, waker)) = waiter {
+            waker.wake();
+        }
+    }
+
+    /// Returns a future which resolves once the stack contains at least one element.
+    ///
+    /// Unlike [AsyncStack::pop], the element is left on the stack.
+    /// # Example
+    /// ```
+    /// use hay::AsyncStack;
+    /// let stack = AsyncStack::new();
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| stack.push(1));
+    ///     futures::executor::block_on(stack.notified());
+    ///     assert_eq!(stack.try_pop(), Some(1));
+    /// });
+    /// ```
+    #[inline(always)]
+    pub fn notified(&self) -> Watch<'_, T, fn(usize) -> bool> {
+        self.watch(|len| len != 0)
+    }
+
+    /// Returns a future which resolves to the length of the stack once `predicate` returns
+    /// `true` for it.
+    ///
+    /// The predicate is checked again every time the length changes, which makes it easy to
+    /// react to the depth crossing a threshold in either direction.
+    /// # Example
+    /// ```
+    /// use hay::AsyncStack;
+    /// let stack = AsyncStack::new();
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| {
+    ///         for i in 0..4 {
+    ///             stack.push(i);
+    ///         }
+    ///     });
+    ///     assert_eq!(futures::executor::block_on(stack.watch(|len| len >= 4)), 4);
+    /// });
+    /// ```
+    #[inline(always)]
+    pub fn watch<F: FnMut(usize) -> bool>(&self, predicate: F) -> Watch<'_, T, F> {
+        Watch {
+            stack: self,
+            predicate,
+            waiter: None,
+        }
+    }
+
+    /// Closes the stack, so pops resolve to [None] instead of waiting once it is empty.
+    /// # Example
+    /// ```
+    /// use hay::AsyncStack;
+    /// let stack = AsyncStack::new();
+    /// stack.push(1);
+    /// stack.close();
+    /// futures::executor::block_on(async {
+    ///     assert_eq!(stack.pop().await, Some(1));
+    ///     assert_eq!(stack.pop().await, None);
+    /// });
+    /// ```
+    pub fn close(&self) {
+        let mut state = self.lock();
+        state.closed = true;
+        let pops = core::mem::take(&mut state.pops.list);
+        drop(state);
+        for (_, waker) in pops {
+            waker.wake();
+        }
+    }
+
+    /// Returns `true` if the stack has been closed.
+    /// # Example
+    /// ```
+    /// use hay::AsyncStack;
+    /// let stack: AsyncStack<i32> = AsyncStack::new();
+    /// assert!(!stack.is_closed());
+    /// stack.close();
+    /// assert!(stack.is_closed());
+    /// ```
+    #[inline(always)]
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    /// Converts a shared stack into a [Stream] of its popped elements, which completes once the
+    /// stack is closed and empty.
+    /// # Example
+    /// ```
+    /// use futures::StreamExt;
+    /// use hay::AsyncStack;
+    /// use std::sync::Arc;
+    /// let stack = Arc::new(AsyncStack::new());
+    /// stack.push(1);
+    /// stack.push(2);
+    /// stack.close();
+    /// let popped: Vec<_> = futures::executor::block_on(stack.into_stream().collect());
+    /// assert_eq!(popped, [2, 1]);
+    /// ```
+    #[cfg(feature = "futures-core")]
+    #[inline(always)]
+    pub fn into_stream(self: Arc<Self>) -> PopStream<T> {
+        PopStream {
+            stack: self,
+            waiter: None,
+        }
+    }
+
+    /// Returns the number of elements on the stack at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::AsyncStack;
+    /// let stack = AsyncStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.lock().stack.len()
+    }
+
+    /// Returns `true` if the stack contained no elements at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::AsyncStack;
+    /// let stack = AsyncStack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.lock().stack.is_empty()
+    }
+}
+impl<T> Default for AsyncStack<T> {
+    /// Constructs a new, empty `AsyncStack<T>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Debug for AsyncStack<T> {
+    /// Formats an `AsyncStack<T>` without its elements, which may change at any moment.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("AsyncStack")
+            .field("len", &state.stack.len())
+            .field("pops", &state.pops.list.len())
+            .field("watchers", &state.watchers.list.len())
+            .field("closed", &state.closed)
+            .finish_non_exhaustive()
+    }
+}
+impl<T> From<Stack<T>> for AsyncStack<T> {
+    /// Wraps a stack so its pops can be awaited.
+    #[inline(always)]
+    fn from(stack: Stack<T>) -> Self {
+        let this = Self::new();
+        this.state.lock().stack = stack;
+        this
+    }
+}
+impl<T> Extend<T> for AsyncStack<T> {
+    /// Pushes a collection of values onto a stack.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        // No pop can be waiting while the stack is exclusively borrowed.
+        self.state.get_mut().stack.extend(iter);
+    }
+}
+
+/// The future returned by [AsyncStack::pop].
+#[must_use = "futures do nothing unless polled"]
+pub struct Pop<'a, T> {
+    stack: &'a AsyncStack<T>,
+    /// The id this pop is registered under while it waits.
+    waiter: Option<usize>,
+}
+impl<T> Future for Pop<'_, T> {
+    type Output = Option<T>;
+
+    /// Attempts to pop an element, registering to be woken by a push if there is none.
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.stack.poll_pop(cx, &mut this.waiter)
+    }
+}
+impl<T> Debug for Pop<'_, T> {
+    /// Formats the stack the future pops from.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Pop").field(self.stack).finish()
+    }
+}
+impl<T> Drop for Pop<'_, T> {
+    /// Unregisters a waiting pop.
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.stack.cancel_pop(self.waiter);
+    }
+}
+
+/// The future returned by [AsyncStack::watch] and [AsyncStack::notified].
+#[must_use = "futures do nothing unless polled"]
+pub struct Watch<'a, T, F> {
+    stack: &'a AsyncStack<T>,
+    predicate: F,
+    /// The id this watch is registered under while it waits.
+    waiter: Option<usize>,
+}
+impl<T, F: FnMut(usize) -> bool + Unpin> Future for Watch<'_, T, F> {
+    type Output = usize;
+
+    /// Checks the length of the stack, registering to be woken when it changes if the predicate
+    /// doesn't match.
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut state = this.stack.lock();
+        let len = state.stack.len();
+        if (this.predicate)(len) {
+            if let Some(id) = this.waiter.take() {
+                state.watchers.unregister(id);
+            }
+            return Poll::Ready(len);
+        }
+        state.watchers.register(&mut this.waiter, cx.waker());
+        Poll::Pending
+    }
+}
+impl<T, F> Debug for Watch<'_, T, F> {
+    /// Formats the stack the future watches.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Watch")
+            .field(self.stack)
+            .finish_non_exhaustive()
+    }
+}
+impl<T, F> Drop for Watch<'_, T, F> {
+    /// Unregisters a waiting watch.
+    fn drop(&mut self) {
+        if let Some(id) = self.waiter {
+            self.stack.lock().watchers.unregister(id);
+        }
+    }
+}
+
+/// The stream returned by [AsyncStack::into_stream].
+#[cfg(feature = "futures-core")]
+#[must_use = "streams do nothing unless polled"]
+pub struct PopStream<T> {
+    stack: Arc<AsyncStack<T>>,
+    /// The id the stream is registered under while it waits.
+    waiter: Option<usize>,
+}
+#[cfg(feature = "futures-core")]
+impl<T> Stream for PopStream<T> {
+    type Item = T;
+
+    /// Attempts to pop an element, registering to be woken by a push if there is none.
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        this.stack.poll_pop(cx, &mut this.waiter)
+    }
+}
+#[cfg(feature = "futures-core")]
+impl<T> Debug for PopStream<T> {
+    /// Formats the stack the stream pops from.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PopStream").field(&self.stack).finish()
+    }
+}
+#[cfg(feature = "futures-core")]
+impl<T> Drop for PopStream<T> {
+    /// Unregisters a waiting stream.
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.stack.cancel_pop(self.waiter);
+    }
+}
@@ -0,0 +1,322 @@
+use crate::{
+    sync::{const_fn, AtomicUsize, Ordering},
+    Full,
+};
+#[cfg(feature = "arbitrary")]
+use arbitrary::{Arbitrary, Unstructured};
+use core::{
+    cell::{Cell, UnsafeCell},
+    fmt::{self, Debug},
+    marker::PhantomData,
+    mem::{ManuallyDrop, MaybeUninit},
+    ptr,
+};
+
+/// A fixed-capacity stack shared between one producer and one consumer, using only atomic
+/// updates of its length.
+///
+/// No locks are taken and nothing is allocated, so the producer can live in an interrupt
+/// handler while the consumer runs in the main loop. Both ends are reached through the handles
+/// returned by [AtomicArrayStack::split].
+/// # Example
+/// ```
+/// use hay::AtomicArrayStack;
+/// let mut stack: AtomicArrayStack<u8, 4> = AtomicArrayStack::new();
+/// let (mut producer, mut consumer) = stack.split();
+/// producer.push(1).unwrap();
+/// producer.push(2).unwrap();
+/// assert_eq!(consumer.pop(), Some(2));
+/// assert_eq!(consumer.pop(), Some(1));
+/// assert_eq!(consumer.pop(), None);
+/// ```
+pub struct AtomicArrayStack<T, const N: usize> {
+    slots: [UnsafeCell<MaybeUninit<T>>; N],
+    len: AtomicUsize,
+}
+// SAFETY: Values are only accessed through the producer and consumer, which each move them
+// between threads, and the slots they touch never overlap.
+unsafe impl<T: Send, const N: usize> Sync for AtomicArrayStack<T, N> {}
+impl<T, const N: usize> AtomicArrayStack<T, N> {
+    const_fn! {
+        /// Constructs a new, empty `AtomicArrayStack<T, N>`.
+        /// # Example
+        /// ```
+        /// use hay::AtomicArrayStack;
+        /// static mut STACK: AtomicArrayStack<u32, 16> = AtomicArrayStack::new();
+        /// ```
+        #[must_use]
+        #[inline(always)]
+        pub fn new() -> Self {
+            Self {
+                slots: [const { UnsafeCell::new(MaybeUninit::uninit()) }; N],
+                len: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    /// Splits the stack into its producer and consumer ends.
+    /// # Example
+    /// ```
+    /// use hay::AtomicArrayStack;
+    /// let mut stack: AtomicArrayStack<u8, 4> = AtomicArrayStack::new();
+    /// let (mut producer, mut consumer) = stack.split();
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(move || producer.push(1).unwrap());
+    /// });
+    /// assert_eq!(consumer.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn split(&mut self) -> (Producer<'_, T, N>, Consumer<'_, T, N>) {
+        (
+            Producer { stack: self },
+            Consumer {
+                stack: self,
+                not_sync: PhantomData,
+            },
+        )
+    }
+
+    /// Returns the maximum number of elements the stack holds.
+    /// # Example
+    /// ```
+    /// use hay::AtomicArrayStack;
+    /// let stack: AtomicArrayStack<u8, 4> = AtomicArrayStack::new();
+    /// assert_eq!(stack.capacity(), 4);
+    /// ```
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of elements on the stack at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::AtomicArrayStack;
+    /// let mut stack: AtomicArrayStack<u8, 4> = AtomicArrayStack::new();
+    /// stack.split().0.push(1).unwrap();
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the stack contained no elements at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::AtomicArrayStack;
+    /// let stack: AtomicArrayStack<u8, 4> = AtomicArrayStack::new();
+    /// assert!(stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the stack was full at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::AtomicArrayStack;
+    /// let mut stack: AtomicArrayStack<u8, 1> = AtomicArrayStack::new();
+    /// stack.split().0.push(1).unwrap();
+    /// assert!(stack.is_full());
+    /// ```
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.len() >= N
+    }
+}
+impl<T, const N: usize> Default for AtomicArrayStack<T, N> {
+    /// Constructs a new, empty `AtomicArrayStack<T, N>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, const N: usize> Debug for AtomicArrayStack<T, N> {
+    /// Formats an `AtomicArrayStack<T, N>` without its elements, which may change at any moment.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AtomicArrayStack")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish_non_exhaustive()
+    }
+}
+#[cfg(feature = "defmt")]
+impl<T, const N: usize> defmt::Format for AtomicArrayStack<T, N> {
+    /// Formats an `AtomicArrayStack<T, N>` without its elements, which may change at any moment.
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "AtomicArrayStack {{ len: {=usize}, capacity: {=usize}, .. }}",
+            self.len(),
+            N,
+        );
+    }
+}
+impl<T, const N: usize> Drop for AtomicArrayStack<T, N> {
+    /// Drops every element left on the stack.
+    fn drop(&mut self) {
+        let len = self.len.load(Ordering::Relaxed);
+        for slot in &mut self.slots[..len] {
+            // SAFETY: Every slot below the length holds a value.
+            unsafe { slot.get_mut().assume_init_drop() };
+        }
+    }
+}
+#[cfg(feature = "arbitrary")]
+impl<'a, T: Arbitrary<'a>, const N: usize> Arbitrary<'a> for AtomicArrayStack<T, N> {
+    /// Generates an arbitrary stack, filled from its bottom to its top until it is full.
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut stack = Self::new();
+        let (mut producer, _) = stack.split();
+        for value in u.arbitrary_iter()? {
+            if producer.push(value?).is_err() {
+                break;
+            }
+        }
+        Ok(stack)
+    }
+
+    /// Returns the number of bytes an arbitrary stack takes.
+    #[inline(always)]
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+/// The producing end of an [AtomicArrayStack], which pushes elements.
+pub struct Producer<'a, T, const N: usize> {
+    stack: &'a AtomicArrayStack<T, N>,
+}
+impl<T, const N: usize> Producer<'_, T, N> {
+    /// Appends an element to the top of the stack, or hands it back if the stack is full.
+    /// # Example
+    /// ```
+    /// use hay::AtomicArrayStack;
+    /// let mut stack: AtomicArrayStack<u8, 1> = AtomicArrayStack::new();
+    /// let (mut producer, _) = stack.split();
+    /// assert_eq!(producer.push(1), Ok(()));
+    /// assert_eq!(producer.push(2), Err(hay::Full(2)));
+    /// ```
+    pub fn push(&mut self, value: T) -> Result<(), Full<T>> {
+        let value = ManuallyDrop::new(value);
+        let mut len = self.stack.len.load(Ordering::Acquire);
+        loop {
+            if len >= N {
+                return Err(Full(ManuallyDrop::into_inner(value)));
+            }
+            // SAFETY: The consumer only ever reads slots below the length it last saw, which is
+            // never above the length seen here, so this slot is free. If the consumer pops first,
+            // the copy is abandoned and written again lower down.
+            unsafe {
+                ptr::copy_nonoverlapping(&*value, (*self.stack.slots[len].get()).as_mut_ptr(), 1);
+            }
+            match self
+                .stack
+                .len
+                .compare_exchange(len, len + 1, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => return Ok(()),
+                Err(current) => len = current,
+            }
+        }
+    }
+
+    /// Returns `true` if the stack was full at the moment it was checked.
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.stack.is_full()
+    }
+}
+impl<T, const N: usize> Debug for Producer<'_, T, N> {
+    /// Formats the stack the producer pushes onto.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Producer").field(self.stack).finish()
+    }
+}
+
+/// The consuming end of an [AtomicArrayStack], which pops elements.
+///
+/// The consumer is only shared between threads if its elements are, since [Consumer::top] lends
+/// them out.
+/// ```compile_fail
+/// use hay::AtomicArrayStack;
+/// use std::cell::Cell;
+/// let mut stack: AtomicArrayStack<Cell<i32>, 4> = AtomicArrayStack::new();
+/// let (mut producer, consumer) = stack.split();
+/// producer.push(Cell::new(1)).unwrap();
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| consumer.top().unwrap().set(2));
+///     scope.spawn(|| consumer.top().unwrap().set(3));
+/// });
+/// ```
+pub struct Consumer<'a, T, const N: usize> {
+    stack: &'a AtomicArrayStack<T, N>,
+    /// Keeps the consumer from being shared between threads unless its elements can be.
+    not_sync: PhantomData<Cell<()>>,
+}
+// SAFETY: Only `Consumer::top` and `Consumer::is_empty` take a shared reference, and `top` only
+// lends out elements, which can be shared between threads.
+unsafe impl<T: Send + Sync, const N: usize> Sync for Consumer<'_, T, N> {}
+impl<T, const N: usize> Consumer<'_, T, N> {
+    /// Returns a reference to the top element in the stack at the moment it was checked.
+    ///
+    /// The element stays valid while it is borrowed, since only the consumer pops.
+    /// # Example
+    /// ```
+    /// use hay::AtomicArrayStack;
+    /// let mut stack: AtomicArrayStack<u8, 4> = AtomicArrayStack::new();
+    /// let (mut producer, consumer) = stack.split();
+    /// producer.push(1).unwrap();
+    /// assert_eq!(consumer.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn top(&self) -> Option<&T> {
+        let len = self.stack.len.load(Ordering::Acquire);
+        let slot = self.stack.slots[len.checked_sub(1)?].get();
+        // SAFETY: The slot holds a value, and the producer never writes below the length.
+        Some(unsafe { (*slot).assume_init_ref() })
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::AtomicArrayStack;
+    /// let mut stack: AtomicArrayStack<u8, 4> = AtomicArrayStack::new();
+    /// let (mut producer, mut consumer) = stack.split();
+    /// producer.push(1).unwrap();
+    /// assert_eq!(consumer.pop(), Some(1));
+    /// assert_eq!(consumer.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        let mut len = self.stack.len.load(Ordering::Acquire);
+        loop {
+            let index = len.checked_sub(1)?;
+            // SAFETY: The slot holds a value, and the producer never writes below the length.
+            // The copy is only kept if taking the slot succeeds.
+            let value = unsafe { ptr::read(self.stack.slots[index].get()) };
+            match self
+                .stack
+                .len
+                .compare_exchange(len, index, Ordering::AcqRel, Ordering::Acquire)
+            {
+                // SAFETY: The slot was taken, so this is now the only copy of its value.
+                Ok(_) => return Some(unsafe { value.assume_init() }),
+                Err(current) => len = current,
+            }
+        }
+    }
+
+    /// Returns `true` if the stack contained no elements at the moment it was checked.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+}
+impl<T, const N: usize> Debug for Consumer<'_, T, N> {
+    /// Formats the stack the consumer pops from.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Consumer").field(self.stack).finish()
+    }
+}
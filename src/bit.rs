@@ -0,0 +1,249 @@
+use crate::{invariant::check_invariants, Stack};
+#[cfg(feature = "serde")]
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The number of bits stored in each word of a `BitStack`.
+const WORD_BITS: usize = usize::BITS as usize;
+
+/// A stack of booleans packed into machine words.
+///
+/// Each flag takes up a single bit, rather than the byte a `Stack<bool>` would spend on it.
+/// # Example
+/// ```
+/// use hay::BitStack;
+/// let mut stack = BitStack::new();
+/// stack.push(true);
+/// stack.push(false);
+/// assert_eq!(stack.len(), 2);
+/// assert_eq!(stack.pop(), Some(false));
+/// assert_eq!(stack.pop(), Some(true));
+/// assert_eq!(stack.pop(), None);
+/// ```
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct BitStack {
+    words: Stack<usize>,
+    len: usize,
+}
+impl BitStack {
+    /// Constructs a new, empty `BitStack`.
+    ///
+    /// The stack will not allocate until bits are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::BitStack;
+    /// let mut stack = BitStack::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            words: Stack::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the top bit in the stack, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::BitStack;
+    /// let mut stack = BitStack::new();
+    /// stack.push(true);
+    /// assert_eq!(stack.top(), Some(true));
+    /// stack.pop();
+    /// assert_eq!(stack.top(), None);
+    /// ```
+    #[inline(always)]
+    pub fn top(&self) -> Option<bool> {
+        let index = self.len.checked_sub(1)?;
+        Some(self.words[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0)
+    }
+
+    /// Overwrites the top bit in the stack, returning its previous value, or [None] if the stack
+    /// is empty.
+    /// # Example
+    /// ```
+    /// use hay::BitStack;
+    /// let mut stack = BitStack::new();
+    /// stack.push(false);
+    /// assert_eq!(stack.set_top(true), Some(false));
+    /// assert_eq!(stack.top(), Some(true));
+    /// ```
+    pub fn set_top(&mut self, bit: bool) -> Option<bool> {
+        let index = self.len.checked_sub(1)?;
+        let word = &mut self.words[index / WORD_BITS];
+        let mask = 1 << (index % WORD_BITS);
+        let previous = *word & mask != 0;
+        if bit {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+        check_invariants!(self);
+        Some(previous)
+    }
+
+    /// Appends a bit to the top of the stack.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// use hay::BitStack;
+    /// let mut stack = BitStack::new();
+    /// stack.push(true);
+    /// assert_eq!(stack.pop(), Some(true));
+    /// ```
+    pub fn push(&mut self, bit: bool) {
+        let offset = self.len % WORD_BITS;
+        if offset == 0 {
+            // Every word is full, so a fresh one is needed.
+            self.words.push(0);
+        }
+        if bit {
+            // Bits above the length are always zero, so there is nothing to clear.
+            *self.words.top_mut().unwrap() |= 1 << offset;
+        }
+        self.len += 1;
+        check_invariants!(self);
+    }
+
+    /// Removes the bit at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::BitStack;
+    /// let mut stack = BitStack::new();
+    /// stack.push(true);
+    /// assert_eq!(stack.pop(), Some(true));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<bool> {
+        let bit = self.set_top(false)?;
+        self.len -= 1;
+        if self.len.is_multiple_of(WORD_BITS) {
+            // The top word no longer holds any bits.
+            self.words.pop();
+        }
+        check_invariants!(self);
+        Some(bit)
+    }
+
+    /// Clears the stack, popping all bits.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the stack.
+    /// # Example
+    /// ```
+    /// use hay::BitStack;
+    /// let mut stack = BitStack::new();
+    /// stack.push(true);
+    /// stack.clear();
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.len = 0;
+        check_invariants!(self);
+    }
+
+    /// Returns the number of bits on the stack.
+    /// # Example
+    /// ```
+    /// use hay::BitStack;
+    /// let mut stack = BitStack::new();
+    /// stack.push(true);
+    /// stack.push(false);
+    /// assert_eq!(stack.len(), 2);
+    /// ```
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the stack contains no bits.
+    /// # Example
+    /// ```
+    /// use hay::BitStack;
+    /// let mut stack = BitStack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push(false);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of set bits on the stack.
+    /// # Example
+    /// ```
+    /// use hay::BitStack;
+    /// let mut stack = BitStack::new();
+    /// stack.extend([true, false, true]);
+    /// assert_eq!(stack.count_ones(), 2);
+    /// ```
+    #[inline(always)]
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Panics if the words don't match the length, or if any bit above the length is set.
+    #[cfg(feature = "strict-debug")]
+    #[track_caller]
+    fn check_invariants(&self) {
+        assert_eq!(
+            self.words.len(),
+            self.len.div_ceil(WORD_BITS),
+            "`BitStack` holds {} words for {} bits",
+            self.words.len(),
+            self.len,
+        );
+        let offset = self.len % WORD_BITS;
+        if let (Some(&top), true) = (self.words.top(), offset != 0) {
+            assert_eq!(
+                top >> offset,
+                0,
+                "`BitStack` has bits set above its length of {}",
+                self.len,
+            );
+        }
+    }
+}
+impl Extend<bool> for BitStack {
+    /// Pushes a collection of bits onto a stack.
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+        for bit in iter {
+            self.push(bit);
+        }
+    }
+}
+impl<'a> Extend<&'a bool> for BitStack {
+    /// Pushes a collection of bits onto a stack.
+    fn extend<I: IntoIterator<Item = &'a bool>>(&mut self, iter: I) {
+        for &bit in iter {
+            self.push(bit);
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl Serialize for BitStack {
+    /// Serializes a stack as a sequence of booleans, from its bottom to its top.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            (0..self.len)
+                .map(|index| self.words[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0),
+        )
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for BitStack {
+    /// Deserializes a stack from a sequence of booleans, from its bottom to its top.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut stack = Self::new();
+        stack.extend(Vec::<bool>::deserialize(deserializer)?);
+        Ok(stack)
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{
+    sync::{const_fn, AtomicUsize, Ordering},
+    trace, ConcurrentStack, Full,
+};
+#[cfg(feature = "arbitrary")]
+use arbitrary::{Arbitrary, Unstructured};
+use core::fmt::{self, Debug};
+#[cfg(feature = "std")]
+use std::sync::{Condvar, Mutex, PoisonError};
+
+/// A lock-free stack holding at most a fixed number of elements, so producers are throttled
+/// rather than exhausting memory.
+///
+/// [BoundedStack::try_push] hands the value back when the stack is full, and with the `std`
+/// feature, [BoundedStack::push] blocks until another thread pops.
+/// # Example
+/// ```
+/// use hay::{BoundedStack, Full};
+/// let stack = BoundedStack::new(1);
+/// assert_eq!(stack.try_push(1), Ok(()));
+/// assert_eq!(stack.try_push(2), Err(Full(2)));
+/// assert_eq!(stack.pop(), Some(1));
+/// assert_eq!(stack.try_push(2), Ok(()));
+/// ```
+pub struct BoundedStack<T> {
+    stack: ConcurrentStack<T>,
+    /// The number of slots taken, counting pushes which are still in progress.
+    len: AtomicUsize,
+    capacity: usize,
+    /// Pushes blocked on a full stack.
+    #[cfg(feature = "std")]
+    waiting: AtomicUsize,
+    #[cfg(feature = "std")]
+    space: Mutex<()>,
+    #[cfg(feature = "std")]
+    popped: Condvar,
+}
+impl<T> BoundedStack<T> {
+    const_fn! {
+        /// Constructs a new, empty `BoundedStack<T>` which holds at most `capacity` elements.
+        /// # Example
+        /// ```
+        /// use hay::BoundedStack;
+        /// let stack: BoundedStack<i32> = BoundedStack::new(16);
+        /// ```
+        #[must_use]
+        #[inline(always)]
+        pub fn new(capacity: usize) -> Self {
+            Self {
+                stack: ConcurrentStack::new(),
+                len: AtomicUsize::new(0),
+                capacity,
+                #[cfg(feature = "std")]
+                waiting: AtomicUsize::new(0),
+                #[cfg(feature = "std")]
+                space: Mutex::new(()),
+                #[cfg(feature = "std")]
+                popped: Condvar::new(),
+            }
+        }
+    }
+
+    /// Returns the maximum number of elements the stack holds.
+    /// # Example
+    /// ```
+    /// use hay::BoundedStack;
+    /// let stack: BoundedStack<i32> = BoundedStack::new(16);
+    /// assert_eq!(stack.capacity(), 16);
+    /// ```
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Appends an element to the top of the stack, or hands it back if the stack is full.
+    /// # Example
+    /// ```
+    /// use hay::{BoundedStack, Full};
+    /// let stack = BoundedStack::new(1);
+    /// assert_eq!(stack.try_push(1), Ok(()));
+    /// assert_eq!(stack.try_push(2), Err(Full(2)));
+    /// ```
+    pub fn try_push(&self, value: T) -> Result<(), Full<T>> {
+        let mut len = self.len.load(Ordering::Relaxed);
+        loop {
+            if len >= self.capacity {
+                trace::overflowed("BoundedStack", self.capacity);
+                return Err(Full(value));
+            }
+            match self
+                .len
+                .compare_exchange_weak(len, len + 1, Ordering::Relaxed, Ordering::Relaxed)
+            {
+                Ok(_) => break,
+                Err(current) => len = current,
+            }
+        }
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Appends an element to the top of the stack, blocking the current thread until another
+    /// thread pops if the stack is full.
+    /// # Example
+    /// ```
+    /// use hay::BoundedStack;
+    /// let stack = BoundedStack::new(1);
+    /// stack.push(1);
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| stack.push(2));
+    ///     while stack.pop() != Some(2) {}
+    /// });
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn push(&self, mut value: T) {
+        loop {
+            match self.try_push(value) {
+                Ok(()) => return,
+                Err(Full(rejected)) => value = rejected,
+            }
+            let guard = self.space.lock().unwrap_or_else(PoisonError::into_inner);
+            self.waiting.fetch_add(1, Ordering::SeqCst);
+            // Checking again now that pops know to wake this thread up.
+            if self.len.load(Ordering::SeqCst) >= self.capacity {
+                drop(
+                    self.popped
+                        .wait(guard)
+                        .unwrap_or_else(PoisonError::into_inner),
+                );
+            }
+            self.waiting.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::BoundedStack;
+    /// let stack = BoundedStack::new(1);
+    /// stack.try_push(1).unwrap();
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    pub fn pop(&self) -> Option<T> {
+        let value = self.stack.pop()?;
+        self.len.fetch_sub(1, Ordering::SeqCst);
+        #[cfg(feature = "std")]
+        if self.waiting.load(Ordering::SeqCst) != 0 {
+            // Taking the lock makes sure a blocked push is actually waiting before waking it.
+            let _guard = self.space.lock().unwrap_or_else(PoisonError::into_inner);
+            self.popped.notify_one();
+        }
+        Some(value)
+    }
+
+    /// Returns the number of elements on the stack at the moment it was checked, counting pushes
+    /// which are still in progress.
+    /// # Example
+    /// ```
+    /// use hay::BoundedStack;
+    /// let stack = BoundedStack::new(2);
+    /// stack.try_push(1).unwrap();
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the stack contained no elements at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::BoundedStack;
+    /// let stack = BoundedStack::new(2);
+    /// assert!(stack.is_empty());
+    /// stack.try_push(1).unwrap();
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the stack was full at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::BoundedStack;
+    /// let stack = BoundedStack::new(1);
+    /// assert!(!stack.is_full());
+    /// stack.try_push(1).unwrap();
+    /// assert!(stack.is_full());
+    /// ```
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.capacity
+    }
+}
+impl<T> Debug for BoundedStack<T> {
+    /// Formats a `BoundedStack<T>` without its elements, which may change at any moment.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedStack")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity)
+            .finish_non_exhaustive()
+    }
+}
+#[cfg(feature = "defmt")]
+impl<T> defmt::Format for BoundedStack<T> {
+    /// Formats a `BoundedStack<T>` without its elements, which may change at any moment.
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "BoundedStack {{ len: {=usize}, capacity: {=usize}, .. }}",
+            self.len(),
+            self.capacity,
+        );
+    }
+}
+#[cfg(feature = "arbitrary")]
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for BoundedStack<T> {
+    /// Generates an arbitrary stack of an arbitrary capacity, filled from its bottom to its top
+    /// until it is full.
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let stack = Self::new(u.arbitrary()?);
+        for value in u.arbitrary_iter()? {
+            if stack.try_push(value?).is_err() {
+                break;
+            }
+        }
+        Ok(stack)
+    }
+
+    /// Returns the number of bytes an arbitrary stack takes.
+    #[inline(always)]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        arbitrary::size_hint::and(usize::size_hint(depth), (0, None))
+    }
+}
@@ -0,0 +1,225 @@
+use crate::{spin::SpinMutex, Stack};
+use alloc::vec::Vec;
+use core::{
+    mem,
+    ops::{Deref, DerefMut},
+};
+
+/// The number of size classes, one for each power of two a capacity can start at.
+const CLASSES: usize = usize::BITS as usize;
+
+/// A pool of stack buffers shared between threads, bucketed by capacity so stacks can draw a
+/// buffer big enough for them.
+///
+/// Stacks made with [Stack::new_from_pool] or [Stack::with_capacity_from_pool] give their
+/// buffer back to the pool when dropped. Each size class holds the buffers whose capacity starts
+/// at the same power of two, so finding a big enough buffer never has to search through the
+/// small ones. Unlike [StackPool](crate::StackPool), the pool can be shared between threads, such
+/// as in a `static`.
+/// # Example
+/// ```
+/// use hay::{BufferPool, Stack};
+/// static POOL: BufferPool<u64> = BufferPool::new();
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             let mut stack = Stack::with_capacity_from_pool(100, &POOL);
+///             stack.extend(0..100);
+///         });
+///     }
+/// });
+/// // Whichever buffers the threads allocated are ready to be reused.
+/// assert!(POOL.idle() >= 1);
+/// let stack = Stack::with_capacity_from_pool(100, &POOL);
+/// assert!(stack.capacity() >= 100);
+/// ```
+#[derive(Debug)]
+pub struct BufferPool<T> {
+    classes: [SpinMutex<Vec<Vec<T>>>; CLASSES],
+    limit: usize,
+}
+impl<T> BufferPool<T> {
+    /// Constructs a new, empty `BufferPool<T>` which keeps every buffer returned to it.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self::with_limit(usize::MAX)
+    }
+
+    /// Constructs a new, empty `BufferPool<T>` which keeps at most `limit` idle buffers in each
+    /// size class, dropping any returned beyond that.
+    /// # Example
+    /// ```
+    /// use hay::{BufferPool, Stack};
+    /// let pool = BufferPool::with_limit(1);
+    /// let a = Stack::<u8>::with_capacity_from_pool(8, &pool);
+    /// let b = Stack::with_capacity_from_pool(8, &pool);
+    /// drop((a, b));
+    /// assert_eq!(pool.idle(), 1);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn with_limit(limit: usize) -> Self {
+        Self {
+            classes: [const { SpinMutex::new(Vec::new()) }; CLASSES],
+            limit,
+        }
+    }
+
+    /// Returns the size class of the buffers with `capacity`, which must not be zero.
+    #[inline(always)]
+    const fn class(capacity: usize) -> usize {
+        capacity.ilog2() as usize
+    }
+
+    /// Takes an idle buffer with room for at least `capacity` elements, preferring the smallest.
+    fn take(&self, capacity: usize) -> Option<Vec<T>> {
+        // Every buffer in the class of the next power of two is big enough, while those in the
+        // class of `capacity` itself may not be.
+        let exact = Self::class(capacity.max(1));
+        let mut class = self.classes[exact].lock();
+        if let Some(index) = class
+            .iter()
+            .rposition(|buffer| buffer.capacity() >= capacity)
+        {
+            return Some(class.swap_remove(index));
+        }
+        drop(class);
+        self.classes[exact + 1..]
+            .iter()
+            .find_map(|class| class.lock().pop())
+    }
+
+    /// Clears a stack and adds its buffer to the pool, unless the buffer's size class already
+    /// holds its limit of idle buffers or the stack never allocated.
+    /// # Example
+    /// ```
+    /// use hay::{BufferPool, Stack};
+    /// let pool = BufferPool::new();
+    /// let mut stack = Stack::new();
+    /// stack.extend(0..16);
+    /// pool.put(stack);
+    /// assert_eq!(pool.idle(), 1);
+    /// ```
+    pub fn put(&self, mut stack: Stack<T>) {
+        // Clearing first, so dropping the elements can't observe the pool locked.
+        stack.clear();
+        let capacity = stack.vec.capacity();
+        if capacity == 0 || mem::size_of::<T>() == 0 {
+            return;
+        }
+        let mut class = self.classes[Self::class(capacity)].lock();
+        if class.len() < self.limit {
+            class.push(stack.vec);
+        }
+    }
+
+    /// Returns the number of idle buffers in the pool.
+    #[inline(always)]
+    pub fn idle(&self) -> usize {
+        self.classes.iter().map(|class| class.lock().len()).sum()
+    }
+
+    /// Drops every idle buffer, releasing their allocations.
+    #[inline(always)]
+    pub fn clear(&self) {
+        for class in &self.classes {
+            drop(mem::take(&mut *class.lock()));
+        }
+    }
+}
+impl<T> Default for BufferPool<T> {
+    /// Constructs a new, empty `BufferPool<T>` which keeps every buffer returned to it.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Stack<T> {
+    /// Constructs a new, empty stack using the smallest idle buffer in `pool`, which it gives
+    /// back when dropped.
+    ///
+    /// The stack will not allocate until elements are pushed onto it if the pool has no idle
+    /// buffers.
+    /// # Example
+    /// ```
+    /// use hay::{BufferPool, Stack};
+    /// let pool = BufferPool::new();
+    /// let mut stack = Stack::new_from_pool(&pool);
+    /// stack.push(1);
+    /// drop(stack);
+    /// let stack = Stack::new_from_pool(&pool);
+    /// assert!(stack.capacity() >= 1);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub fn new_from_pool(pool: &BufferPool<T>) -> BufferStack<'_, T> {
+        let vec = pool.take(0).unwrap_or_default();
+        BufferStack {
+            pool,
+            stack: Stack { vec },
+        }
+    }
+
+    /// Constructs a new, empty stack with room for at least `capacity` elements, using an idle
+    /// buffer in `pool` if it has one big enough, which it gives back when dropped.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    #[must_use]
+    #[inline(always)]
+    pub fn with_capacity_from_pool(capacity: usize, pool: &BufferPool<T>) -> BufferStack<'_, T> {
+        let vec = pool
+            .take(capacity)
+            .unwrap_or_else(|| Vec::with_capacity(capacity));
+        BufferStack {
+            pool,
+            stack: Stack { vec },
+        }
+    }
+}
+
+/// A stack whose buffer came from a [BufferPool], and goes back into it when dropped.
+#[derive(Debug)]
+pub struct BufferStack<'a, T> {
+    pool: &'a BufferPool<T>,
+    stack: Stack<T>,
+}
+impl<T> BufferStack<'_, T> {
+    /// Detaches the stack from its pool, so its buffer isn't returned when dropped.
+    /// # Example
+    /// ```
+    /// use hay::{BufferPool, BufferStack, Stack};
+    /// let pool = BufferPool::new();
+    /// let mut stack = Stack::new_from_pool(&pool);
+    /// stack.push(1);
+    /// let stack = BufferStack::into_inner(stack);
+    /// assert_eq!(pool.idle(), 0);
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn into_inner(mut this: Self) -> Stack<T> {
+        mem::replace(&mut this.stack, Stack::new())
+    }
+}
+impl<T> Deref for BufferStack<'_, T> {
+    type Target = Stack<T>;
+
+    /// Dereferences to the stack.
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.stack
+    }
+}
+impl<T> DerefMut for BufferStack<'_, T> {
+    /// Mutably dereferences to the stack.
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.stack
+    }
+}
+impl<T> Drop for BufferStack<'_, T> {
+    /// Gives the stack's buffer back to its pool, unless it was detached or never allocated.
+    fn drop(&mut self) {
+        self.pool.put(mem::replace(&mut self.stack, Stack::new()));
+    }
+}
@@ -0,0 +1,268 @@
+use bumpalo::{collections::Vec, Bump};
+use core::ops::{Deref, DerefMut};
+
+/// A growable and shrinkable stack array type, allocated in a [Bump] arena.
+///
+/// This mirrors [Stack](crate::Stack), but takes its memory from the arena instead of the global
+/// allocator, so stacks built and dropped every frame cause no allocator traffic, and all of their
+/// memory is freed at once when the arena is reset. Growing the stack leaves its old buffer in the
+/// arena until then.
+/// # Example
+/// ```
+/// use bumpalo::Bump;
+/// use hay::BumpStack;
+/// let mut bump = Bump::new();
+/// for frame in 0..3 {
+///     let mut stack = BumpStack::new_in(&bump);
+///     stack.push(frame);
+///     stack.push(frame + 1);
+///     assert_eq!(stack.pop(), Some(frame + 1));
+///     assert_eq!(stack.pop(), Some(frame));
+///     drop(stack);
+///     // Freeing everything the frame allocated.
+///     bump.reset();
+/// }
+/// ```
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BumpStack<'bump, T> {
+    vec: Vec<'bump, T>,
+}
+impl<'bump, T> BumpStack<'bump, T> {
+    /// Constructs a new, empty `BumpStack<'bump, T>` in the given arena.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// use bumpalo::Bump;
+    /// use hay::BumpStack;
+    /// let bump = Bump::new();
+    /// let stack: BumpStack<i32> = BumpStack::new_in(&bump);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub fn new_in(bump: &'bump Bump) -> Self {
+        Self {
+            vec: Vec::new_in(bump),
+        }
+    }
+
+    /// Constructs a new, empty `BumpStack<'bump, T>` in the given arena, with room for at least
+    /// `capacity` elements.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// use bumpalo::Bump;
+    /// use hay::BumpStack;
+    /// let bump = Bump::new();
+    /// let stack: BumpStack<i32> = BumpStack::with_capacity_in(16, &bump);
+    /// assert!(stack.capacity() >= 16);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub fn with_capacity_in(capacity: usize, bump: &'bump Bump) -> Self {
+        Self {
+            vec: Vec::with_capacity_in(capacity, bump),
+        }
+    }
+
+    /// Returns a reference to the arena the stack lives in.
+    /// # Example
+    /// ```
+    /// use bumpalo::Bump;
+    /// use hay::BumpStack;
+    /// let bump = Bump::new();
+    /// let stack: BumpStack<i32> = BumpStack::new_in(&bump);
+    /// assert!(std::ptr::eq(stack.bump(), &bump));
+    /// ```
+    #[inline(always)]
+    pub fn bump(&self) -> &'bump Bump {
+        self.vec.bump()
+    }
+
+    /// Returns the top element in the stack.
+    /// # Example
+    /// ```
+    /// use bumpalo::Bump;
+    /// use hay::BumpStack;
+    /// let bump = Bump::new();
+    /// let mut stack = BumpStack::new_in(&bump);
+    /// stack.push(1);
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn top(&self) -> Option<&T> {
+        self.vec.last()
+    }
+
+    /// Returns a mutable reference to the top element in the stack.
+    /// # Example
+    /// ```
+    /// use bumpalo::Bump;
+    /// use hay::BumpStack;
+    /// let bump = Bump::new();
+    /// let mut stack = BumpStack::new_in(&bump);
+    /// stack.push(1);
+    /// *stack.top_mut().unwrap() = 2;
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    #[inline(always)]
+    pub fn top_mut(&mut self) -> Option<&mut T> {
+        self.vec.last_mut()
+    }
+
+    /// Appends an element to the top of the stack.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// use bumpalo::Bump;
+    /// use hay::BumpStack;
+    /// let bump = Bump::new();
+    /// let mut stack = BumpStack::new_in(&bump);
+    /// stack.push(1);
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn push(&mut self, value: T) {
+        self.vec.push(value);
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use bumpalo::Bump;
+    /// use hay::BumpStack;
+    /// let bump = Bump::new();
+    /// let mut stack = BumpStack::new_in(&bump);
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+
+    /// Clears the stack, popping all values.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the stack.
+    /// # Example
+    /// ```
+    /// use bumpalo::Bump;
+    /// use hay::BumpStack;
+    /// let bump = Bump::new();
+    /// let mut stack = BumpStack::new_in(&bump);
+    /// stack.push(1);
+    /// stack.clear();
+    /// assert!(stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+
+    /// Returns the number of elements in the stack.
+    /// # Example
+    /// ```
+    /// use bumpalo::Bump;
+    /// use hay::BumpStack;
+    /// let bump = Bump::new();
+    /// let mut stack = BumpStack::new_in(&bump);
+    /// stack.push(1);
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    /// # Example
+    /// ```
+    /// use bumpalo::Bump;
+    /// use hay::BumpStack;
+    /// let bump = Bump::new();
+    /// let mut stack = BumpStack::new_in(&bump);
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns the number of elements the stack can hold without reallocating.
+    /// # Example
+    /// ```
+    /// use bumpalo::Bump;
+    /// use hay::BumpStack;
+    /// let bump = Bump::new();
+    /// let stack: BumpStack<i32> = BumpStack::with_capacity_in(4, &bump);
+    /// assert!(stack.capacity() >= 4);
+    /// ```
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Converts the stack into a slice that lives as long as the arena, from bottom to top.
+    ///
+    /// The elements will never be dropped, since the arena doesn't run destructors.
+    /// # Example
+    /// ```
+    /// use bumpalo::Bump;
+    /// use hay::BumpStack;
+    /// let bump = Bump::new();
+    /// let mut stack = BumpStack::new_in(&bump);
+    /// stack.extend([1, 2, 3]);
+    /// let slice: &mut [i32] = stack.into_bump_slice_mut();
+    /// assert_eq!(slice, [1, 2, 3]);
+    /// ```
+    #[inline(always)]
+    pub fn into_bump_slice_mut(self) -> &'bump mut [T] {
+        self.vec.into_bump_slice_mut()
+    }
+
+    /// Extracts a vector containing the entire stack.
+    #[inline(always)]
+    pub const fn as_vec(&self) -> &Vec<'bump, T> {
+        &self.vec
+    }
+
+    /// Extracts a mutable vector containing the entire stack.
+    #[inline(always)]
+    pub fn as_mut_vec(&mut self) -> &mut Vec<'bump, T> {
+        &mut self.vec
+    }
+}
+impl<T> Deref for BumpStack<'_, T> {
+    /// The resulting type when dereferencing `BumpStack<'bump, T>`.
+    type Target = [T];
+
+    /// Dereferences a `BumpStack<'bump, T>`.
+    fn deref(&self) -> &Self::Target {
+        &self.vec
+    }
+}
+impl<T> DerefMut for BumpStack<'_, T> {
+    /// Mutably dereferences a `BumpStack<'bump, T>`.
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.vec
+    }
+}
+impl<T> Extend<T> for BumpStack<'_, T> {
+    /// Pushes a collection of values onto a stack.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        // Moving all new values onto the stack.
+        self.vec.extend(iter);
+    }
+}
+impl<'a, T: 'a + Copy> Extend<&'a T> for BumpStack<'_, T> {
+    /// Pushes a collection of values onto a stack.
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        // Copying all new values onto the stack.
+        self.vec.extend(iter);
+    }
+}
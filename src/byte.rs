@@ -0,0 +1,235 @@
+use crate::Stack;
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::io::{self, Read, Write};
+
+/// A stack of bytes which can be written to and read from as an [io] stream.
+///
+/// Writing pushes byte runs onto the top of the stack, and reading pops them back off. Runs are
+/// always handed back in the order they were written, so a run written with [Write::write_all]
+/// comes back unchanged when the same number of bytes is popped.
+/// # Example
+/// ```
+/// use hay::ByteStack;
+/// use std::io::Write;
+/// let mut stack = ByteStack::new();
+/// stack.write_all(b"hello ").unwrap();
+/// write!(stack, "world").unwrap();
+/// assert_eq!(stack.pop_bytes(5), b"world");
+/// assert_eq!(stack.pop_bytes(6), b"hello ");
+/// ```
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ByteStack {
+    stack: Stack<u8>,
+}
+impl ByteStack {
+    /// Constructs a new, empty `ByteStack`.
+    ///
+    /// The stack will not allocate until bytes are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::ByteStack;
+    /// let mut stack = ByteStack::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            stack: Stack::new(),
+        }
+    }
+
+    /// Appends a byte to the top of the stack.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::ByteStack;
+    /// let mut stack = ByteStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn push(&mut self, byte: u8) {
+        self.stack.push(byte);
+    }
+
+    /// Appends a run of bytes to the top of the stack, so that the last byte of the run becomes
+    /// the top of the stack.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::ByteStack;
+    /// let mut stack = ByteStack::new();
+    /// stack.push_bytes(&[1, 2, 3]);
+    /// assert_eq!(stack.pop(), Some(3));
+    /// ```
+    #[inline(always)]
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        self.stack.extend(bytes);
+    }
+
+    /// Removes the byte at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::ByteStack;
+    /// let mut stack = ByteStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<u8> {
+        self.stack.pop()
+    }
+
+    /// Removes up to `count` bytes from the top of the stack and returns them in the order they
+    /// were pushed.
+    ///
+    /// If fewer than `count` bytes are on the stack, all of them are returned.
+    /// # Example
+    /// ```
+    /// use hay::ByteStack;
+    /// let mut stack = ByteStack::new();
+    /// stack.push_bytes(&[1, 2, 3]);
+    /// assert_eq!(stack.pop_bytes(2), [2, 3]);
+    /// assert_eq!(stack.pop_bytes(2), [1]);
+    /// ```
+    pub fn pop_bytes(&mut self, count: usize) -> Vec<u8> {
+        let at = self.stack.len().saturating_sub(count);
+        self.stack.as_mut_vec().split_off(at)
+    }
+
+    /// Copies the top bytes of the stack into `buf` without popping them, returning the number of
+    /// bytes copied.
+    ///
+    /// The bytes are copied in the order they were pushed, ending with the top of the stack. If
+    /// the stack holds fewer bytes than `buf`, only the first [ByteStack::len] bytes of `buf` are
+    /// written to.
+    /// # Example
+    /// ```
+    /// use hay::ByteStack;
+    /// let mut stack = ByteStack::new();
+    /// stack.push_bytes(&[1, 2, 3]);
+    /// let mut buf = [0; 2];
+    /// assert_eq!(stack.read_top(&mut buf), 2);
+    /// assert_eq!(buf, [2, 3]);
+    /// assert_eq!(stack.len(), 3);
+    /// ```
+    pub fn read_top(&self, buf: &mut [u8]) -> usize {
+        let count = buf.len().min(self.stack.len());
+        buf[..count].copy_from_slice(&self.stack[self.stack.len() - count..]);
+        count
+    }
+
+    /// Clears the stack, popping all bytes.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the stack.
+    /// # Example
+    /// ```
+    /// use hay::ByteStack;
+    /// let mut stack = ByteStack::new();
+    /// stack.push(1);
+    /// stack.clear();
+    /// assert!(stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+
+    /// Returns the number of bytes on the stack.
+    /// # Example
+    /// ```
+    /// use hay::ByteStack;
+    /// let mut stack = ByteStack::new();
+    /// stack.push_bytes(b"abc");
+    /// assert_eq!(stack.len(), 3);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack contains no bytes.
+    /// # Example
+    /// ```
+    /// use hay::ByteStack;
+    /// let mut stack = ByteStack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+}
+impl Deref for ByteStack {
+    /// The resulting type when dereferencing `ByteStack`.
+    type Target = [u8];
+
+    /// Dereferences a `ByteStack`.
+    fn deref(&self) -> &Self::Target {
+        &self.stack
+    }
+}
+impl DerefMut for ByteStack {
+    /// Mutably dereferences a `ByteStack`.
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.stack
+    }
+}
+impl Extend<u8> for ByteStack {
+    /// Pushes a collection of bytes onto a stack.
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        self.stack.extend(iter);
+    }
+}
+impl<'a> Extend<&'a u8> for ByteStack {
+    /// Pushes a collection of bytes onto a stack.
+    fn extend<I: IntoIterator<Item = &'a u8>>(&mut self, iter: I) {
+        self.stack.extend(iter);
+    }
+}
+impl Write for ByteStack {
+    /// Pushes all of `buf` onto the stack.
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push_bytes(buf);
+        Ok(buf.len())
+    }
+
+    /// Does nothing, as writes go straight onto the stack.
+    #[inline(always)]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+impl Read for ByteStack {
+    /// Pops up to `buf.len()` bytes off the top of the stack into `buf`, in the order they were
+    /// pushed.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.read_top(buf);
+        let len = self.stack.len() - count;
+        self.stack.as_mut_vec().truncate(len);
+        Ok(count)
+    }
+}
+#[cfg(feature = "serde")]
+impl Serialize for ByteStack {
+    /// Serializes a stack as a sequence of bytes, from its bottom to its top.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.stack.serialize(serializer)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ByteStack {
+    /// Deserializes a stack from a sequence of bytes, from its bottom to its top.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Stack::deserialize(deserializer).map(|stack| Self { stack })
+    }
+}
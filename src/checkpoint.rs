@@ -0,0 +1,235 @@
+use crate::Stack;
+use core::ops::Deref;
+
+/// A token for a point a [Checkpointed] stack can be rolled back to, returned by
+/// [Checkpointed::checkpoint].
+///
+/// The token is consumed by [Checkpointed::rollback] or [Checkpointed::commit], so each
+/// checkpoint is resolved exactly once.
+#[derive(Debug, Hash, PartialEq, Eq)]
+#[must_use = "a checkpoint should be rolled back to or committed"]
+pub struct Checkpoint {
+    level: usize,
+    depth: usize,
+}
+impl Checkpoint {
+    /// Returns the length the stack had when the checkpoint was taken, which rolling back to it
+    /// truncates the stack to.
+    #[inline(always)]
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the number of checkpoints which were already open when this one was taken.
+    #[inline(always)]
+    pub const fn level(&self) -> usize {
+        self.level
+    }
+}
+
+/// A stack which can take checkpoints, and later roll back to one or commit it, for backtracking
+/// solvers and speculative parsers.
+///
+/// Checkpoints nest, and must be resolved innermost first. The elements beneath the innermost
+/// checkpoint can't be popped or changed, so rolling back only ever has to truncate the stack.
+/// # Example
+/// ```
+/// use hay::Checkpointed;
+/// let mut stack = Checkpointed::new();
+/// stack.push('a');
+/// let outer = stack.checkpoint();
+/// stack.push('b');
+/// let inner = stack.checkpoint();
+/// stack.push('c');
+/// // The inner guess failed, but the outer one holds up.
+/// stack.rollback(inner);
+/// stack.commit(outer);
+/// assert_eq!(*stack, ['a', 'b']);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpointed<T> {
+    stack: Stack<T>,
+    marks: Stack<usize>,
+}
+impl<T> Checkpointed<T> {
+    /// Constructs a new, empty `Checkpointed<T>`.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            stack: Stack::new(),
+            marks: Stack::new(),
+        }
+    }
+
+    /// Takes a checkpoint at the current length of the stack, freezing the elements already in
+    /// it until the checkpoint is resolved.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    #[inline(always)]
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let checkpoint = Checkpoint {
+            level: self.marks.len(),
+            depth: self.stack.len(),
+        };
+        self.marks.push(checkpoint.depth);
+        checkpoint
+    }
+
+    /// Removes every element pushed since `checkpoint` was taken, and resolves it.
+    /// # Panics
+    /// Panics if `checkpoint` isn't the innermost open checkpoint of this stack.
+    /// # Example
+    /// ```
+    /// use hay::Checkpointed;
+    /// let mut stack = Checkpointed::new();
+    /// let checkpoint = stack.checkpoint();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// stack.rollback(checkpoint);
+    /// assert!(stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        self.resolve(&checkpoint);
+        self.stack.vec.truncate(checkpoint.depth);
+    }
+
+    /// Keeps every element pushed since `checkpoint` was taken, and resolves it.
+    /// # Panics
+    /// Panics if `checkpoint` isn't the innermost open checkpoint of this stack.
+    /// # Example
+    /// ```should_panic
+    /// use hay::Checkpointed;
+    /// let mut stack = Checkpointed::<i32>::new();
+    /// let outer = stack.checkpoint();
+    /// let _inner = stack.checkpoint();
+    /// // The inner checkpoint must be resolved first.
+    /// stack.commit(outer);
+    /// ```
+    #[inline(always)]
+    pub fn commit(&mut self, checkpoint: Checkpoint) {
+        self.resolve(&checkpoint);
+    }
+
+    /// Removes the innermost open checkpoint, checking that it is `checkpoint`.
+    fn resolve(&mut self, checkpoint: &Checkpoint) {
+        assert!(
+            checkpoint.level + 1 == self.marks.len() && self.marks.top() == Some(&checkpoint.depth),
+            "checkpoint is not the innermost open one"
+        );
+        self.marks.pop();
+    }
+
+    /// Returns the number of open checkpoints.
+    #[inline(always)]
+    pub const fn checkpoints(&self) -> usize {
+        self.marks.len()
+    }
+
+    /// Returns a reference to the top element in the stack.
+    #[inline(always)]
+    pub const fn top(&self) -> Option<&T> {
+        self.stack.top()
+    }
+
+    /// Returns a mutable reference to the top element in the stack, or [None] if it is empty or
+    /// frozen by the innermost checkpoint.
+    #[inline(always)]
+    pub fn top_mut(&mut self) -> Option<&mut T> {
+        if self.frozen() {
+            return None;
+        }
+        self.stack.top_mut()
+    }
+
+    /// Appends an element to the top of the stack.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    #[inline(always)]
+    pub fn push(&mut self, value: T) {
+        self.stack.push(value);
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty or
+    /// frozen by the innermost checkpoint.
+    /// # Example
+    /// ```
+    /// use hay::Checkpointed;
+    /// let mut stack = Checkpointed::new();
+    /// stack.push(1);
+    /// let checkpoint = stack.checkpoint();
+    /// assert_eq!(stack.pop(), None);
+    /// stack.commit(checkpoint);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.frozen() {
+            return None;
+        }
+        self.stack.pop()
+    }
+
+    /// Returns `true` if the top element is beneath the innermost checkpoint.
+    #[inline(always)]
+    fn frozen(&self) -> bool {
+        self.marks.top() == Some(&self.stack.len())
+    }
+
+    /// Returns the number of elements in the stack.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Returns the number of bytes the stack has allocated on the heap, counting the elements
+    /// and the depths of the open checkpoints, including their spare capacity.
+    ///
+    /// Memory the elements own themselves, like the buffers of strings, isn't counted.
+    #[inline(always)]
+    pub const fn allocated_bytes(&self) -> usize {
+        self.stack.allocated_bytes() + self.marks.allocated_bytes()
+    }
+
+    /// Consumes the stack, discarding any open checkpoints, and returns its elements.
+    #[inline(always)]
+    pub fn into_inner(self) -> Stack<T> {
+        self.stack
+    }
+}
+impl<T> Default for Checkpointed<T> {
+    /// Constructs a new, empty `Checkpointed<T>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Deref for Checkpointed<T> {
+    /// The slice of elements in the stack.
+    type Target = [T];
+
+    /// Returns the elements in the stack, from bottom to top.
+    #[inline(always)]
+    fn deref(&self) -> &[T] {
+        &self.stack
+    }
+}
+impl<T> From<Stack<T>> for Checkpointed<T> {
+    /// Wraps a stack, with no checkpoints open.
+    #[inline(always)]
+    fn from(stack: Stack<T>) -> Self {
+        Self {
+            stack,
+            marks: Stack::new(),
+        }
+    }
+}
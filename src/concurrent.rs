@@ -0,0 +1,247 @@
+use crate::{
+    hazard::Domain,
+    sync::{const_fn, hint, AtomicPtr, Ordering},
+};
+use alloc::boxed::Box;
+use core::{
+    fmt::{self, Debug},
+    mem::ManuallyDrop,
+    ptr,
+};
+
+/// The number of slots in the elimination array of a [ConcurrentStack].
+const ELIMINATION_SLOTS: usize = 8;
+/// The number of times a push waits in the elimination array for a pop to take its node.
+const ELIMINATION_SPINS: usize = 64;
+
+/// A node in a [ConcurrentStack].
+struct Node<T> {
+    value: ManuallyDrop<T>,
+    next: AtomicPtr<Node<T>>,
+}
+
+/// A lock-free stack which can be pushed to and popped from by many threads at once.
+///
+/// This is a Treiber stack: elements live in a linked list of nodes, and the head of the list
+/// is swapped in and out with atomic compare-and-swap operations.
+///
+/// When an exchange fails because other threads got there first, the stack backs off into an
+/// elimination array, where a push and a pop can hand a value straight to each other without
+/// touching the head at all. Under heavy contention this keeps throughput from collapsing as
+/// threads are added.
+///
+/// Popped nodes are reclaimed with hazard pointers: a thread announces which node it is about to
+/// read, and popped nodes are only freed once no thread has announced them. This rules out both
+/// use-after-free and ABA problems, without leaking nodes until the stack is dropped.
+/// # Example
+/// ```
+/// use hay::ConcurrentStack;
+/// let stack = ConcurrentStack::new();
+/// std::thread::scope(|scope| {
+///     for i in 0..4 {
+///         let stack = &stack;
+///         scope.spawn(move || stack.push(i));
+///     }
+/// });
+/// let mut popped = Vec::new();
+/// while let Some(value) = stack.pop() {
+///     popped.push(value);
+/// }
+/// popped.sort();
+/// assert_eq!(popped, [0, 1, 2, 3]);
+/// ```
+pub struct ConcurrentStack<T> {
+    head: AtomicPtr<Node<T>>,
+    hazards: Domain<Node<T>>,
+    /// Nodes offered by pushes to pops, which either take them or leave them to be withdrawn.
+    elimination: [AtomicPtr<Node<T>>; ELIMINATION_SLOTS],
+}
+// SAFETY: Values are only ever accessed by the thread that pushed or popped them.
+unsafe impl<T: Send> Send for ConcurrentStack<T> {}
+// SAFETY: Values are only ever accessed by the thread that pushed or popped them.
+unsafe impl<T: Send> Sync for ConcurrentStack<T> {}
+impl<T> ConcurrentStack<T> {
+    const_fn! {
+        /// Constructs a new, empty `ConcurrentStack<T>`.
+        ///
+        /// The stack will not allocate until elements are pushed onto it.
+        /// # Example
+        /// ```
+        /// use hay::ConcurrentStack;
+        /// let stack: ConcurrentStack<i32> = ConcurrentStack::new();
+        /// ```
+        #[must_use]
+        #[inline(always)]
+        pub fn new() -> Self {
+            Self {
+                head: AtomicPtr::new(ptr::null_mut()),
+                hazards: Domain::new(),
+                #[cfg(not(loom))]
+                elimination: [const { AtomicPtr::new(ptr::null_mut()) }; ELIMINATION_SLOTS],
+                #[cfg(loom)]
+                elimination: core::array::from_fn(|_| AtomicPtr::new(ptr::null_mut())),
+            }
+        }
+    }
+
+    /// Returns the index of the elimination slot the calling thread should start at.
+    #[inline(always)]
+    fn elimination_start() -> usize {
+        // Threads run on separate native stacks, so hashing the address of a local spreads them
+        // across the slots.
+        let marker = 0u8;
+        let address = ptr::addr_of!(marker) as usize as u64;
+        (address.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 32) as usize % ELIMINATION_SLOTS
+    }
+
+    /// Appends an element to the top of the stack.
+    /// # Example
+    /// ```
+    /// use hay::ConcurrentStack;
+    /// let stack = ConcurrentStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    pub fn push(&self, value: T) {
+        let node = Box::into_raw(Box::new(Node {
+            value: ManuallyDrop::new(value),
+            next: AtomicPtr::new(ptr::null_mut()),
+        }));
+        loop {
+            let head = self.head.load(Ordering::Relaxed);
+            // SAFETY: The node isn't shared until the exchange below succeeds.
+            unsafe { (*node).next.store(head, Ordering::Relaxed) };
+            if self
+                .head
+                .compare_exchange(head, node, Ordering::Release, Ordering::Relaxed)
+                .is_ok()
+                || self.eliminate_push(node)
+            {
+                return;
+            }
+        }
+    }
+
+    /// Offers a node to a concurrent pop through the elimination array, returning `true` if it
+    /// was taken.
+    fn eliminate_push(&self, node: *mut Node<T>) -> bool {
+        let start = Self::elimination_start();
+        let Some(slot) = (0..ELIMINATION_SLOTS)
+            .map(|offset| &self.elimination[(start + offset) % ELIMINATION_SLOTS])
+            .find(|slot| {
+                slot.compare_exchange(ptr::null_mut(), node, Ordering::Release, Ordering::Relaxed)
+                    .is_ok()
+            })
+        else {
+            return false;
+        };
+        for _ in 0..ELIMINATION_SPINS {
+            if slot.load(Ordering::Relaxed) != node {
+                return true;
+            }
+            hint::spin_loop();
+        }
+        // Withdrawing the offer, unless a pop took it in the meantime.
+        slot.compare_exchange(node, ptr::null_mut(), Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::ConcurrentStack;
+    /// let stack = ConcurrentStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    pub fn pop(&self) -> Option<T> {
+        let guard = self.hazards.acquire();
+        loop {
+            let head = guard.protect(&self.head);
+            if head.is_null() {
+                return None;
+            }
+            // SAFETY: The hazard pointer keeps the node from being freed.
+            let next = unsafe { (*head).next.load(Ordering::Acquire) };
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::SeqCst, Ordering::Relaxed)
+                .is_ok()
+            {
+                guard.clear();
+                // SAFETY: Winning the exchange gives this thread sole ownership of the value.
+                let value = unsafe { ManuallyDrop::take(&mut (*head).value) };
+                // SAFETY: The node came from a box, was unlinked above, and is now empty.
+                unsafe { guard.retire(head) };
+                return Some(value);
+            }
+            if let Some(value) = self.eliminate_pop() {
+                return Some(value);
+            }
+        }
+    }
+
+    /// Takes a node offered by a concurrent push from the elimination array, if there is one.
+    fn eliminate_pop(&self) -> Option<T> {
+        let start = Self::elimination_start();
+        (0..ELIMINATION_SLOTS).find_map(|offset| {
+            let slot = &self.elimination[(start + offset) % ELIMINATION_SLOTS];
+            let node = slot.load(Ordering::Relaxed);
+            if node.is_null()
+                || slot
+                    .compare_exchange(node, ptr::null_mut(), Ordering::Acquire, Ordering::Relaxed)
+                    .is_err()
+            {
+                return None;
+            }
+            // SAFETY: Winning the exchange gives this thread sole ownership of the node, which
+            // never made it into the stack, so no other thread can be looking at it.
+            let mut owned = unsafe { Box::from_raw(node) };
+            // SAFETY: The value was never popped.
+            Some(unsafe { ManuallyDrop::take(&mut owned.value) })
+        })
+    }
+
+    /// Returns `true` if the stack contained no elements at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::ConcurrentStack;
+    /// let stack = ConcurrentStack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+}
+impl<T> Default for ConcurrentStack<T> {
+    /// Constructs a new, empty `ConcurrentStack<T>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Debug for ConcurrentStack<T> {
+    /// Formats a `ConcurrentStack<T>` without its elements, which may change at any moment.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConcurrentStack")
+            .field("is_empty", &self.is_empty())
+            .finish_non_exhaustive()
+    }
+}
+impl<T> Drop for ConcurrentStack<T> {
+    /// Drops every element left on the stack and frees every node.
+    fn drop(&mut self) {
+        let mut node = self.head.load(Ordering::Relaxed);
+        while !node.is_null() {
+            // SAFETY: The stack is exclusively borrowed, and every node in it is still live.
+            let mut owned = unsafe { Box::from_raw(node) };
+            node = owned.next.load(Ordering::Relaxed);
+            // SAFETY: The value hasn't been popped.
+            unsafe { ManuallyDrop::drop(&mut owned.value) };
+        }
+    }
+}
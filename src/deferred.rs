@@ -0,0 +1,274 @@
+use crate::Stack;
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Debug},
+    mem,
+    ops::Deref,
+};
+
+/// A stack which defers dropping the elements it clears or truncates, so that clearing a stack
+/// of heavy elements takes constant time.
+///
+/// Cleared elements are moved aside, buffer and all, and only dropped by
+/// [DeferredStack::drop_pending], a chunk at a time between latency-sensitive work, or on another
+/// thread with [DeferredStack::drop_pending_in_background]. Whatever is still pending is dropped
+/// along with the stack.
+/// # Example
+/// ```
+/// use hay::DeferredStack;
+/// let mut stack = DeferredStack::new();
+/// stack.extend((0..1000).map(Box::new));
+/// // Clearing hands the boxes off without freeing any of them.
+/// stack.clear();
+/// assert!(stack.is_empty());
+/// assert_eq!(stack.pending(), 1000);
+/// // Freeing them a few at a time, between frames.
+/// while stack.drop_pending(256) != 0 {}
+/// assert_eq!(stack.pending(), 0);
+/// ```
+pub struct DeferredStack<T> {
+    stack: Stack<T>,
+    pending: Stack<Vec<T>>,
+}
+impl<T> DeferredStack<T> {
+    /// Constructs a new, empty `DeferredStack<T>`.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            stack: Stack::new(),
+            pending: Stack::new(),
+        }
+    }
+
+    /// Returns a reference to the top element in the stack.
+    #[inline(always)]
+    pub const fn top(&self) -> Option<&T> {
+        self.stack.top()
+    }
+
+    /// Returns a mutable reference to the top element in the stack.
+    #[inline(always)]
+    pub const fn top_mut(&mut self) -> Option<&mut T> {
+        self.stack.top_mut()
+    }
+
+    /// Appends an element to the top of the stack.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    #[inline(always)]
+    pub fn push(&mut self, value: T) {
+        self.stack.push(value);
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        self.stack.pop()
+    }
+
+    /// Clears the stack in constant time, deferring the drop of its elements.
+    ///
+    /// The buffer goes along with the elements, so the stack allocates anew once it is pushed
+    /// onto again.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        if !self.stack.is_empty() {
+            self.pending.push(mem::take(&mut self.stack.vec));
+        }
+    }
+
+    /// Shortens the stack to `len` elements, deferring the drop of the rest, or does nothing if
+    /// it is already no longer than `len`.
+    ///
+    /// The removed elements are moved into a buffer of their own, which takes time linear in
+    /// their number, but none of them are dropped.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::DeferredStack;
+    /// let mut stack = DeferredStack::new();
+    /// stack.extend(["a", "b", "c"].map(String::from));
+    /// stack.truncate(1);
+    /// assert_eq!(*stack, ["a"]);
+    /// assert_eq!(stack.pending(), 2);
+    /// ```
+    #[inline(always)]
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.stack.len() {
+            self.pending.push(self.stack.vec.split_off(len));
+        }
+    }
+
+    /// Returns the number of elements waiting to be dropped.
+    #[inline(always)]
+    pub fn pending(&self) -> usize {
+        self.pending.iter().map(Vec::len).sum()
+    }
+
+    /// Drops up to `limit` of the elements waiting to be dropped, freeing their buffers once
+    /// they are empty, and returns how many were dropped.
+    /// # Example
+    /// ```
+    /// use hay::DeferredStack;
+    /// let mut stack = DeferredStack::new();
+    /// stack.extend((0..10).map(Box::new));
+    /// stack.truncate(5);
+    /// stack.clear();
+    /// assert_eq!(stack.drop_pending(8), 8);
+    /// assert_eq!(stack.drop_pending(8), 2);
+    /// assert_eq!(stack.drop_pending(8), 0);
+    /// ```
+    pub fn drop_pending(&mut self, limit: usize) -> usize {
+        let mut dropped = 0;
+        while let Some(batch) = self.pending.top_mut() {
+            let count = batch.len().min(limit - dropped);
+            batch.truncate(batch.len() - count);
+            dropped += count;
+            if !batch.is_empty() {
+                break;
+            }
+            self.pending.pop();
+        }
+        dropped
+    }
+
+    /// Moves every element waiting to be dropped onto a new thread, which drops them, and
+    /// returns its handle.
+    /// # Example
+    /// ```
+    /// use hay::DeferredStack;
+    /// let mut stack = DeferredStack::new();
+    /// stack.extend((0..1000).map(|i| vec![i; 16]));
+    /// stack.clear();
+    /// let dropping = stack.drop_pending_in_background();
+    /// assert_eq!(stack.pending(), 0);
+    /// dropping.join().unwrap();
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn drop_pending_in_background(&mut self) -> std::thread::JoinHandle<()>
+    where
+        T: Send + 'static,
+    {
+        let pending = mem::replace(&mut self.pending, Stack::new());
+        std::thread::spawn(move || drop(pending))
+    }
+
+    /// Returns the number of elements in the stack, not counting those waiting to be dropped.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Returns the number of bytes the stack has allocated on the heap, counting the buffers of
+    /// the elements waiting to be dropped and the stack keeping track of them, including their
+    /// spare capacity.
+    ///
+    /// Memory the elements own themselves, like the buffers of strings, isn't counted.
+    /// # Example
+    /// ```
+    /// use hay::DeferredStack;
+    /// let mut stack = DeferredStack::new();
+    /// stack.extend(0u64..1000);
+    /// let allocated = stack.allocated_bytes();
+    /// stack.truncate(10);
+    /// let truncated = stack.allocated_bytes();
+    /// assert!(truncated > allocated);
+    /// stack.drop_pending(usize::MAX);
+    /// assert!(stack.allocated_bytes() < truncated);
+    /// ```
+    pub fn allocated_bytes(&self) -> usize {
+        let pending: usize = self
+            .pending
+            .iter()
+            .map(|batch| batch.capacity() * mem::size_of::<T>())
+            .sum();
+        self.stack.allocated_bytes() + pending + self.pending.allocated_bytes()
+    }
+
+    /// Consumes the stack, dropping every element waiting to be dropped, and returns its
+    /// elements.
+    #[inline(always)]
+    pub fn into_inner(self) -> Stack<T> {
+        self.stack
+    }
+}
+impl<T: Clone> Clone for DeferredStack<T> {
+    /// Clones the elements in the stack, leaving the clone with nothing waiting to be dropped.
+    /// # Example
+    /// ```
+    /// use hay::DeferredStack;
+    /// let mut stack = DeferredStack::new();
+    /// stack.extend([1, 2, 3]);
+    /// stack.truncate(1);
+    /// let clone = stack.clone();
+    /// assert_eq!(clone.pending(), 0);
+    /// assert_eq!(clone, stack);
+    /// ```
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self::from(self.stack.clone())
+    }
+}
+impl<T: Debug> Debug for DeferredStack<T> {
+    /// Formats the elements in the stack, and how many are waiting to be dropped.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeferredStack")
+            .field("stack", &self.stack)
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+impl<T: PartialEq> PartialEq for DeferredStack<T> {
+    /// Compares the elements in the stacks, ignoring those waiting to be dropped.
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.stack == other.stack
+    }
+}
+impl<T: Eq> Eq for DeferredStack<T> {}
+impl<T> Default for DeferredStack<T> {
+    /// Constructs a new, empty `DeferredStack<T>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Deref for DeferredStack<T> {
+    /// The slice of elements in the stack.
+    type Target = [T];
+
+    /// Returns the elements in the stack, from bottom to top.
+    #[inline(always)]
+    fn deref(&self) -> &[T] {
+        &self.stack
+    }
+}
+impl<T> Extend<T> for DeferredStack<T> {
+    /// Pushes a collection of values onto a stack.
+    #[inline(always)]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.stack.extend(iter);
+    }
+}
+impl<T> From<Stack<T>> for DeferredStack<T> {
+    /// Wraps a stack, with nothing waiting to be dropped.
+    #[inline(always)]
+    fn from(stack: Stack<T>) -> Self {
+        Self {
+            stack,
+            pending: Stack::new(),
+        }
+    }
+}
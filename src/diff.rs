@@ -0,0 +1,203 @@
+use crate::{fnv, Stack};
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Display},
+    hash::Hash,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The changes turning one stack into another: the elements popped above their common base, then
+/// the elements pushed onto it.
+///
+/// Made by [Stack::diff] and applied by [Stack::apply]. Sending a patch instead of a whole stack
+/// keeps synchronizing two copies of a stack proportional to how much they differ.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Patch<T> {
+    base: usize,
+    base_hash: u64,
+    pops: usize,
+    pushes: Vec<T>,
+}
+impl<T> Patch<T> {
+    /// Returns the length of the base both stacks share, from the bottom up.
+    #[inline(always)]
+    pub const fn base(&self) -> usize {
+        self.base
+    }
+
+    /// Returns the number of elements popped off the old stack down to the base.
+    #[inline(always)]
+    pub const fn pops(&self) -> usize {
+        self.pops
+    }
+
+    /// Returns the elements pushed onto the base, from the bottom up.
+    #[inline(always)]
+    pub fn pushes(&self) -> &[T] {
+        &self.pushes
+    }
+
+    /// Returns `true` if the patch changes nothing.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.pops == 0 && self.pushes.is_empty()
+    }
+
+    /// Consumes the patch, returning the elements pushed onto the base.
+    #[inline(always)]
+    pub fn into_pushes(self) -> Vec<T> {
+        self.pushes
+    }
+}
+impl<T: Hash + PartialEq + Clone> Stack<T> {
+    /// Returns the [Patch] turning this stack into `other`, made of the longest base they share
+    /// from the bottom up, the number of elements to pop down to it, and the elements to push
+    /// onto it.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut old = Stack::new();
+    /// old.extend([1, 2, 3]);
+    /// let mut new = Stack::new();
+    /// new.extend([1, 2, 4, 5]);
+    /// let patch = old.diff(&new);
+    /// assert_eq!(patch.base(), 2);
+    /// assert_eq!(patch.pops(), 1);
+    /// assert_eq!(patch.pushes(), [4, 5]);
+    /// assert!(old.diff(&old).is_empty());
+    /// ```
+    pub fn diff(&self, other: &Self) -> Patch<T> {
+        let base = self
+            .vec
+            .iter()
+            .zip(&other.vec)
+            .take_while(|(a, b)| a == b)
+            .count();
+        Patch {
+            base,
+            base_hash: fnv::hash(&self.vec[..base]),
+            pops: self.vec.len() - base,
+            pushes: other.vec[base..].to_vec(),
+        }
+    }
+
+    /// Applies a [Patch] made by diffing this stack, popping down to its base and pushing its
+    /// elements.
+    /// # Errors
+    /// Returns a [PatchError], leaving the stack unchanged, if the stack isn't the one the patch
+    /// was made from: if its length differs, or if the hash of its base does. A patch whose
+    /// lengths overflow when added expects a length of `usize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::{PatchError, Stack};
+    /// let mut old = Stack::new();
+    /// old.extend([1, 2, 3]);
+    /// let mut new = Stack::new();
+    /// new.extend([1, 4]);
+    /// let patch = old.diff(&new);
+    /// let mut replica = old.clone();
+    /// replica.apply(patch.clone()).unwrap();
+    /// assert_eq!(replica, new);
+    /// assert_eq!(
+    ///     replica.apply(patch),
+    ///     Err(PatchError::Length { expected: 3, len: 2 }),
+    /// );
+    /// let mut other = Stack::new();
+    /// other.extend([5, 2, 3]);
+    /// assert_eq!(other.apply(old.diff(&new)), Err(PatchError::Base));
+    /// ```
+    pub fn apply(&mut self, patch: Patch<T>) -> Result<(), PatchError> {
+        // A deserialized patch may claim any lengths, so they're added without overflowing.
+        match patch.base.checked_add(patch.pops) {
+            Some(expected) if expected == self.vec.len() => {}
+            expected => {
+                return Err(PatchError::Length {
+                    expected: expected.unwrap_or(usize::MAX),
+                    len: self.vec.len(),
+                })
+            }
+        }
+        if fnv::hash(&self.vec[..patch.base]) != patch.base_hash {
+            return Err(PatchError::Base);
+        }
+        self.vec.truncate(patch.base);
+        self.vec.extend(patch.pushes);
+        Ok(())
+    }
+}
+
+/// An error returned when a [Patch] is applied to a stack other than the one it was made from.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PatchError {
+    /// The stack's length differs from the one the patch was made from.
+    Length {
+        /// The length of the stack the patch was made from.
+        expected: usize,
+        /// The length of the stack.
+        len: usize,
+    },
+    /// The stack's base differs from the one the patch was made from.
+    Base,
+}
+impl Display for PatchError {
+    /// Formats the error.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Length { expected, len } => write!(
+                f,
+                "patch expects a stack of length {expected}, but it has length {len}"
+            ),
+            Self::Base => f.write_str("patch expects a different base"),
+        }
+    }
+}
+impl core::error::Error for PatchError {}
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for Patch<T> {
+    /// Serializes a patch as a tuple of its base length and hash, pop count, and pushed elements.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut new = Stack::new();
+    /// new.push(1);
+    /// let patch = Stack::new().diff(&new);
+    /// let json = serde_json::to_string(&patch).unwrap();
+    /// assert!(json.starts_with("[0,") && json.ends_with(",0,[1]]"));
+    /// assert_eq!(serde_json::from_str::<hay::Patch<i32>>(&json).unwrap(), patch);
+    /// ```
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.base, self.base_hash, self.pops, &self.pushes).serialize(serializer)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    /// Deserializes a patch from a tuple of its base length and hash, pop count, and pushed
+    /// elements.
+    ///
+    /// The patch isn't checked until it is applied, which rejects one crafted to not fit.
+    /// # Example
+    /// ```
+    /// use hay::{Patch, PatchError, Stack};
+    /// let json = format!("[{},0,1,[]]", usize::MAX);
+    /// let patch: Patch<i32> = serde_json::from_str(&json).unwrap();
+    /// let mut stack = Stack::new();
+    /// stack.push(1);
+    /// assert_eq!(
+    ///     stack.apply(patch),
+    ///     Err(PatchError::Length { expected: usize::MAX, len: 1 }),
+    /// );
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (base, base_hash, pops, pushes) =
+            <(usize, u64, usize, Vec<T>)>::deserialize(deserializer)?;
+        Ok(Self {
+            base,
+            base_hash,
+            pops,
+            pushes,
+        })
+    }
+}
@@ -0,0 +1,160 @@
+use crate::Stack;
+use core::mem;
+
+/// A pair of stacks for generation-based processing, popping from the current generation while
+/// pushing onto the next.
+///
+/// Once the current generation is used up, [DoubleStack::swap_generations] makes the next one
+/// current in constant time, reusing both buffers, for breadth-first searches by level and
+/// frame-based simulations.
+/// # Example
+/// ```
+/// use hay::DoubleStack;
+/// // Counting the nodes on each level of a binary tree of depth 3.
+/// let mut levels = DoubleStack::new();
+/// levels.push(1u32);
+/// levels.swap_generations();
+/// let mut widths = Vec::new();
+/// while !levels.is_empty() {
+///     widths.push(levels.current().len());
+///     while let Some(node) = levels.pop() {
+///         if node < 4 {
+///             levels.push(node * 2);
+///             levels.push(node * 2 + 1);
+///         }
+///     }
+///     levels.swap_generations();
+/// }
+/// assert_eq!(widths, [1, 2, 4]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DoubleStack<T> {
+    current: Stack<T>,
+    next: Stack<T>,
+}
+impl<T> DoubleStack<T> {
+    /// Constructs a new `DoubleStack<T>`, with both generations empty.
+    ///
+    /// The stacks will not allocate until elements are pushed onto them.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            current: Stack::new(),
+            next: Stack::new(),
+        }
+    }
+
+    /// Appends an element to the top of the next generation.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    #[inline(always)]
+    pub fn push(&mut self, value: T) {
+        self.next.push(value);
+    }
+
+    /// Removes the element at the top of the current generation and returns it, or [None] if it
+    /// is empty.
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        self.current.pop()
+    }
+
+    /// Returns a reference to the top element of the current generation.
+    #[inline(always)]
+    pub const fn top(&self) -> Option<&T> {
+        self.current.top()
+    }
+
+    /// Makes the next generation current, and starts a new next generation, in constant time.
+    ///
+    /// Elements left in the current generation carry over into the new next one, beneath
+    /// anything pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::DoubleStack;
+    /// let mut stack = DoubleStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), None);
+    /// stack.swap_generations();
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn swap_generations(&mut self) {
+        mem::swap(&mut self.current, &mut self.next);
+    }
+
+    /// Returns the stack of the current generation, which is popped from.
+    #[inline(always)]
+    pub const fn current(&self) -> &Stack<T> {
+        &self.current
+    }
+
+    /// Returns a mutable reference to the stack of the current generation, which is popped from.
+    #[inline(always)]
+    pub const fn current_mut(&mut self) -> &mut Stack<T> {
+        &mut self.current
+    }
+
+    /// Returns the stack of the next generation, which is pushed onto.
+    #[inline(always)]
+    pub const fn next(&self) -> &Stack<T> {
+        &self.next
+    }
+
+    /// Returns a mutable reference to the stack of the next generation, which is pushed onto.
+    #[inline(always)]
+    pub const fn next_mut(&mut self) -> &mut Stack<T> {
+        &mut self.next
+    }
+
+    /// Returns the number of elements in both generations.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.current.len() + self.next.len()
+    }
+
+    /// Returns `true` if both generations contain no elements.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.current.is_empty() && self.next.is_empty()
+    }
+
+    /// Returns the number of bytes both generations have allocated on the heap, including their
+    /// spare capacity.
+    ///
+    /// Memory the elements own themselves, like the buffers of strings, isn't counted.
+    #[inline(always)]
+    pub const fn allocated_bytes(&self) -> usize {
+        self.current.allocated_bytes() + self.next.allocated_bytes()
+    }
+
+    /// Clears both generations, popping all values.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the stacks.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.current.clear();
+        self.next.clear();
+    }
+
+    /// Consumes the stack, returning the current and next generations.
+    #[inline(always)]
+    pub fn into_inner(self) -> (Stack<T>, Stack<T>) {
+        (self.current, self.next)
+    }
+}
+impl<T> Default for DoubleStack<T> {
+    /// Constructs a new `DoubleStack<T>`, with both generations empty.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Extend<T> for DoubleStack<T> {
+    /// Pushes a collection of values onto the next generation.
+    #[inline(always)]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.next.extend(iter);
+    }
+}
@@ -0,0 +1,167 @@
+use core::{
+    fmt::{self, Debug},
+    mem::ManuallyDrop,
+    ptr,
+    sync::atomic::Ordering,
+};
+use crossbeam_epoch::{self as epoch, Atomic, Owned};
+
+/// A node in an [EpochStack].
+struct Node<T> {
+    value: ManuallyDrop<T>,
+    next: Atomic<Node<T>>,
+}
+
+/// A lock-free stack which can be pushed to and popped from by many threads at once, with nodes
+/// reclaimed by [crossbeam_epoch].
+///
+/// This is the same Treiber stack as [ConcurrentStack](crate::ConcurrentStack), but popped nodes
+/// are freed by crossbeam's epoch-based garbage collector rather than with hazard pointers,
+/// which suits programs already built around crossbeam.
+/// # Example
+/// ```
+/// use hay::EpochStack;
+/// let stack = EpochStack::new();
+/// std::thread::scope(|scope| {
+///     for i in 0..4 {
+///         let stack = &stack;
+///         scope.spawn(move || stack.push(i));
+///     }
+/// });
+/// let mut popped = Vec::new();
+/// while let Some(value) = stack.pop() {
+///     popped.push(value);
+/// }
+/// popped.sort();
+/// assert_eq!(popped, [0, 1, 2, 3]);
+/// ```
+pub struct EpochStack<T> {
+    head: Atomic<Node<T>>,
+}
+// SAFETY: Values are only ever accessed by the thread that pushed or popped them.
+unsafe impl<T: Send> Send for EpochStack<T> {}
+// SAFETY: Values are only ever accessed by the thread that pushed or popped them.
+unsafe impl<T: Send> Sync for EpochStack<T> {}
+impl<T> EpochStack<T> {
+    /// Constructs a new, empty `EpochStack<T>`.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::EpochStack;
+    /// let stack: EpochStack<i32> = EpochStack::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            head: Atomic::null(),
+        }
+    }
+
+    /// Appends an element to the top of the stack.
+    /// # Example
+    /// ```
+    /// use hay::EpochStack;
+    /// let stack = EpochStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    pub fn push(&self, value: T) {
+        let mut node = Owned::new(Node {
+            value: ManuallyDrop::new(value),
+            next: Atomic::null(),
+        });
+        let guard = epoch::pin();
+        loop {
+            let head = self.head.load(Ordering::Relaxed, &guard);
+            node.next.store(head, Ordering::Relaxed);
+            match self.head.compare_exchange(
+                head,
+                node,
+                Ordering::Release,
+                Ordering::Relaxed,
+                &guard,
+            ) {
+                Ok(_) => return,
+                Err(error) => node = error.new,
+            }
+        }
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::EpochStack;
+    /// let stack = EpochStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    pub fn pop(&self) -> Option<T> {
+        let guard = epoch::pin();
+        loop {
+            let head = self.head.load(Ordering::Acquire, &guard);
+            // SAFETY: The pinned guard keeps the node from being freed.
+            let node = unsafe { head.as_ref() }?;
+            let next = node.next.load(Ordering::Relaxed, &guard);
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::Acquire, Ordering::Relaxed, &guard)
+                .is_ok()
+            {
+                // SAFETY: Winning the exchange gives this thread sole ownership of the value, and
+                // the node is unreachable now, so it can be freed once every guard is unpinned.
+                unsafe {
+                    let value = ptr::read(&node.value);
+                    guard.defer_destroy(head);
+                    return Some(ManuallyDrop::into_inner(value));
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if the stack contained no elements at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::EpochStack;
+    /// let stack = EpochStack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire, &epoch::pin()).is_null()
+    }
+}
+impl<T> Default for EpochStack<T> {
+    /// Constructs a new, empty `EpochStack<T>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Debug for EpochStack<T> {
+    /// Formats an `EpochStack<T>` without its elements, which may change at any moment.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EpochStack")
+            .field("is_empty", &self.is_empty())
+            .finish_non_exhaustive()
+    }
+}
+impl<T> Drop for EpochStack<T> {
+    /// Drops every element left on the stack and frees every node.
+    fn drop(&mut self) {
+        // SAFETY: The stack is exclusively borrowed, so no other thread can reach its nodes.
+        unsafe {
+            let guard = epoch::unprotected();
+            let mut node = self.head.load(Ordering::Relaxed, guard);
+            while !node.is_null() {
+                let mut owned = node.into_owned();
+                node = owned.next.load(Ordering::Relaxed, guard);
+                ManuallyDrop::drop(&mut owned.value);
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::Full;
+use alloc::collections::TryReserveError;
+use core::fmt::{self, Display};
+
+/// The error returned when an operation on a stack can't be carried out.
+///
+/// The fallible operations throughout the crate convert into this, so they compose with `?`.
+/// # Example
+/// ```
+/// use hay::{ArrayStack, StackError};
+/// fn fill(stack: &mut ArrayStack<i32, 2>) -> Result<(), StackError> {
+///     for value in 0..3 {
+///         stack.try_push(value)?;
+///     }
+///     Ok(())
+/// }
+/// assert_eq!(fill(&mut ArrayStack::new()), Err(StackError::Full));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StackError {
+    /// The stack was already holding as many elements as it can.
+    Full,
+    /// Memory couldn't be allocated, or the capacity would exceed `isize::MAX` bytes.
+    Alloc(TryReserveError),
+    /// A depth was at or beyond the length of the stack.
+    OutOfBounds {
+        /// The depth which was asked for.
+        depth: usize,
+        /// The length of the stack.
+        len: usize,
+    },
+    /// An operation needed more elements than the stack held.
+    Underflow {
+        /// The number of elements the operation needed.
+        needed: usize,
+        /// The length of the stack.
+        len: usize,
+    },
+}
+impl Display for StackError {
+    /// Formats the error.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full => f.write_str("stack is full"),
+            Self::Alloc(error) => error.fmt(f),
+            Self::OutOfBounds { depth, len } => {
+                write!(
+                    f,
+                    "depth {depth} is out of bounds for a stack of length {len}"
+                )
+            }
+            Self::Underflow { needed, len } => {
+                write!(f, "needed {needed} elements, but the stack holds {len}")
+            }
+        }
+    }
+}
+impl core::error::Error for StackError {
+    /// Returns the allocation error, if any.
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Alloc(error) => Some(error),
+            Self::Full | Self::OutOfBounds { .. } | Self::Underflow { .. } => None,
+        }
+    }
+}
+impl From<TryReserveError> for StackError {
+    /// Wraps an allocation error.
+    #[inline(always)]
+    fn from(error: TryReserveError) -> Self {
+        Self::Alloc(error)
+    }
+}
+impl<T> From<Full<T>> for StackError {
+    /// Converts a rejected push, dropping the value which couldn't be pushed.
+    #[inline(always)]
+    fn from(_: Full<T>) -> Self {
+        Self::Full
+    }
+}
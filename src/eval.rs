@@ -0,0 +1,345 @@
+//! Evaluates expressions in reverse Polish (postfix) notation on a [Stack].
+//!
+//! An expression is a sequence of [Token]s: operands are pushed, and each operator pops as many
+//! operands as its [Operator::arity] and pushes its result. The set of operators is whatever
+//! type implements [Operator], and [Arithmetic] covers the usual ones on numbers.
+//! # Example
+//! ```
+//! use hay::eval::{self, Arithmetic, Token};
+//! // (1 + 2) * 4
+//! let tokens = [
+//!     Token::Operand(1),
+//!     Token::Operand(2),
+//!     Token::Operator(Arithmetic::Add),
+//!     Token::Operand(4),
+//!     Token::Operator(Arithmetic::Mul),
+//! ];
+//! assert_eq!(eval::eval(tokens), Ok(12));
+//! ```
+use crate::Stack;
+use core::fmt::{self, Display};
+
+/// A token of an expression in postfix notation.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Token<V, O> {
+    /// A value, pushed as it is.
+    Operand(V),
+    /// An operator, applied to the values on top of the stack.
+    Operator(O),
+}
+
+/// An operator taking a fixed number of operands and producing a value from them.
+/// # Example
+/// ```
+/// use hay::eval::{self, Operator, Token};
+/// struct Max;
+/// impl Operator<i32> for Max {
+///     type Error = core::convert::Infallible;
+///
+///     fn arity(&self) -> usize {
+///         2
+///     }
+///
+///     fn apply(&self, operands: &[i32]) -> Result<i32, Self::Error> {
+///         Ok(operands[0].max(operands[1]))
+///     }
+/// }
+/// let tokens = [Token::Operand(1), Token::Operand(3), Token::Operator(Max)];
+/// assert_eq!(eval::eval(tokens), Ok(3));
+/// ```
+pub trait Operator<V> {
+    /// The error returned when the operator can't be applied to its operands.
+    type Error;
+
+    /// Returns the number of operands the operator takes.
+    fn arity(&self) -> usize;
+
+    /// Applies the operator to its operands, which are given in the order they were pushed and
+    /// are always [Operator::arity] long.
+    /// # Errors
+    /// Returns an error if the operator can't be applied to `operands`.
+    fn apply(&self, operands: &[V]) -> Result<V, Self::Error>;
+}
+
+/// The error returned when an expression can't be evaluated.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error<E> {
+    /// The expression held no tokens.
+    Empty,
+    /// An operator had fewer operands on the stack than it takes.
+    Underflow {
+        /// The index of the operator's token.
+        position: usize,
+        /// The number of operands the operator takes.
+        arity: usize,
+        /// The number of operands on the stack.
+        len: usize,
+    },
+    /// More than one value was left on the stack once every token was evaluated.
+    Leftover {
+        /// The number of values left on the stack.
+        len: usize,
+    },
+    /// An operator couldn't be applied to its operands.
+    Operator {
+        /// The index of the operator's token.
+        position: usize,
+        /// The error the operator returned.
+        error: E,
+    },
+}
+impl<E: Display> Display for Error<E> {
+    /// Formats the error.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("expression is empty"),
+            Self::Underflow {
+                position,
+                arity,
+                len,
+            } => write!(
+                f,
+                "operator at {position} takes {arity} operands, but only {len} are on the stack"
+            ),
+            Self::Leftover { len } => write!(f, "expression left {len} values on the stack"),
+            Self::Operator { position, error } => {
+                write!(f, "operator at {position} failed: {error}")
+            }
+        }
+    }
+}
+impl<E: core::error::Error + 'static> core::error::Error for Error<E> {
+    /// Returns the operator's error, if any.
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Operator { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// An evaluator of postfix expressions, which keeps its stack between evaluations so they don't
+/// reallocate.
+/// # Example
+/// ```
+/// use hay::eval::{Arithmetic, Evaluator, Token};
+/// let mut evaluator = Evaluator::new();
+/// for value in 0..3 {
+///     let tokens = [
+///         Token::Operand(value),
+///         Token::Operator(Arithmetic::Neg),
+///     ];
+///     assert_eq!(evaluator.eval(tokens), Ok(-value));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Evaluator<V> {
+    stack: Stack<V>,
+}
+impl<V> Evaluator<V> {
+    /// Constructs a new `Evaluator<V>`.
+    ///
+    /// The evaluator will not allocate until an expression is evaluated.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            stack: Stack::new(),
+        }
+    }
+
+    /// Evaluates a postfix expression, returning the single value it leaves on the stack.
+    /// # Errors
+    /// Returns an [Error] if the expression is empty, if an operator underflows the stack or
+    /// fails, or if more than one value is left.
+    /// # Example
+    /// ```
+    /// use hay::eval::{Arithmetic, Error, Evaluator, Token};
+    /// let mut evaluator = Evaluator::new();
+    /// assert_eq!(
+    ///     evaluator.eval([Token::Operand(1), Token::Operator(Arithmetic::Add)]),
+    ///     Err(Error::Underflow { position: 1, arity: 2, len: 1 }),
+    /// );
+    /// assert_eq!(
+    ///     evaluator.eval([Token::<_, Arithmetic>::Operand(1), Token::Operand(2)]),
+    ///     Err(Error::Leftover { len: 2 }),
+    /// );
+    /// ```
+    pub fn eval<O: Operator<V>>(
+        &mut self,
+        tokens: impl IntoIterator<Item = Token<V, O>>,
+    ) -> Result<V, Error<O::Error>> {
+        self.stack.clear();
+        for (position, token) in tokens.into_iter().enumerate() {
+            match token {
+                Token::Operand(value) => self.stack.push(value),
+                Token::Operator(operator) => {
+                    let arity = operator.arity();
+                    let len = self.stack.len();
+                    let Some(start) = len.checked_sub(arity) else {
+                        return Err(Error::Underflow {
+                            position,
+                            arity,
+                            len,
+                        });
+                    };
+                    let value = operator
+                        .apply(&self.stack[start..])
+                        .map_err(|error| Error::Operator { position, error })?;
+                    self.stack.vec.truncate(start);
+                    self.stack.push(value);
+                }
+            }
+        }
+        match self.stack.len() {
+            0 => Err(Error::Empty),
+            1 => Ok(self.stack.pop().unwrap()),
+            len => Err(Error::Leftover { len }),
+        }
+    }
+}
+impl<V> Default for Evaluator<V> {
+    /// Constructs a new `Evaluator<V>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Evaluates a postfix expression with a fresh [Evaluator], returning the single value it leaves
+/// on the stack.
+/// # Errors
+/// Returns an [Error] if the expression is empty, if an operator underflows the stack or fails,
+/// or if more than one value is left.
+#[inline(always)]
+pub fn eval<V, O: Operator<V>>(
+    tokens: impl IntoIterator<Item = Token<V, O>>,
+) -> Result<V, Error<O::Error>> {
+    Evaluator::new().eval(tokens)
+}
+
+/// The arithmetic operators, implemented for every primitive integer and float.
+///
+/// Integer operators are checked, so overflowing or dividing by zero is an [ArithmeticError]
+/// rather than a panic.
+/// # Example
+/// ```
+/// use hay::eval::{self, Arithmetic, ArithmeticError, Error, Token};
+/// let tokens = [
+///     Token::Operand(1u8),
+///     Token::Operand(0),
+///     Token::Operator(Arithmetic::Div),
+/// ];
+/// assert_eq!(
+///     eval::eval(tokens),
+///     Err(Error::Operator { position: 2, error: ArithmeticError::DivisionByZero }),
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Arithmetic {
+    /// Adds two operands.
+    Add,
+    /// Subtracts the second operand from the first.
+    Sub,
+    /// Multiplies two operands.
+    Mul,
+    /// Divides the first operand by the second.
+    Div,
+    /// Takes the remainder of dividing the first operand by the second.
+    Rem,
+    /// Negates one operand.
+    Neg,
+}
+
+/// The error returned when an [Arithmetic] operator can't be applied to integers.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ArithmeticError {
+    /// The result doesn't fit in the type.
+    Overflow,
+    /// The divisor was zero.
+    DivisionByZero,
+}
+impl Display for ArithmeticError {
+    /// Formats the error.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow => f.write_str("arithmetic overflow"),
+            Self::DivisionByZero => f.write_str("division by zero"),
+        }
+    }
+}
+impl core::error::Error for ArithmeticError {}
+
+/// Implements [Operator] for [Arithmetic] on integer types with checked operations.
+macro_rules! impl_integer_arithmetic {
+    ($($integer:ty),*) => {$(
+        impl Operator<$integer> for Arithmetic {
+            /// The error returned when the operation overflows or divides by zero.
+            type Error = ArithmeticError;
+
+            /// Returns one for [Arithmetic::Neg], and two for the others.
+            #[inline(always)]
+            fn arity(&self) -> usize {
+                match self {
+                    Self::Neg => 1,
+                    _ => 2,
+                }
+            }
+
+            /// Applies the checked operation.
+            fn apply(&self, operands: &[$integer]) -> Result<$integer, Self::Error> {
+                if let Self::Neg = self {
+                    return operands[0].checked_neg().ok_or(ArithmeticError::Overflow);
+                }
+                let (a, b) = (operands[0], operands[1]);
+                if matches!(self, Self::Div | Self::Rem) && b == 0 {
+                    return Err(ArithmeticError::DivisionByZero);
+                }
+                match self {
+                    Self::Add => a.checked_add(b),
+                    Self::Sub => a.checked_sub(b),
+                    Self::Mul => a.checked_mul(b),
+                    Self::Div => a.checked_div(b),
+                    Self::Rem => a.checked_rem(b),
+                    Self::Neg => unreachable!(),
+                }
+                .ok_or(ArithmeticError::Overflow)
+            }
+        }
+    )*};
+}
+impl_integer_arithmetic!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// Implements [Operator] for [Arithmetic] on float types, which never fail.
+macro_rules! impl_float_arithmetic {
+    ($($float:ty),*) => {$(
+        impl Operator<$float> for Arithmetic {
+            /// Float operations never fail.
+            type Error = core::convert::Infallible;
+
+            /// Returns one for [Arithmetic::Neg], and two for the others.
+            #[inline(always)]
+            fn arity(&self) -> usize {
+                match self {
+                    Self::Neg => 1,
+                    _ => 2,
+                }
+            }
+
+            /// Applies the operation.
+            fn apply(&self, operands: &[$float]) -> Result<$float, Self::Error> {
+                Ok(match self {
+                    Self::Add => operands[0] + operands[1],
+                    Self::Sub => operands[0] - operands[1],
+                    Self::Mul => operands[0] * operands[1],
+                    Self::Div => operands[0] / operands[1],
+                    Self::Rem => operands[0] % operands[1],
+                    Self::Neg => -operands[0],
+                })
+            }
+        }
+    )*};
+}
+impl_float_arithmetic!(f32, f64);
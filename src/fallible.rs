@@ -0,0 +1,355 @@
+//! A stack whose operations never panic, for code which must be certified not to abort.
+//!
+//! [Stack] mirrors [crate::Stack], but every operation which could panic there returns an
+//! [Error] instead, and it doesn't dereference to a slice so indexing can't panic either.
+//! # Example
+//! ```
+//! use hay::fallible::{Error, Stack};
+//! let mut stack = Stack::new();
+//! stack.push(1)?;
+//! stack.push(2)?;
+//! assert_eq!(stack.get(1), Ok(&1));
+//! assert_eq!(stack.get(2), Err(Error::OutOfBounds { depth: 2, len: 2 }));
+//! assert_eq!(stack.pop(), Some(2));
+//! # Ok::<(), Error>(())
+//! ```
+pub use crate::StackError as Error;
+use alloc::{collections::TryReserveError, vec::Vec};
+use core::{cmp::Ordering, mem};
+
+/// A growable and shrinkable stack array type, whose operations never panic.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Stack<T> {
+    vec: Vec<T>,
+}
+impl<T> Stack<T> {
+    /// Constructs a new, empty `Stack<T>`.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let stack: Stack<i32> = Stack::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self { vec: Vec::new() }
+    }
+
+    /// Constructs a new, empty `Stack<T>` with room for at least `capacity` elements.
+    /// # Errors
+    /// Returns [Error::Alloc] if the memory couldn't be allocated.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let stack: Stack<i32> = Stack::try_with_capacity(16).unwrap();
+    /// assert!(stack.capacity() >= 16);
+    /// assert!(Stack::<i32>::try_with_capacity(usize::MAX).is_err());
+    /// ```
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, Error> {
+        let mut stack = Self::new();
+        stack.reserve(capacity)?;
+        Ok(stack)
+    }
+
+    /// Reserves room for at least `additional` more elements.
+    /// # Errors
+    /// Returns [Error::Alloc] if the memory couldn't be allocated.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let mut stack: Stack<i32> = Stack::new();
+    /// stack.reserve(4).unwrap();
+    /// assert!(stack.capacity() >= 4);
+    /// ```
+    #[inline(always)]
+    pub fn reserve(&mut self, additional: usize) -> Result<(), Error> {
+        Ok(crate::try_reserve(&mut self.vec, additional)?)
+    }
+
+    /// Returns the number of elements the stack can hold without reallocating.
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns the number of bytes the stack has allocated on the heap, including the spare
+    /// capacity.
+    ///
+    /// See [crate::Stack::allocated_bytes].
+    #[inline(always)]
+    pub const fn allocated_bytes(&self) -> usize {
+        self.vec.capacity() * mem::size_of::<T>()
+    }
+
+    /// Returns a reference to the top element in the stack.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1).unwrap();
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub const fn top(&self) -> Option<&T> {
+        self.vec.as_slice().last()
+    }
+
+    /// Returns a mutable reference to the top element in the stack.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1).unwrap();
+    /// *stack.top_mut().unwrap() = 2;
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    #[inline(always)]
+    pub const fn top_mut(&mut self) -> Option<&mut T> {
+        self.vec.as_mut_slice().last_mut()
+    }
+
+    /// Returns a reference to the element `depth` elements below the top of the stack.
+    /// # Errors
+    /// Returns [Error::OutOfBounds] if `depth` is at or beyond the length of the stack.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1).unwrap();
+    /// stack.push(2).unwrap();
+    /// assert_eq!(stack.get(0), Ok(&2));
+    /// assert_eq!(stack.get(1), Ok(&1));
+    /// assert!(stack.get(2).is_err());
+    /// ```
+    #[inline(always)]
+    pub fn get(&self, depth: usize) -> Result<&T, Error> {
+        let index = self.index(depth)?;
+        self.vec.get(index).ok_or(self.out_of_bounds(depth))
+    }
+
+    /// Returns a mutable reference to the element `depth` elements below the top of the stack.
+    /// # Errors
+    /// Returns [Error::OutOfBounds] if `depth` is at or beyond the length of the stack.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1).unwrap();
+    /// stack.push(2).unwrap();
+    /// *stack.get_mut(1).unwrap() = 3;
+    /// assert_eq!(stack.get(1), Ok(&3));
+    /// ```
+    #[inline(always)]
+    pub fn get_mut(&mut self, depth: usize) -> Result<&mut T, Error> {
+        let index = self.index(depth)?;
+        let error = self.out_of_bounds(depth);
+        self.vec.get_mut(index).ok_or(error)
+    }
+
+    /// Appends an element to the top of the stack.
+    /// # Errors
+    /// Returns [Error::Alloc] if the stack had to grow but the memory couldn't be allocated, in
+    /// which case `value` is dropped.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let mut stack = Stack::new();
+    /// assert_eq!(stack.push(1), Ok(()));
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn push(&mut self, value: T) -> Result<(), Error> {
+        self.reserve(1)?;
+        self.vec.push(value);
+        Ok(())
+    }
+
+    /// Appends an element to the top of the stack, or hands it back along with the error if the
+    /// stack had to grow but the memory couldn't be allocated.
+    /// # Errors
+    /// Returns the [TryReserveError] and `value` if the memory couldn't be allocated.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let mut stack = Stack::new();
+    /// assert!(stack.try_push(1).is_ok());
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn try_push(&mut self, value: T) -> Result<(), (TryReserveError, T)> {
+        if let Err(error) = crate::try_reserve(&mut self.vec, 1) {
+            return Err((error, value));
+        }
+        self.vec.push(value);
+        Ok(())
+    }
+
+    /// Inserts an element into a sorted stack, keeping it sorted with the greatest element on
+    /// top, and returns the depth it was inserted at.
+    ///
+    /// See [crate::Stack::push_sorted].
+    /// # Errors
+    /// Returns [Error::Alloc] if the stack had to grow but the memory couldn't be allocated, in
+    /// which case `value` is dropped.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push_sorted(3).unwrap();
+    /// stack.push_sorted(1).unwrap();
+    /// assert_eq!(stack.push_sorted(2), Ok(1));
+    /// ```
+    #[inline(always)]
+    pub fn push_sorted(&mut self, value: T) -> Result<usize, Error>
+    where
+        T: Ord,
+    {
+        self.push_sorted_by(value, T::cmp)
+    }
+
+    /// Inserts an element into a stack sorted by `compare`, keeping it sorted with the greatest
+    /// element on top, and returns the depth it was inserted at.
+    ///
+    /// See [crate::Stack::push_sorted_by].
+    /// # Errors
+    /// Returns [Error::Alloc] if the stack had to grow but the memory couldn't be allocated, in
+    /// which case `value` is dropped.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push_sorted_by(1, |a, b| b.cmp(a)).unwrap();
+    /// stack.push_sorted_by(2, |a, b| b.cmp(a)).unwrap();
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    pub fn push_sorted_by(
+        &mut self,
+        value: T,
+        mut compare: impl FnMut(&T, &T) -> Ordering,
+    ) -> Result<usize, Error> {
+        self.reserve(1)?;
+        let index = self
+            .vec
+            .partition_point(|element| compare(element, &value) != Ordering::Greater);
+        // The partition point is never beyond the length, so inserting there never panics.
+        self.vec.insert(index, value);
+        Ok(self.vec.len() - 1 - index)
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1).unwrap();
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+
+    /// Removes and returns the element `depth` elements below the top of the stack, shifting the
+    /// elements above it down.
+    /// # Errors
+    /// Returns [Error::OutOfBounds] if `depth` is at or beyond the length of the stack.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1).unwrap();
+    /// stack.push(2).unwrap();
+    /// assert_eq!(stack.remove(1), Ok(1));
+    /// assert!(stack.remove(1).is_err());
+    /// ```
+    #[inline(always)]
+    pub fn remove(&mut self, depth: usize) -> Result<T, Error> {
+        let index = self.index(depth)?;
+        Ok(self.vec.remove(index))
+    }
+
+    /// Pops elements until the stack holds `len` elements.
+    /// # Errors
+    /// Returns [Error::OutOfBounds] if the stack holds fewer than `len` elements.
+    /// # Example
+    /// ```
+    /// use hay::fallible::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1).unwrap();
+    /// stack.push(2).unwrap();
+    /// assert!(stack.truncate(3).is_err());
+    /// stack.truncate(1).unwrap();
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn truncate(&mut self, len: usize) -> Result<(), Error> {
+        if len > self.vec.len() {
+            return Err(self.out_of_bounds(len));
+        }
+        self.vec.truncate(len);
+        Ok(())
+    }
+
+    /// Clears the stack, popping all values.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the stack.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+
+    /// Returns the number of elements in the stack.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Extracts a slice of the elements, from the bottom of the stack up.
+    ///
+    /// Indexing the slice can panic, so prefer [Stack::get] or the slice's own `get`.
+    #[inline(always)]
+    pub const fn as_slice(&self) -> &[T] {
+        self.vec.as_slice()
+    }
+
+    /// Turns a depth into an index into the vector.
+    #[inline(always)]
+    fn index(&self, depth: usize) -> Result<usize, Error> {
+        match self.vec.len().checked_sub(depth) {
+            Some(above) if above > 0 => Ok(above - 1),
+            _ => Err(self.out_of_bounds(depth)),
+        }
+    }
+
+    /// Returns the error for `depth` being out of bounds.
+    #[inline(always)]
+    fn out_of_bounds(&self, depth: usize) -> Error {
+        Error::OutOfBounds {
+            depth,
+            len: self.vec.len(),
+        }
+    }
+}
+impl<T> From<crate::Stack<T>> for Stack<T> {
+    /// Converts a stack into its panic-free counterpart, without reallocating.
+    #[inline(always)]
+    fn from(stack: crate::Stack<T>) -> Self {
+        Self { vec: stack.vec }
+    }
+}
+impl<T> From<Stack<T>> for crate::Stack<T> {
+    /// Converts a panic-free stack back into a regular one, without reallocating.
+    #[inline(always)]
+    fn from(stack: Stack<T>) -> Self {
+        Self { vec: stack.vec }
+    }
+}
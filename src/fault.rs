@@ -0,0 +1,119 @@
+//! Injected allocation failures, so code using the fallible operations can exercise its
+//! out-of-memory handling in tests.
+//!
+//! Failures are injected per thread, into every allocation the fallible operations of the crate's
+//! stacks make, like [fallible::Stack::push](crate::fallible::Stack::push) and
+//! [Stack::try_push](crate::Stack::try_push). Operations which don't need to allocate are never
+//! failed. This is only meant for testing, so the feature should only be enabled for
+//! dev-dependencies.
+//! # Example
+//! ```
+//! use hay::{fallible::Stack, fault};
+//! fault::fail_every(3);
+//! let mut stack = Stack::new();
+//! let mut failures = 0;
+//! for value in 0..64 {
+//!     if stack.push(value).is_err() {
+//!         failures += 1;
+//!     }
+//! }
+//! fault::reset();
+//! assert!(failures > 0);
+//! assert_eq!(stack.len(), 64 - failures);
+//! ```
+use alloc::{boxed::Box, collections::TryReserveError, vec::Vec};
+use core::cell::RefCell;
+
+/// When allocations are made to fail.
+enum Rule {
+    Every(usize),
+    With(Box<dyn FnMut(usize) -> bool>),
+}
+
+/// The rule in effect on a thread, along with the number of allocations it has seen.
+struct Injector {
+    rule: Rule,
+    attempts: usize,
+}
+
+std::thread_local! {
+    static INJECTOR: RefCell<Option<Injector>> = const { RefCell::new(None) };
+}
+
+/// Makes every `n`th allocation on the current thread fail, counting from the next one.
+/// # Panics
+/// Panics if `n` is zero.
+#[inline(always)]
+pub fn fail_every(n: usize) {
+    assert!(n != 0, "can't fail every zeroth allocation");
+    install(Rule::Every(n));
+}
+
+/// Makes the allocations on the current thread fail whenever `f` returns `true`, calling it with
+/// the number of allocations made before, counting from the next one.
+/// # Example
+/// ```
+/// use hay::{fault, Stack};
+/// // Failing only the second allocation.
+/// fault::fail_with(|attempt| attempt == 1);
+/// let mut stack = Stack::new();
+/// for value in 0..4 {
+///     stack.try_push(value).unwrap();
+/// }
+/// assert!(stack.try_push(4).is_err());
+/// fault::reset();
+/// ```
+#[inline(always)]
+pub fn fail_with(f: impl FnMut(usize) -> bool + 'static) {
+    install(Rule::With(Box::new(f)));
+}
+
+/// Stops failing allocations on the current thread.
+#[inline(always)]
+pub fn reset() {
+    INJECTOR.with_borrow_mut(|injector| *injector = None);
+}
+
+/// Returns the number of allocations made on the current thread since failures were last
+/// injected, or zero if they aren't.
+/// # Example
+/// ```
+/// use hay::{fallible::Stack, fault};
+/// fault::fail_with(|_| false);
+/// let mut stack = Stack::new();
+/// stack.push(1).unwrap();
+/// stack.push(2).unwrap();
+/// assert_eq!(fault::attempts(), 1);
+/// fault::reset();
+/// ```
+#[inline(always)]
+pub fn attempts() -> usize {
+    INJECTOR.with_borrow(|injector| injector.as_ref().map_or(0, |injector| injector.attempts))
+}
+
+/// Puts a rule in effect on the current thread.
+#[inline(always)]
+fn install(rule: Rule) {
+    INJECTOR.with_borrow_mut(|injector| *injector = Some(Injector { rule, attempts: 0 }));
+}
+
+/// Counts an allocation, returning an error to fail it with if the rule in effect says so.
+pub(crate) fn inject() -> Result<(), TryReserveError> {
+    let fail = INJECTOR.with_borrow_mut(|injector| {
+        let Some(injector) = injector else {
+            return false;
+        };
+        let attempt = injector.attempts;
+        injector.attempts += 1;
+        match &mut injector.rule {
+            Rule::Every(n) => (attempt + 1) % *n == 0,
+            Rule::With(f) => f(attempt),
+        }
+    });
+    if fail {
+        // The error can't be constructed directly, so it is taken from a reservation which can
+        // never succeed.
+        return Vec::<u8>::new().try_reserve(usize::MAX);
+    }
+    Ok(())
+}
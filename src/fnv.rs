@@ -0,0 +1,67 @@
+use core::hash::{Hash, Hasher};
+
+/// A 64-bit Fowler-Noll-Vo hasher, which hashes a value the same way in every process and on
+/// every platform since it takes no random keys and writes integers as little-endian.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Fnv(u64);
+impl Default for Fnv {
+    /// Constructs a hasher starting from the FNV offset basis.
+    #[inline(always)]
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+impl Hasher for Fnv {
+    /// Returns the hash of the bytes written so far.
+    #[inline(always)]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    /// Mixes `bytes` into the hash, one at a time.
+    #[inline(always)]
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Mixes a `u16` into the hash as little-endian.
+    #[inline(always)]
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// Mixes a `u32` into the hash as little-endian.
+    #[inline(always)]
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// Mixes a `u64` into the hash as little-endian.
+    #[inline(always)]
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// Mixes a `u128` into the hash as little-endian.
+    #[inline(always)]
+    fn write_u128(&mut self, value: u128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// Mixes a `usize` into the hash as a little-endian `u64`, so it doesn't depend on the
+    /// pointer width.
+    #[inline(always)]
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+}
+
+/// Hashes `value` with a fresh [Fnv].
+#[inline(always)]
+pub(crate) fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = Fnv::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,536 @@
+//! A stack whose reallocations follow a pluggable [GrowthPolicy], instead of the doubling it
+//! would inherit from [Vec].
+//!
+//! [Stack] mirrors [crate::Stack], but asks its policy for the new capacity whenever a push finds
+//! it full. Policies compose, so a growth factor can be rounded to whole pages and capped. A
+//! stack can also opt into shrinking on its own with a [Shrink] policy, so a spike in its length
+//! doesn't hold on to memory forever.
+//! # Example
+//! ```
+//! use hay::growth::{Capped, Factor, Stack};
+//! // Growing by half each time, but never past 1000 elements.
+//! let mut stack = Stack::with_policy(Capped::new(Factor::new(3, 2), 1000));
+//! for value in 0..1000 {
+//!     stack.push(value);
+//! }
+//! assert_eq!(stack.capacity(), 1000);
+//! assert_eq!(stack.try_push(1000).unwrap_err().into_inner(), 1000);
+//! ```
+use crate::Full;
+use alloc::vec::Vec;
+use core::{
+    hash::{Hash, Hasher},
+    mem,
+    ops::{Deref, DerefMut},
+};
+
+/// A strategy for how much a full stack grows by.
+/// # Example
+/// ```
+/// use hay::growth::{GrowthPolicy, Stack};
+/// // Growing one element at a time, which never wastes memory.
+/// struct Exact;
+/// impl GrowthPolicy for Exact {
+///     fn grow(&self, _capacity: usize, required: usize, _element_size: usize) -> Option<usize> {
+///         Some(required)
+///     }
+/// }
+/// let mut stack = Stack::with_policy(Exact);
+/// stack.push(1);
+/// stack.push(2);
+/// assert_eq!(stack.capacity(), 2);
+/// ```
+pub trait GrowthPolicy {
+    /// Returns the capacity a stack of elements `element_size` bytes large should grow to from
+    /// `capacity`, which must be at least `required`, or [None] if it mustn't grow that far.
+    ///
+    /// A capacity below `required` is treated as [None].
+    fn grow(&self, capacity: usize, required: usize, element_size: usize) -> Option<usize>;
+}
+impl<P: GrowthPolicy + ?Sized> GrowthPolicy for &P {
+    /// Forwards to the referenced policy.
+    #[inline(always)]
+    fn grow(&self, capacity: usize, required: usize, element_size: usize) -> Option<usize> {
+        (**self).grow(capacity, required, element_size)
+    }
+}
+
+/// Doubles the capacity, starting from four elements.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Doubling;
+impl GrowthPolicy for Doubling {
+    /// Returns twice the capacity, or `required` or four if either is greater.
+    #[inline(always)]
+    fn grow(&self, capacity: usize, required: usize, _element_size: usize) -> Option<usize> {
+        Some(capacity.saturating_mul(2).max(required).max(4))
+    }
+}
+
+/// Multiplies the capacity by a fraction, such as 3/2 for growing by half each time.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Factor {
+    numerator: usize,
+    denominator: usize,
+}
+impl Factor {
+    /// Constructs a new `Factor` multiplying by `numerator / denominator`.
+    /// # Panics
+    /// Panics if `denominator` is zero.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(numerator: usize, denominator: usize) -> Self {
+        assert!(
+            denominator != 0,
+            "a growth factor's denominator must not be zero"
+        );
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+}
+impl GrowthPolicy for Factor {
+    /// Returns the capacity multiplied by the factor, or `required` if it is greater.
+    #[inline(always)]
+    fn grow(&self, capacity: usize, required: usize, _element_size: usize) -> Option<usize> {
+        Some((capacity.saturating_mul(self.numerator) / self.denominator).max(required))
+    }
+}
+
+/// Adds a fixed number of elements to the capacity.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Increment(pub usize);
+impl GrowthPolicy for Increment {
+    /// Returns the capacity plus the increment, or `required` if it is greater.
+    #[inline(always)]
+    fn grow(&self, capacity: usize, required: usize, _element_size: usize) -> Option<usize> {
+        Some(capacity.saturating_add(self.0).max(required))
+    }
+}
+
+/// Rounds the capacity another policy grows to up to a whole number of pages.
+/// # Example
+/// ```
+/// use hay::growth::{GrowthPolicy, Increment, PageAligned};
+/// let policy = PageAligned::new(Increment(1), 4096);
+/// // A page holds 341 elements of 12 bytes, leaving 4 bytes over.
+/// assert_eq!(policy.grow(0, 1, 12), Some(341));
+/// ```
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PageAligned<P> {
+    policy: P,
+    page_size: usize,
+}
+impl<P> PageAligned<P> {
+    /// Constructs a new `PageAligned<P>` rounding the capacities `policy` grows to up to pages of
+    /// `page_size` bytes.
+    /// # Panics
+    /// Panics if `page_size` is zero.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(policy: P, page_size: usize) -> Self {
+        assert!(page_size != 0, "a page must not be empty");
+        Self { policy, page_size }
+    }
+}
+impl<P: GrowthPolicy> GrowthPolicy for PageAligned<P> {
+    /// Returns the capacity the inner policy grows to, plus as many elements as fit in the rest
+    /// of its last page.
+    fn grow(&self, capacity: usize, required: usize, element_size: usize) -> Option<usize> {
+        let grown = self.policy.grow(capacity, required, element_size)?;
+        if element_size == 0 {
+            return Some(grown);
+        }
+        let bytes = grown
+            .checked_mul(element_size)?
+            .checked_next_multiple_of(self.page_size)?;
+        Some(bytes / element_size)
+    }
+}
+
+/// Caps the capacity another policy grows to at a maximum number of elements.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Capped<P> {
+    policy: P,
+    max: usize,
+}
+impl<P> Capped<P> {
+    /// Constructs a new `Capped<P>` limiting the capacities `policy` grows to to `max`.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(policy: P, max: usize) -> Self {
+        Self { policy, max }
+    }
+}
+impl<P: GrowthPolicy> GrowthPolicy for Capped<P> {
+    /// Returns the capacity the inner policy grows to, or the maximum if it is less, or [None]
+    /// if `required` is over the maximum.
+    #[inline(always)]
+    fn grow(&self, capacity: usize, required: usize, element_size: usize) -> Option<usize> {
+        if required > self.max {
+            return None;
+        }
+        let grown = self.policy.grow(capacity, required, element_size)?;
+        Some(grown.min(self.max))
+    }
+}
+
+/// When a [Stack] shrinks its allocation on its own: once its length has stayed below a fraction
+/// of its capacity for a number of operations in a row.
+///
+/// Waiting for several operations keeps a stack whose length hovers around the threshold from
+/// reallocating back and forth.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Shrink {
+    numerator: usize,
+    denominator: usize,
+    operations: usize,
+}
+impl Shrink {
+    /// Constructs a new `Shrink` policy, shrinking once the length has stayed below
+    /// `numerator / denominator` of the capacity for `operations` pushes, pops, and clears in a
+    /// row.
+    /// # Panics
+    /// Panics if `denominator` is zero.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(numerator: usize, denominator: usize, operations: usize) -> Self {
+        assert!(
+            denominator != 0,
+            "a shrink fraction's denominator must not be zero"
+        );
+        Self {
+            numerator,
+            denominator,
+            operations,
+        }
+    }
+
+    /// Returns `true` if `len` is below the fraction of `capacity`.
+    #[inline(always)]
+    fn below(&self, len: usize, capacity: usize) -> bool {
+        (len as u128) * (self.denominator as u128) < (capacity as u128) * (self.numerator as u128)
+    }
+}
+
+/// A growable and shrinkable stack array type, which grows according to a [GrowthPolicy].
+///
+/// Stacks compare and hash by their elements alone.
+#[derive(Clone, Debug, Default)]
+pub struct Stack<T, P = Doubling> {
+    vec: Vec<T>,
+    policy: P,
+    shrink: Option<Shrink>,
+    /// The number of operations in a row the length has been below the shrink threshold.
+    low: usize,
+    frozen: bool,
+}
+impl<T, P: GrowthPolicy> Stack<T, P> {
+    /// Constructs a new, empty `Stack<T, P>` growing according to `policy`.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    #[must_use]
+    #[inline(always)]
+    pub const fn with_policy(policy: P) -> Self {
+        Self {
+            vec: Vec::new(),
+            policy,
+            shrink: None,
+            low: 0,
+            frozen: false,
+        }
+    }
+
+    /// Makes the stack shrink its allocation on its own according to `shrink`, to the capacity
+    /// its growth policy would grow its length to.
+    /// # Example
+    /// ```
+    /// use hay::growth::{Shrink, Stack};
+    /// // Shrinking once under a quarter full for 8 operations in a row.
+    /// let mut stack = Stack::<u32>::new().with_shrink(Shrink::new(1, 4, 8));
+    /// for value in 0..1024 {
+    ///     stack.push(value);
+    /// }
+    /// while stack.len() > 16 {
+    ///     stack.pop();
+    /// }
+    /// assert!(stack.capacity() < 64);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn with_shrink(mut self, shrink: Shrink) -> Self {
+        self.shrink = Some(shrink);
+        self.low = 0;
+        self
+    }
+
+    /// Returns the policy the stack shrinks according to, if any.
+    #[inline(always)]
+    pub const fn shrink(&self) -> Option<&Shrink> {
+        self.shrink.as_ref()
+    }
+
+    /// Freezes the capacity of the stack, so it neither grows nor shrinks until it is thawed.
+    ///
+    /// Pushing onto a full frozen stack fails instead of reallocating, so a stack can be grown
+    /// during setup and then be guaranteed to never allocate, as real-time code needs. A
+    /// [crate::Stack] built during setup is frozen by [crate::Stack::into_frozen].
+    /// # Example
+    /// ```
+    /// use hay::growth::Stack;
+    /// let mut stack: Stack<i32> = Stack::new();
+    /// stack.push(0);
+    /// assert_eq!(stack.capacity(), 4);
+    /// stack.freeze_capacity();
+    /// for value in 1..4 {
+    ///     stack.push(value);
+    /// }
+    /// assert_eq!(stack.try_push(4).unwrap_err().into_inner(), 4);
+    /// stack.thaw_capacity();
+    /// stack.push(4);
+    /// ```
+    #[inline(always)]
+    pub const fn freeze_capacity(&mut self) {
+        self.frozen = true;
+    }
+
+    /// Thaws the capacity of the stack, so it grows and shrinks according to its policies again.
+    #[inline(always)]
+    pub const fn thaw_capacity(&mut self) {
+        self.frozen = false;
+        self.low = 0;
+    }
+
+    /// Returns `true` if the capacity of the stack is frozen.
+    #[inline(always)]
+    pub const fn is_capacity_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Returns a reference to the policy the stack grows according to.
+    #[inline(always)]
+    pub const fn policy(&self) -> &P {
+        &self.policy
+    }
+
+    /// Returns a reference to the top element in the stack.
+    #[inline(always)]
+    pub const fn top(&self) -> Option<&T> {
+        self.vec.as_slice().last()
+    }
+
+    /// Returns a mutable reference to the top element in the stack.
+    #[inline(always)]
+    pub const fn top_mut(&mut self) -> Option<&mut T> {
+        self.vec.as_mut_slice().last_mut()
+    }
+
+    /// Appends an element to the top of the stack, growing it according to its policy if it is
+    /// full.
+    /// # Panics
+    /// Panics if the policy refuses to grow the stack or its capacity is frozen, or if the new
+    /// capacity exceeds `isize::MAX`.
+    #[inline(always)]
+    pub fn push(&mut self, value: T) {
+        if self.try_push(value).is_err() {
+            panic!("growth policy refused to grow the stack, or its capacity is frozen");
+        }
+    }
+
+    /// Appends an element to the top of the stack, growing it according to its policy if it is
+    /// full, or hands the element back if the policy refuses.
+    /// # Errors
+    /// Returns [Full] with the element if the stack is full and its policy refuses to grow it, or
+    /// its capacity is frozen.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    pub fn try_push(&mut self, value: T) -> Result<(), Full<T>> {
+        let len = self.vec.len();
+        if len == self.vec.capacity() {
+            if self.frozen {
+                return Err(Full(value));
+            }
+            let required = len.checked_add(1);
+            let capacity = required.and_then(|required| {
+                self.policy
+                    .grow(len, required, mem::size_of::<T>())
+                    .filter(|&capacity| capacity >= required)
+            });
+            let Some(capacity) = capacity else {
+                return Err(Full(value));
+            };
+            self.vec.reserve_exact(capacity - len);
+        }
+        self.vec.push(value);
+        self.settle();
+        Ok(())
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.vec.pop()?;
+        self.settle();
+        Some(value)
+    }
+
+    /// Clears the stack, popping all values.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the stack, unless it
+    /// leads to the stack shrinking on its own.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.vec.clear();
+        self.settle();
+    }
+
+    /// Counts an operation towards shrinking, and shrinks the stack if it has been below its
+    /// shrink threshold for long enough.
+    #[inline(always)]
+    fn settle(&mut self) {
+        let Some(shrink) = self.shrink.filter(|_| !self.frozen) else {
+            return;
+        };
+        if !shrink.below(self.vec.len(), self.vec.capacity()) {
+            self.low = 0;
+            return;
+        }
+        self.low += 1;
+        if self.low >= shrink.operations {
+            self.low = 0;
+            self.shrink_to_policy();
+        }
+    }
+
+    /// Shrinks the allocation to the capacity the growth policy would grow the length to.
+    #[cold]
+    fn shrink_to_policy(&mut self) {
+        let len = self.vec.len();
+        let capacity = self
+            .policy
+            .grow(len, len, mem::size_of::<T>())
+            .map_or(len, |capacity| capacity.max(len));
+        self.vec.shrink_to(capacity);
+    }
+
+    /// Returns the number of elements in the stack.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns the number of elements the stack can hold without growing.
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns the number of bytes the stack has allocated on the heap, including the spare
+    /// capacity, which is what its growth and shrink policies control.
+    ///
+    /// See [crate::Stack::allocated_bytes].
+    #[inline(always)]
+    pub const fn allocated_bytes(&self) -> usize {
+        self.vec.capacity() * mem::size_of::<T>()
+    }
+
+    /// Consumes the stack, returning its elements as a [crate::Stack].
+    #[inline(always)]
+    pub fn into_inner(self) -> crate::Stack<T> {
+        crate::Stack { vec: self.vec }
+    }
+}
+impl<T, P: GrowthPolicy + Default> Stack<T, P> {
+    /// Constructs a new, empty `Stack<T, P>` growing according to the default policy.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::growth::Stack;
+    /// let mut stack: Stack<i32> = Stack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.capacity(), 4);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::with_policy(P::default())
+    }
+}
+impl<T> crate::Stack<T> {
+    /// Converts the stack into a [Stack] with its capacity frozen, taking over its buffer without
+    /// reallocating, so pushing beyond its capacity fails instead of allocating.
+    ///
+    /// The freeze lives on the policy-driven [Stack], which [crate::Stack] is converted into at
+    /// no cost, so [crate::Stack] stays the size of a [Vec]. See [Stack::freeze_capacity].
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// // Allocating during setup, and never again once processing starts.
+    /// let mut voices = Stack::new();
+    /// voices.extend([0u8; 8]);
+    /// voices.clear();
+    /// let capacity = voices.capacity();
+    /// let mut voices = voices.into_frozen();
+    /// for voice in 0..capacity as u8 {
+    ///     voices.push(voice);
+    /// }
+    /// assert!(voices.try_push(0).is_err());
+    /// assert_eq!(voices.capacity(), capacity);
+    /// ```
+    #[inline(always)]
+    pub fn into_frozen(self) -> Stack<T> {
+        let mut stack = Stack::from(self);
+        stack.freeze_capacity();
+        stack
+    }
+}
+impl<T, P: GrowthPolicy + Default> From<crate::Stack<T>> for Stack<T, P> {
+    /// Wraps a stack, taking over its buffer without reallocating, to grow according to the
+    /// default policy from then on.
+    #[inline(always)]
+    fn from(stack: crate::Stack<T>) -> Self {
+        Self {
+            vec: stack.vec,
+            ..Self::new()
+        }
+    }
+}
+impl<T: PartialEq, P> PartialEq for Stack<T, P> {
+    /// Returns `true` if both stacks hold equal elements.
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.vec == other.vec
+    }
+}
+impl<T: Eq, P> Eq for Stack<T, P> {}
+impl<T: Hash, P> Hash for Stack<T, P> {
+    /// Hashes the elements in the stack.
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.vec.hash(state);
+    }
+}
+impl<T, P> Deref for Stack<T, P> {
+    /// The slice of elements in the stack.
+    type Target = [T];
+
+    /// Returns the elements in the stack, from bottom to top.
+    #[inline(always)]
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+impl<T, P> DerefMut for Stack<T, P> {
+    /// Returns the elements in the stack mutably, from bottom to top.
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.vec
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{fnv, Stack};
+use core::{
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+/// The odd base the hash of each element is weighted by a power of, according to its position.
+const BASE: u64 = 0x9e37_79b9_7f4a_7c15;
+/// The inverse of [BASE] modulo 2^64, found by Newton's method doubling the correct bits from the
+/// three every odd number is its own inverse to.
+const BASE_INVERSE: u64 = {
+    let mut inverse = BASE;
+    let mut step = 0;
+    while step < 5 {
+        inverse = inverse.wrapping_mul(2u64.wrapping_sub(BASE.wrapping_mul(inverse)));
+        step += 1;
+    }
+    inverse
+};
+
+/// A stack which maintains a hash of its contents as it is pushed and popped, so it can be read
+/// in constant time.
+///
+/// The hash is the sum of the hash of each element weighted by a power of a fixed base according
+/// to its position, which a push adds a term to and a pop takes one away from. Stacks with the
+/// same contents share a hash, which doesn't depend on the process or platform, so it suits
+/// keying memoized results on the state of a stack. The stack itself hashes to the same value,
+/// without touching its elements.
+/// # Example
+/// ```
+/// use hay::HashedStack;
+/// let mut a = HashedStack::new();
+/// a.push(1);
+/// a.push(2);
+/// let mut b = HashedStack::new();
+/// b.push(1);
+/// assert_ne!(a.content_hash(), b.content_hash());
+/// b.push(2);
+/// assert_eq!(a.content_hash(), b.content_hash());
+/// a.pop();
+/// b.pop();
+/// assert_eq!(a.content_hash(), b.content_hash());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HashedStack<T> {
+    stack: Stack<T>,
+    hash: u64,
+    power: u64,
+}
+impl<T: Hash> HashedStack<T> {
+    /// Constructs a new, empty `HashedStack<T>`.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::HashedStack;
+    /// let stack: HashedStack<i32> = HashedStack::new();
+    /// assert_eq!(stack.content_hash(), 0);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            stack: Stack::new(),
+            hash: 0,
+            power: 1,
+        }
+    }
+
+    /// Returns the hash of the elements in the stack, in constant time.
+    #[inline(always)]
+    pub const fn content_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns a reference to the top element in the stack.
+    ///
+    /// There is no mutable counterpart, since changing an element would outdate the hash.
+    #[inline(always)]
+    pub const fn top(&self) -> Option<&T> {
+        self.stack.top()
+    }
+
+    /// Appends an element to the top of the stack, adding it to the hash.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::HashedStack;
+    /// let mut stack = HashedStack::new();
+    /// stack.push(1);
+    /// assert_ne!(stack.content_hash(), 0);
+    /// ```
+    #[inline(always)]
+    pub fn push(&mut self, value: T) {
+        self.hash = self
+            .hash
+            .wrapping_add(fnv::hash(&value).wrapping_mul(self.power));
+        self.power = self.power.wrapping_mul(BASE);
+        self.stack.push(value);
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty,
+    /// taking it away from the hash.
+    /// # Example
+    /// ```
+    /// use hay::HashedStack;
+    /// let mut stack = HashedStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.content_hash(), 0);
+    /// ```
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.stack.pop()?;
+        self.power = self.power.wrapping_mul(BASE_INVERSE);
+        self.hash = self
+            .hash
+            .wrapping_sub(fnv::hash(&value).wrapping_mul(self.power));
+        Some(value)
+    }
+
+    /// Clears the stack, popping all values, and resets the hash.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.stack.clear();
+        self.hash = 0;
+        self.power = 1;
+    }
+
+    /// Returns the number of elements in the stack.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Consumes the wrapper, returning the underlying stack.
+    #[inline(always)]
+    pub fn into_inner(self) -> Stack<T> {
+        self.stack
+    }
+}
+impl<T: Hash> Default for HashedStack<T> {
+    /// Constructs a new, empty `HashedStack<T>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Deref for HashedStack<T> {
+    /// The resulting type when dereferencing `HashedStack<T>`.
+    type Target = [T];
+
+    /// Dereferences a `HashedStack<T>`.
+    fn deref(&self) -> &Self::Target {
+        &self.stack
+    }
+}
+impl<T> Hash for HashedStack<T> {
+    /// Hashes the stack by its content hash, without touching its elements.
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+impl<T: Hash> Extend<T> for HashedStack<T> {
+    /// Pushes a collection of values onto a stack, adding each to the hash.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+impl<T: Hash> From<Stack<T>> for HashedStack<T> {
+    /// Hashes the elements of a stack, from the bottom up.
+    /// # Example
+    /// ```
+    /// use hay::{HashedStack, Stack};
+    /// let mut stack = Stack::new();
+    /// stack.push(1);
+    /// let mut hashed = HashedStack::new();
+    /// hashed.push(1);
+    /// assert_eq!(HashedStack::from(stack).content_hash(), hashed.content_hash());
+    /// ```
+    fn from(stack: Stack<T>) -> Self {
+        let mut hashed = Self::new();
+        hashed.stack.vec.reserve_exact(stack.len());
+        hashed.extend(stack.vec);
+        hashed
+    }
+}
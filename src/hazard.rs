@@ -0,0 +1,175 @@
+//! Hazard pointers, for freeing the nodes of lock-free structures while other threads may still
+//! be reading them.
+//!
+//! Before dereferencing a shared node, a thread publishes its address in a hazard pointer.
+//! Removed nodes are retired rather than freed, and are only freed once a scan of every hazard
+//! pointer shows that no thread is protecting them.
+use crate::sync::{const_fn, AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+use alloc::{boxed::Box, vec::Vec};
+use core::{cell::UnsafeCell, ptr};
+
+/// The number of retired nodes a record holds on to, on top of two per record in the domain,
+/// before scanning for nodes to free.
+const RETIRE_SLACK: usize = 8;
+
+/// A hazard pointer, along with the nodes its owner has retired.
+struct Record<N> {
+    hazard: AtomicPtr<N>,
+    /// Whether a [Guard] currently owns the record.
+    active: AtomicBool,
+    /// The next record in the domain, which never changes once the record is published.
+    next: *mut Record<N>,
+    /// Nodes retired while owning this record, only ever touched by the owner.
+    retired: UnsafeCell<Vec<*mut N>>,
+}
+
+/// A collection of hazard pointers protecting the nodes of one structure.
+pub(crate) struct Domain<N> {
+    records: AtomicPtr<Record<N>>,
+    record_count: AtomicUsize,
+}
+impl<N> Domain<N> {
+    const_fn! {
+        /// Constructs a new domain without any hazard pointers.
+        #[inline(always)]
+        pub(crate) fn new() -> Self {
+            Self {
+                records: AtomicPtr::new(ptr::null_mut()),
+                record_count: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    /// Iterates over every record in the domain.
+    fn records(&self) -> impl Iterator<Item = &Record<N>> {
+        let mut record = self.records.load(Ordering::Acquire);
+        core::iter::from_fn(move || {
+            // SAFETY: Records are only freed when the domain is dropped.
+            let current = unsafe { record.as_ref()? };
+            record = current.next;
+            Some(current)
+        })
+    }
+
+    /// Takes ownership of a hazard pointer, allocating a new one if every record is in use.
+    pub(crate) fn acquire(&self) -> Guard<'_, N> {
+        let idle = self.records().find(|record| {
+            record
+                .active
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        });
+        if let Some(record) = idle {
+            return Guard {
+                domain: self,
+                record,
+            };
+        }
+        let record = Box::into_raw(Box::new(Record {
+            hazard: AtomicPtr::new(ptr::null_mut()),
+            active: AtomicBool::new(true),
+            next: ptr::null_mut(),
+            retired: UnsafeCell::new(Vec::new()),
+        }));
+        let mut head = self.records.load(Ordering::Relaxed);
+        loop {
+            // SAFETY: The record isn't shared until the exchange below succeeds.
+            unsafe { (*record).next = head };
+            match self.records.compare_exchange_weak(
+                head,
+                record,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(current) => head = current,
+            }
+        }
+        self.record_count.fetch_add(1, Ordering::Relaxed);
+        Guard {
+            domain: self,
+            // SAFETY: Records are only freed when the domain is dropped.
+            record: unsafe { &*record },
+        }
+    }
+}
+impl<N> Drop for Domain<N> {
+    /// Frees every record along with the nodes retired to it.
+    fn drop(&mut self) {
+        let mut record = self.records.load(Ordering::Relaxed);
+        while !record.is_null() {
+            // SAFETY: The domain is exclusively borrowed, so no guards are alive.
+            let mut owned = unsafe { Box::from_raw(record) };
+            record = owned.next;
+            for &node in owned.retired.get_mut().iter() {
+                // SAFETY: Retired nodes are no longer reachable, and nothing can protect them now.
+                drop(unsafe { Box::from_raw(node) });
+            }
+        }
+    }
+}
+
+/// Exclusive ownership of one hazard pointer in a [Domain].
+pub(crate) struct Guard<'a, N> {
+    domain: &'a Domain<N>,
+    record: &'a Record<N>,
+}
+impl<N> Guard<'_, N> {
+    /// Loads the pointer in `source` and protects it from being freed until the next call to
+    /// `protect`, `clear`, or until the guard is dropped.
+    pub(crate) fn protect(&self, source: &AtomicPtr<N>) -> *mut N {
+        let mut node = source.load(Ordering::Relaxed);
+        loop {
+            self.record.hazard.store(node, Ordering::SeqCst);
+            // Making sure the node wasn't removed before the hazard pointer was published.
+            let current = source.load(Ordering::SeqCst);
+            if current == node {
+                return node;
+            }
+            node = current;
+        }
+    }
+
+    /// Stops protecting the current node.
+    #[inline(always)]
+    pub(crate) fn clear(&self) {
+        self.record.hazard.store(ptr::null_mut(), Ordering::Release);
+    }
+
+    /// Hands a node over to be freed once no hazard pointer protects it.
+    /// # Safety
+    /// The node must have been allocated with [Box], and must no longer be reachable from the
+    /// structure. Any value inside it which needs dropping must already have been moved out,
+    /// unless it is meant to be dropped along with the node.
+    pub(crate) unsafe fn retire(&self, node: *mut N) {
+        // SAFETY: Only the owner of the record touches its retired nodes.
+        let retired = unsafe { &mut *self.record.retired.get() };
+        retired.push(node);
+        let threshold = 2 * self.domain.record_count.load(Ordering::Relaxed) + RETIRE_SLACK;
+        if retired.len() < threshold {
+            return;
+        }
+        let mut hazards: Vec<*mut N> = self
+            .domain
+            .records()
+            .map(|record| record.hazard.load(Ordering::SeqCst))
+            .filter(|hazard| !hazard.is_null())
+            .collect();
+        hazards.sort_unstable();
+        retired.retain(|&node| {
+            if hazards.binary_search(&node).is_ok() {
+                return true;
+            }
+            // SAFETY: The node is unreachable and no hazard pointer protects it.
+            drop(unsafe { Box::from_raw(node) });
+            false
+        });
+    }
+}
+impl<N> Drop for Guard<'_, N> {
+    /// Clears the hazard pointer and hands the record back to the domain.
+    fn drop(&mut self) {
+        self.clear();
+        self.record.active.store(false, Ordering::Release);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{LifoCache, Stack};
+
+/// A browser-style navigation history, keeping the entries behind and ahead of the current one
+/// on two stacks.
+///
+/// Going back moves the current entry onto the forward stack, and going forward moves it back,
+/// while visiting a new entry clears the forward stack. Once the history holds its limit of
+/// entries, visiting evicts the oldest.
+/// # Example
+/// ```
+/// use hay::History;
+/// let mut history = History::new();
+/// history.visit("home");
+/// history.visit("search");
+/// history.visit("result");
+/// assert_eq!(history.back(), Some(&"search"));
+/// assert_eq!(history.back(), Some(&"home"));
+/// assert_eq!(history.forward(), Some(&"search"));
+/// // Visiting from the middle replaces everything ahead.
+/// history.visit("settings");
+/// assert_eq!(history.forward(), None);
+/// assert_eq!(history.current(), Some(&"settings"));
+/// assert_eq!(history.len(), 3);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct History<T> {
+    back: LifoCache<T>,
+    current: Option<T>,
+    forward: Stack<T>,
+}
+impl<T> History<T> {
+    /// Constructs a new, empty `History<T>` which keeps every entry visited.
+    ///
+    /// The history will not allocate until entries are visited.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self::with_limit(usize::MAX)
+    }
+
+    /// Constructs a new, empty `History<T>` which holds at most `limit` entries, including the
+    /// current one.
+    /// # Panics
+    /// Panics if `limit` is zero.
+    /// # Example
+    /// ```
+    /// use hay::History;
+    /// let mut history = History::with_limit(2);
+    /// history.visit(1);
+    /// history.visit(2);
+    /// history.visit(3);
+    /// assert_eq!(history.back(), Some(&2));
+    /// assert_eq!(history.back(), None);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn with_limit(limit: usize) -> Self {
+        assert!(limit != 0, "a history must hold at least the current entry");
+        Self {
+            back: LifoCache::new(limit - 1),
+            current: None,
+            forward: Stack::new(),
+        }
+    }
+
+    /// Returns the maximum number of entries the history holds, including the current one.
+    #[inline(always)]
+    pub const fn limit(&self) -> usize {
+        self.back.capacity().saturating_add(1)
+    }
+
+    /// Makes a new entry the current one, moving the previous one behind it and dropping every
+    /// entry ahead of it.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    pub fn visit(&mut self, entry: T) {
+        self.forward.clear();
+        if let Some(previous) = self.current.replace(entry) {
+            self.back.push(previous);
+        }
+    }
+
+    /// Goes back to the entry before the current one and returns it, or returns [None] and stays
+    /// put if there is none.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    pub fn back(&mut self) -> Option<&T> {
+        let previous = self.back.pop()?;
+        if let Some(current) = self.current.replace(previous) {
+            self.forward.push(current);
+        }
+        self.current.as_ref()
+    }
+
+    /// Goes forward to the entry after the current one and returns it, or returns [None] and
+    /// stays put if there is none.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    pub fn forward(&mut self) -> Option<&T> {
+        let next = self.forward.pop()?;
+        if let Some(current) = self.current.replace(next) {
+            self.back.push(current);
+        }
+        self.current.as_ref()
+    }
+
+    /// Returns a reference to the current entry, or [None] if nothing has been visited.
+    #[inline(always)]
+    pub const fn current(&self) -> Option<&T> {
+        self.current.as_ref()
+    }
+
+    /// Returns a mutable reference to the current entry, or [None] if nothing has been visited.
+    #[inline(always)]
+    pub const fn current_mut(&mut self) -> Option<&mut T> {
+        self.current.as_mut()
+    }
+
+    /// Returns `true` if there is an entry before the current one.
+    #[inline(always)]
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    /// Returns `true` if there is an entry after the current one.
+    #[inline(always)]
+    pub const fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+
+    /// Returns an iterator over the entries before the current one, from the most recent.
+    #[inline(always)]
+    pub fn back_entries(&self) -> impl ExactSizeIterator<Item = &T> {
+        self.back.iter().rev()
+    }
+
+    /// Returns an iterator over the entries after the current one, from the nearest.
+    #[inline(always)]
+    pub fn forward_entries(&self) -> impl ExactSizeIterator<Item = &T> {
+        self.forward.iter().rev()
+    }
+
+    /// Returns the number of entries in the history, including the current one.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.back.len() + usize::from(self.current.is_some()) + self.forward.len()
+    }
+
+    /// Returns `true` if nothing has been visited.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.current.is_none()
+    }
+
+    /// Drops every entry, including the current one.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.back.clear();
+        self.current = None;
+        self.forward.clear();
+    }
+}
+impl<T> Default for History<T> {
+    /// Constructs a new, empty `History<T>` which keeps every entry visited.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
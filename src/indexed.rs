@@ -0,0 +1,294 @@
+use crate::{invariant::check_invariants, Stack};
+use alloc::vec::Vec;
+use core::{borrow::Borrow, hash::Hash, ops::Deref};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+
+/// A stack that keeps track of how far down each of its elements is.
+///
+/// Every element is indexed by a key (the element itself by default, or a key extracted by a
+/// closure), so asking for the depth of the topmost element with a given key is O(1).
+///
+/// Depths are measured from the top of the stack, with the top element at depth `0`.
+/// # Example
+/// ```
+/// use hay::IndexedStack;
+/// let mut scopes = IndexedStack::new();
+/// scopes.push("global");
+/// scopes.push("fn main");
+/// scopes.push("loop");
+/// assert_eq!(scopes.depth_of("loop"), Some(0));
+/// assert_eq!(scopes.depth_of("global"), Some(2));
+/// scopes.pop();
+/// assert_eq!(scopes.depth_of("loop"), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct IndexedStack<T, K = T, F = fn(&T) -> T> {
+    stack: Stack<T>,
+    index: HashMap<K, Vec<usize>>,
+    key: F,
+}
+impl<T: Clone + Hash + Eq> IndexedStack<T> {
+    /// Constructs a new, empty `IndexedStack<T>` keyed by the elements themselves.
+    /// # Example
+    /// ```
+    /// use hay::IndexedStack;
+    /// let mut stack: IndexedStack<i32> = IndexedStack::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::with_key(T::clone)
+    }
+}
+impl<T: Clone + Hash + Eq> Default for IndexedStack<T> {
+    /// Constructs a new, empty `IndexedStack<T>` keyed by the elements themselves.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, K: Hash + Eq, F: Fn(&T) -> K> IndexedStack<T, K, F> {
+    /// Constructs a new, empty `IndexedStack` which indexes elements by the key `key` extracts.
+    /// # Example
+    /// ```
+    /// use hay::IndexedStack;
+    /// let mut stack = IndexedStack::with_key(|binding: &(&str, i32)| binding.0);
+    /// stack.push(("x", 1));
+    /// stack.push(("y", 2));
+    /// assert_eq!(stack.depth_of("x"), Some(1));
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub fn with_key(key: F) -> Self {
+        Self {
+            stack: Stack::new(),
+            index: HashMap::new(),
+            key,
+        }
+    }
+
+    /// Appends an element to the top of the stack.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::IndexedStack;
+    /// let mut stack = IndexedStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.depth_of(&1), Some(0));
+    /// ```
+    pub fn push(&mut self, value: T) {
+        let key = (self.key)(&value);
+        self.index.entry(key).or_default().push(self.stack.len());
+        self.stack.push(value);
+        check_invariants!(self);
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::IndexedStack;
+    /// let mut stack = IndexedStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert!(!stack.contains(&1));
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.stack.pop()?;
+        let key = (self.key)(&value);
+        // Forgetting the popped position, along with the key if this was its last occurrence.
+        if let Some(positions) = self.index.get_mut(&key) {
+            positions.pop();
+            if positions.is_empty() {
+                self.index.remove(&key);
+            }
+        }
+        check_invariants!(self);
+        Some(value)
+    }
+
+    /// Returns the depth of the topmost element with the given key, or [None] if there is none.
+    ///
+    /// The top element is at depth `0`.
+    /// # Example
+    /// ```
+    /// use hay::IndexedStack;
+    /// let mut stack = IndexedStack::new();
+    /// stack.push('a');
+    /// stack.push('b');
+    /// stack.push('a');
+    /// assert_eq!(stack.depth_of(&'a'), Some(0));
+    /// assert_eq!(stack.depth_of(&'b'), Some(1));
+    /// assert_eq!(stack.depth_of(&'c'), None);
+    /// ```
+    pub fn depth_of<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        let position = self.index.get(key)?.last()?;
+        Some(self.stack.len() - 1 - position)
+    }
+
+    /// Returns `true` if the stack contains an element with the given key.
+    /// # Example
+    /// ```
+    /// use hay::IndexedStack;
+    /// let mut stack = IndexedStack::new();
+    /// stack.push(1);
+    /// assert!(stack.contains(&1));
+    /// assert!(!stack.contains(&2));
+    /// ```
+    #[inline(always)]
+    pub fn contains<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.index.contains_key(key)
+    }
+
+    /// Returns the number of elements on the stack with the given key.
+    /// # Example
+    /// ```
+    /// use hay::IndexedStack;
+    /// let mut stack = IndexedStack::new();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// stack.push(1);
+    /// assert_eq!(stack.count(&1), 2);
+    /// assert_eq!(stack.count(&3), 0);
+    /// ```
+    #[inline(always)]
+    pub fn count<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        self.index.get(key).map_or(0, Vec::len)
+    }
+
+    /// Clears the stack, popping all values.
+    /// # Example
+    /// ```
+    /// use hay::IndexedStack;
+    /// let mut stack = IndexedStack::new();
+    /// stack.push(1);
+    /// stack.clear();
+    /// assert_eq!(stack.depth_of(&1), None);
+    /// ```
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.stack.clear();
+        self.index.clear();
+        check_invariants!(self);
+    }
+
+    /// Panics if the positions in the index don't cover every element exactly once, in order and
+    /// under its own key.
+    #[cfg(feature = "strict-debug")]
+    #[track_caller]
+    fn check_invariants(&self) {
+        let mut positions = 0;
+        for (key, indices) in &self.index {
+            assert!(
+                !indices.is_empty(),
+                "`IndexedStack` keeps a key with no positions"
+            );
+            for (i, &position) in indices.iter().enumerate() {
+                assert!(
+                    indices.get(i + 1).is_none_or(|&next| position < next),
+                    "`IndexedStack` positions of a key are out of order at {position}",
+                );
+                let element = self.stack.get(position).unwrap_or_else(|| {
+                    panic!(
+                        "`IndexedStack` indexes position {position}, beyond its length of {}",
+                        self.stack.len(),
+                    )
+                });
+                assert!(
+                    (self.key)(element) == *key,
+                    "`IndexedStack` indexes position {position} under the wrong key",
+                );
+            }
+            positions += indices.len();
+        }
+        assert_eq!(
+            positions,
+            self.stack.len(),
+            "`IndexedStack` indexes {positions} positions, but holds {} elements",
+            self.stack.len(),
+        );
+    }
+}
+impl<T, K, F> IndexedStack<T, K, F> {
+    /// Returns a reference to the top element in the stack.
+    /// # Example
+    /// ```
+    /// use hay::IndexedStack;
+    /// let mut stack = IndexedStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn top(&self) -> Option<&T> {
+        self.stack.top()
+    }
+
+    /// Returns the number of elements on the stack.
+    /// # Example
+    /// ```
+    /// use hay::IndexedStack;
+    /// let mut stack = IndexedStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    /// # Example
+    /// ```
+    /// use hay::IndexedStack;
+    /// let stack: IndexedStack<i32> = IndexedStack::new();
+    /// assert!(stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+}
+impl<T, K, F> Deref for IndexedStack<T, K, F> {
+    /// The resulting type when dereferencing `IndexedStack<T, K, F>`.
+    type Target = [T];
+
+    /// Dereferences an `IndexedStack<T, K, F>`.
+    fn deref(&self) -> &Self::Target {
+        &self.stack
+    }
+}
+impl<T, K: Hash + Eq, F: Fn(&T) -> K> Extend<T> for IndexedStack<T, K, F> {
+    /// Pushes a collection of values onto a stack.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl<T: Serialize, K, F> Serialize for IndexedStack<T, K, F> {
+    /// Serializes a stack as a sequence, from its bottom to its top.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.stack.serialize(serializer)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de> + Clone + Hash + Eq> Deserialize<'de> for IndexedStack<T> {
+    /// Deserializes a stack from a sequence, from its bottom to its top, rebuilding its index.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut stack = Self::new();
+        stack.extend(Vec::<T>::deserialize(deserializer)?);
+        Ok(stack)
+    }
+}
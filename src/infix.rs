@@ -0,0 +1,263 @@
+//! Converts expressions in infix notation to the postfix tokens [crate::eval] evaluates, with the
+//! shunting-yard algorithm.
+//!
+//! Operators are ordered by their [Precedence], parentheses group subexpressions, and functions
+//! take their arguments in parentheses separated by commas.
+//! # Example
+//! ```
+//! use hay::{
+//!     eval::{self, Arithmetic},
+//!     infix::{self, Infix},
+//! };
+//! // -(1 + 2) * 4
+//! let tokens = [
+//!     Infix::Prefix(Arithmetic::Neg),
+//!     Infix::Open,
+//!     Infix::Operand(1),
+//!     Infix::Operator(Arithmetic::Add),
+//!     Infix::Operand(2),
+//!     Infix::Close,
+//!     Infix::Operator(Arithmetic::Mul),
+//!     Infix::Operand(4),
+//! ];
+//! let postfix = infix::to_postfix(tokens).unwrap();
+//! assert_eq!(eval::eval(postfix), Ok(-12));
+//! ```
+use crate::{
+    eval::{Arithmetic, Token},
+    Stack,
+};
+use alloc::vec::Vec;
+use core::{
+    cmp::Ordering,
+    fmt::{self, Display},
+};
+
+/// A token of an expression in infix notation.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Infix<V, O> {
+    /// A value.
+    Operand(V),
+    /// An operator written between its operands, or after its only one.
+    Operator(O),
+    /// An operator written before its only operand, such as negation.
+    Prefix(O),
+    /// A function, applied to the arguments in the parentheses following it.
+    Function(O),
+    /// An opening parenthesis.
+    Open,
+    /// A closing parenthesis.
+    Close,
+    /// A comma, separating the arguments of a function.
+    Comma,
+}
+
+/// Which way operators of the same precedence group.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum Associativity {
+    /// `a - b - c` is `(a - b) - c`.
+    #[default]
+    Left,
+    /// `a ^ b ^ c` is `a ^ (b ^ c)`.
+    Right,
+}
+
+/// How tightly an operator binds its operands, relative to others.
+/// # Example
+/// ```
+/// use hay::infix::{Associativity, Precedence};
+/// enum Op {
+///     Or,
+///     And,
+///     Pow,
+/// }
+/// impl Precedence for Op {
+///     fn precedence(&self) -> u8 {
+///         match self {
+///             Op::Or => 1,
+///             Op::And => 2,
+///             Op::Pow => 3,
+///         }
+///     }
+///
+///     fn associativity(&self) -> Associativity {
+///         match self {
+///             Op::Pow => Associativity::Right,
+///             _ => Associativity::Left,
+///         }
+///     }
+/// }
+/// ```
+pub trait Precedence {
+    /// Returns the precedence of the operator, where higher precedences bind tighter.
+    fn precedence(&self) -> u8;
+
+    /// Returns which way operators of the same precedence group, which is left by default.
+    #[inline(always)]
+    fn associativity(&self) -> Associativity {
+        Associativity::Left
+    }
+}
+impl Precedence for Arithmetic {
+    /// Returns one for addition and subtraction, two for multiplication, division, and
+    /// remainder, and three for negation.
+    #[inline(always)]
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Add | Self::Sub => 1,
+            Self::Mul | Self::Div | Self::Rem => 2,
+            Self::Neg => 3,
+        }
+    }
+}
+
+/// The error returned when an infix expression's parentheses or commas are misplaced.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// An opening parenthesis was never closed.
+    UnclosedOpen {
+        /// The index of the parenthesis's token.
+        position: usize,
+    },
+    /// A closing parenthesis had no opening one.
+    UnmatchedClose {
+        /// The index of the parenthesis's token.
+        position: usize,
+    },
+    /// A comma was outside of any parentheses.
+    MisplacedComma {
+        /// The index of the comma's token.
+        position: usize,
+    },
+}
+impl Display for Error {
+    /// Formats the error.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnclosedOpen { position } => write!(f, "parenthesis at {position} is unclosed"),
+            Self::UnmatchedClose { position } => {
+                write!(f, "parenthesis at {position} closes nothing")
+            }
+            Self::MisplacedComma { position } => {
+                write!(f, "comma at {position} is outside of parentheses")
+            }
+        }
+    }
+}
+impl core::error::Error for Error {}
+
+/// An entry on the stack of operators waiting for their operands.
+enum Pending<O> {
+    /// An operator, including a prefix one.
+    Operator(O),
+    /// A function, waiting for its parentheses to close.
+    Function(O),
+    /// An opening parenthesis, at the given index.
+    Open(usize),
+}
+
+/// Converts an infix expression to postfix tokens.
+///
+/// Only the placement of parentheses and commas is checked, so a misplaced operand or operator
+/// shows up when the postfix tokens are evaluated.
+/// # Errors
+/// Returns an [Error] if a parenthesis is unmatched or a comma is outside of parentheses.
+/// # Example
+/// ```
+/// use hay::{
+///     eval::{Arithmetic, Token},
+///     infix::{self, Error, Infix},
+/// };
+/// let postfix = infix::to_postfix([
+///     Infix::Operand(1),
+///     Infix::Operator(Arithmetic::Sub),
+///     Infix::Operand(2),
+///     Infix::Operator(Arithmetic::Sub),
+///     Infix::Operand(3),
+/// ]);
+/// assert_eq!(
+///     postfix.unwrap(),
+///     [
+///         Token::Operand(1),
+///         Token::Operand(2),
+///         Token::Operator(Arithmetic::Sub),
+///         Token::Operand(3),
+///         Token::Operator(Arithmetic::Sub),
+///     ],
+/// );
+/// assert_eq!(
+///     infix::to_postfix([Infix::<i32, Arithmetic>::Open, Infix::Operand(1)]),
+///     Err(Error::UnclosedOpen { position: 0 }),
+/// );
+/// ```
+pub fn to_postfix<V, O: Precedence>(
+    tokens: impl IntoIterator<Item = Infix<V, O>>,
+) -> Result<Vec<Token<V, O>>, Error> {
+    let mut output = Vec::new();
+    let mut pending = Stack::new();
+    for (position, token) in tokens.into_iter().enumerate() {
+        match token {
+            Infix::Operand(value) => output.push(Token::Operand(value)),
+            Infix::Operator(operator) => {
+                while let Some(Pending::Operator(top)) = pending.vec.pop_if(
+                    |top| matches!(top, Pending::Operator(top) if binds_before(top, &operator)),
+                ) {
+                    output.push(Token::Operator(top));
+                }
+                pending.push(Pending::Operator(operator));
+            }
+            // Prefix operators have no left operand, so nothing before them can be waiting for it.
+            Infix::Prefix(operator) => pending.push(Pending::Operator(operator)),
+            Infix::Function(function) => pending.push(Pending::Function(function)),
+            Infix::Open => pending.push(Pending::Open(position)),
+            Infix::Close => {
+                if !pop_to_open(&mut pending, &mut output) {
+                    return Err(Error::UnmatchedClose { position });
+                }
+                pending.pop();
+                if let Some(Pending::Function(function)) = pending
+                    .vec
+                    .pop_if(|top| matches!(top, Pending::Function(_)))
+                {
+                    output.push(Token::Operator(function));
+                }
+            }
+            Infix::Comma => {
+                if !pop_to_open(&mut pending, &mut output) {
+                    return Err(Error::MisplacedComma { position });
+                }
+            }
+        }
+    }
+    while let Some(entry) = pending.pop() {
+        match entry {
+            Pending::Operator(operator) | Pending::Function(operator) => {
+                output.push(Token::Operator(operator));
+            }
+            Pending::Open(position) => return Err(Error::UnclosedOpen { position }),
+        }
+    }
+    Ok(output)
+}
+
+/// Returns `true` if `top`, waiting on the stack, takes its operands before `operator` does.
+#[inline(always)]
+fn binds_before<O: Precedence>(top: &O, operator: &O) -> bool {
+    match top.precedence().cmp(&operator.precedence()) {
+        Ordering::Greater => true,
+        Ordering::Equal => operator.associativity() == Associativity::Left,
+        Ordering::Less => false,
+    }
+}
+
+/// Moves operators from `pending` to `output` until an opening parenthesis is on top, returning
+/// `false` if there is none.
+fn pop_to_open<V, O>(pending: &mut Stack<Pending<O>>, output: &mut Vec<Token<V, O>>) -> bool {
+    while let Some(Pending::Operator(operator) | Pending::Function(operator)) =
+        pending.vec.pop_if(|top| !matches!(top, Pending::Open(_)))
+    {
+        output.push(Token::Operator(operator));
+    }
+    !pending.is_empty()
+}
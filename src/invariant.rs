@@ -0,0 +1,12 @@
+//! Validation of the stacks' internal invariants after every mutating operation, enabled by the
+//! `strict-debug` feature to catch bugs and misuse early.
+
+/// Validates the invariants of a stack with its `check_invariants` method when the
+/// `strict-debug` feature is enabled, and does nothing otherwise.
+macro_rules! check_invariants {
+    ($stack:expr) => {
+        #[cfg(feature = "strict-debug")]
+        $stack.check_invariants();
+    };
+}
+pub(crate) use check_invariants;
@@ -0,0 +1,320 @@
+use crate::Stack;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, Error, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+/// The magic bytes opening a journal.
+const MAGIC: [u8; 4] = *b"hayJ";
+/// The version of the journal format, bumped whenever it changes incompatibly.
+const VERSION: u8 = 1;
+/// The size of the header, holding the magic bytes and the version.
+const HEADER: u64 = 5;
+/// The tag of a record pushing the postcard payload following its little-endian `u32` length.
+const PUSH: u8 = 0;
+/// The tag of a record popping the top element.
+const POP: u8 = 1;
+/// The tag of a record clearing the stack.
+const CLEAR: u8 = 2;
+/// The number of records beyond the length of the stack the journal may hold before it is
+/// compacted.
+const SLACK: usize = 1024;
+
+/// A stack which records every operation in an append-only journal file, so its state can be
+/// rebuilt after a crash.
+///
+/// Each operation is appended to the journal before it is applied, and the journal is compacted
+/// down to one record per element once it holds many more. A record torn by a crash is dropped
+/// when the journal is reopened, so the stack comes back as of the last complete operation.
+///
+/// Used as a work queue, processing the [JournalStack::top] element before popping it gives
+/// at-least-once processing, since a crash in between leaves the element on the stack.
+/// # Example
+/// ```
+/// use hay::JournalStack;
+/// let path = std::env::temp_dir().join("hay-journal-example");
+/// # let _ = std::fs::remove_file(&path);
+/// let mut stack = JournalStack::open(&path)?;
+/// stack.push(1)?;
+/// stack.push(2)?;
+/// stack.pop()?;
+/// drop(stack);
+/// let mut stack = JournalStack::<i32>::open(&path)?;
+/// assert_eq!(stack.top(), Some(&1));
+/// # std::fs::remove_file(&path)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct JournalStack<T> {
+    stack: Stack<T>,
+    file: File,
+    path: PathBuf,
+    end: u64,
+    records: usize,
+}
+impl<T: Serialize + DeserializeOwned> JournalStack<T> {
+    /// Opens the stack journaled in the file at `path`, replaying its records, or creates an
+    /// empty one if the file doesn't exist or is empty.
+    /// # Errors
+    /// Returns an error if the file can't be opened, read, or repaired, or one of kind
+    /// [ErrorKind::InvalidData] if it isn't a journal or holds a record which can't be replayed.
+    /// # Example
+    /// ```
+    /// use hay::JournalStack;
+    /// use std::io::Write;
+    /// let path = std::env::temp_dir().join("hay-journal-open");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let mut stack = JournalStack::open(&path)?;
+    /// stack.push(1)?;
+    /// drop(stack);
+    /// // Tearing a push record, as if the process crashed while writing it.
+    /// std::fs::OpenOptions::new().append(true).open(&path)?.write_all(&[0, 1])?;
+    /// let mut stack = JournalStack::<i32>::open(&path)?;
+    /// assert_eq!(stack.as_slice(), [1]);
+    /// stack.push(2)?;
+    /// assert_eq!(JournalStack::<i32>::open(&path)?.as_slice(), [1, 2]);
+    /// # std::fs::remove_file(&path)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        let mut stack = Self {
+            stack: Stack::new(),
+            file,
+            path,
+            end: HEADER,
+            records: 0,
+        };
+        if bytes.is_empty() {
+            stack.file.write_all(&header())?;
+            return Ok(stack);
+        }
+        if bytes.len() < HEADER as usize || bytes[..4] != MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "not a journal"));
+        }
+        if bytes[4] != VERSION {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "unsupported journal version",
+            ));
+        }
+        stack.replay(&bytes[HEADER as usize..])?;
+        if stack.end != bytes.len() as u64 {
+            // Dropping the record torn by a crash, so new ones follow the last complete one.
+            stack.file.set_len(stack.end)?;
+        }
+        stack.file.seek(SeekFrom::Start(stack.end))?;
+        Ok(stack)
+    }
+
+    /// Returns a reference to the top element in the stack.
+    #[inline(always)]
+    pub fn top(&self) -> Option<&T> {
+        self.stack.top()
+    }
+
+    /// Appends an element to the top of the stack, journaling it first.
+    /// # Errors
+    /// Returns an error if the record can't be written, in which case the stack is left
+    /// unchanged.
+    pub fn push(&mut self, value: T) -> io::Result<()> {
+        let payload = postcard::to_allocvec(&value).map_err(Error::other)?;
+        let len = u32::try_from(payload.len())
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "element is too large to journal"))?;
+        let mut record = Vec::with_capacity(5 + payload.len());
+        record.push(PUSH);
+        record.extend_from_slice(&len.to_le_bytes());
+        record.extend_from_slice(&payload);
+        self.append(&record)?;
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty,
+    /// journaling it first.
+    /// # Errors
+    /// Returns an error if the record can't be written, in which case the stack is left
+    /// unchanged.
+    pub fn pop(&mut self) -> io::Result<Option<T>> {
+        if self.stack.is_empty() {
+            return Ok(None);
+        }
+        self.append(&[POP])?;
+        Ok(self.stack.pop())
+    }
+
+    /// Clears the stack, popping all values, and journaling it first.
+    /// # Errors
+    /// Returns an error if the record can't be written, in which case the stack is left
+    /// unchanged.
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.append(&[CLEAR])?;
+        self.stack.clear();
+        Ok(())
+    }
+
+    /// Returns the number of elements in the stack.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Extracts a slice of the elements, from the bottom of the stack up.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        &self.stack
+    }
+
+    /// Blocks until every record written so far has reached the disk, so they survive losing
+    /// power as well as the process crashing.
+    /// # Errors
+    /// Returns an error if the records can't be synced.
+    #[inline(always)]
+    pub fn sync(&self) -> io::Result<()> {
+        self.file.sync_data()
+    }
+
+    /// Rewrites the journal to hold a single push record per element, atomically replacing the
+    /// old one.
+    ///
+    /// This happens automatically once the journal holds many more records than elements.
+    /// # Errors
+    /// Returns an error if the new journal can't be written or moved into place, in which case
+    /// the old one is kept.
+    /// # Example
+    /// ```
+    /// use hay::JournalStack;
+    /// let path = std::env::temp_dir().join("hay-journal-compact");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let mut stack = JournalStack::open(&path)?;
+    /// for value in 0..100 {
+    ///     stack.push(value)?;
+    ///     stack.pop()?;
+    /// }
+    /// stack.push(7)?;
+    /// let before = std::fs::metadata(&path)?.len();
+    /// stack.compact()?;
+    /// assert!(std::fs::metadata(&path)?.len() < before);
+    /// drop(stack);
+    /// assert_eq!(JournalStack::<i32>::open(&path)?.as_slice(), [7]);
+    /// # std::fs::remove_file(&path)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn compact(&mut self) -> io::Result<()> {
+        let mut bytes = header().to_vec();
+        for value in self.stack.iter() {
+            let payload = postcard::to_allocvec(value).map_err(Error::other)?;
+            bytes.push(PUSH);
+            // Every element was journaled already, so its payload fits in a `u32`.
+            bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&payload);
+        }
+        let mut name = OsString::from(self.path.as_os_str());
+        name.push(".compact");
+        let temporary = PathBuf::from(name);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&temporary)?;
+        let result = file
+            .write_all(&bytes)
+            .and_then(|()| file.sync_all())
+            .and_then(|()| fs::rename(&temporary, &self.path));
+        if let Err(error) = result {
+            let _ = fs::remove_file(&temporary);
+            return Err(error);
+        }
+        self.file = file;
+        self.end = bytes.len() as u64;
+        self.records = self.stack.len();
+        Ok(())
+    }
+
+    /// Replays the records in `bytes` onto the stack, stopping at a torn record.
+    fn replay(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message);
+        let mut position = 0;
+        while let Some(&tag) = bytes.get(position) {
+            let size = match tag {
+                PUSH => {
+                    let Some(len) = bytes.get(position + 1..position + 5) else {
+                        break;
+                    };
+                    let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+                    let Some(payload) = bytes.get(position + 5..position + 5 + len) else {
+                        break;
+                    };
+                    let value = postcard::from_bytes(payload)
+                        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+                    self.stack.push(value);
+                    5 + len
+                }
+                POP => {
+                    self.stack
+                        .pop()
+                        .ok_or_else(|| invalid("journal pops an empty stack"))?;
+                    1
+                }
+                CLEAR => {
+                    self.stack.clear();
+                    1
+                }
+                _ => return Err(invalid("unknown journal record")),
+            };
+            position += size;
+            self.records += 1;
+        }
+        self.end = HEADER + position as u64;
+        Ok(())
+    }
+
+    /// Appends a record to the journal, compacting it first if it has grown too long.
+    fn append(&mut self, record: &[u8]) -> io::Result<()> {
+        if self.records > self.stack.len().saturating_mul(2).saturating_add(SLACK) {
+            self.compact()?;
+        }
+        if let Err(error) = self.file.write_all(record) {
+            // Dropping the partial record, so the journal only holds whole ones.
+            let _ = self
+                .file
+                .set_len(self.end)
+                .and_then(|()| self.file.seek(SeekFrom::Start(self.end)));
+            return Err(error);
+        }
+        self.end += record.len() as u64;
+        self.records += 1;
+        Ok(())
+    }
+}
+impl<T: Debug> Debug for JournalStack<T> {
+    /// Formats the elements, from the bottom of the stack up.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.stack.fmt(f)
+    }
+}
+
+/// Returns the header opening every journal.
+#[inline(always)]
+fn header() -> [u8; HEADER as usize] {
+    [MAGIC[0], MAGIC[1], MAGIC[2], MAGIC[3], VERSION]
+}
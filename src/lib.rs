@@ -1,12 +1,199 @@
 //! A growable and shrinkable stack array type.
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
 #![warn(missing_docs)]
 extern crate alloc;
-use alloc::vec::Vec;
+pub mod algorithms;
+#[cfg(feature = "allocator_api")]
+mod alloc_stack;
+mod arena;
+mod array;
+pub mod ast;
+#[cfg(target_has_atomic = "ptr")]
+mod async_stack;
+#[cfg(target_has_atomic = "ptr")]
+mod atomic_array;
+mod bit;
+#[cfg(target_has_atomic = "ptr")]
+mod bounded;
+#[cfg(target_has_atomic = "ptr")]
+mod buffer_pool;
+#[cfg(feature = "bumpalo")]
+mod bump;
+#[cfg(feature = "std")]
+mod byte;
+mod checkpoint;
+#[cfg(target_has_atomic = "ptr")]
+mod concurrent;
+mod deferred;
+mod diff;
+mod double;
+#[cfg(feature = "crossbeam-epoch")]
+mod epoch;
+mod error;
+pub mod eval;
+pub mod fallible;
+#[cfg(feature = "fault-injection")]
+pub mod fault;
+mod fnv;
+pub mod growth;
+mod hashed;
+#[cfg(target_has_atomic = "ptr")]
+mod hazard;
+mod history;
+#[cfg(feature = "std")]
+mod indexed;
+pub mod infix;
+mod invariant;
+#[cfg(all(feature = "std", feature = "postcard"))]
+mod journal;
+mod lifo_cache;
+pub mod matching;
+mod metrics;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod object_pool;
+mod oplog;
+#[cfg(feature = "rayon")]
+mod par;
+pub mod pda;
+#[cfg(feature = "bytemuck")]
+mod pod;
+mod pool;
+mod pop_all;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+mod published;
+mod rle;
+#[cfg(feature = "simd")]
+mod search;
+#[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+mod sharded;
+#[cfg(feature = "postcard")]
+mod snapshot;
+#[cfg(all(feature = "std", feature = "postcard"))]
+mod spill;
+#[cfg(target_has_atomic = "ptr")]
+mod spin;
+#[cfg(target_has_atomic = "ptr")]
+mod spin_stack;
+mod stack_ops;
+#[cfg(feature = "critical-section")]
+mod static_stack;
+mod str;
+#[cfg(target_has_atomic = "ptr")]
+mod sync;
+#[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+mod sync_stack;
+mod tagged;
+mod timestamped;
+#[cfg(feature = "serde")]
+pub mod top_first;
+mod trace;
+pub mod trampoline;
+pub mod traverse;
+pub mod vm;
+#[cfg(target_has_atomic = "ptr")]
+mod work_stack;
+#[cfg(feature = "zeroize")]
+mod zeroizing;
+#[cfg(feature = "quickcheck")]
+use alloc::boxed::Box;
+use alloc::{
+    collections::{BTreeSet, BinaryHeap, TryReserveError, VecDeque},
+    vec::Vec,
+};
+#[cfg(feature = "allocator_api")]
+pub use alloc_stack::AllocStack;
+#[cfg(feature = "arbitrary")]
+use arbitrary::{Arbitrary, Unstructured};
+pub use arena::{Arena, Marker};
+pub use array::{ArrayStack, Full};
+#[cfg(all(feature = "futures-core", target_has_atomic = "ptr"))]
+pub use async_stack::PopStream;
+#[cfg(target_has_atomic = "ptr")]
+pub use async_stack::{AsyncStack, Pop, Watch};
+#[cfg(target_has_atomic = "ptr")]
+pub use atomic_array::{AtomicArrayStack, Consumer, Producer};
+pub use bit::BitStack;
+#[cfg(feature = "borsh")]
+use borsh::{
+    io::{Read, Result as IoResult, Write},
+    BorshDeserialize, BorshSerialize,
+};
+#[cfg(target_has_atomic = "ptr")]
+pub use bounded::BoundedStack;
+#[cfg(target_has_atomic = "ptr")]
+pub use buffer_pool::{BufferPool, BufferStack};
+#[cfg(feature = "bumpalo")]
+pub use bump::BumpStack;
+#[cfg(feature = "std")]
+pub use byte::ByteStack;
+pub use checkpoint::{Checkpoint, Checkpointed};
+#[cfg(target_has_atomic = "ptr")]
+pub use concurrent::ConcurrentStack;
 use core::{
-    iter::Extend,
-    ops::{Deref, DerefMut},
+    borrow::{Borrow, BorrowMut},
+    cmp::Ordering,
+    iter::{self, Extend},
+    marker::PhantomData,
+    mem,
+    ops::{Bound, Deref, DerefMut, RangeBounds},
+    ptr, slice,
 };
+pub use deferred::DeferredStack;
+pub use diff::{Patch, PatchError};
+pub use double::DoubleStack;
+#[cfg(feature = "crossbeam-epoch")]
+pub use epoch::EpochStack;
+pub use error::StackError;
+pub use hashed::HashedStack;
+pub use history::History;
+#[cfg(feature = "std")]
+pub use indexed::IndexedStack;
+#[cfg(all(feature = "std", feature = "postcard"))]
+pub use journal::JournalStack;
+pub use lifo_cache::LifoCache;
+pub use metrics::{MeteredStack, StackMetrics};
+#[cfg(feature = "mmap")]
+pub use mmap::MmapStack;
+pub use object_pool::{Pool, PoolHandle};
+pub use oplog::{Op, OpLog};
+pub use pool::{Pooled, StackPool};
+pub use pop_all::PopAll;
+#[cfg(feature = "quickcheck")]
+use quickcheck::Gen;
+#[cfg(feature = "rkyv")]
+use rkyv::{
+    rancor::{Fallible, Source},
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    Archive, DeserializeUnsized, Place,
+};
+pub use rle::RleStack;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+pub use sharded::ShardedStack;
+#[cfg(all(feature = "std", feature = "postcard"))]
+pub use spill::SpillStack;
+#[cfg(target_has_atomic = "ptr")]
+pub use spin_stack::SpinStack;
+pub use stack_ops::StackOps;
+#[cfg(feature = "critical-section")]
+pub use static_stack::StaticStack;
+pub use str::StrStack;
+#[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+pub use sync_stack::SyncStack;
+pub use tagged::TaggedStack;
+#[cfg(feature = "std")]
+pub use timestamped::StdClock;
+pub use timestamped::{Clock, TimestampedStack};
+#[cfg(target_has_atomic = "ptr")]
+pub use work_stack::{Stealer, WorkStack};
+#[cfg(feature = "zeroize")]
+pub use zeroizing::ZeroizingStack;
 
 /// A growable and shrinkable stack array type.
 ///
@@ -56,8 +243,8 @@ impl<T> Stack<T> {
     /// assert_eq!(stack.top(), None);
     /// ```
     #[inline(always)]
-    pub fn top(&self) -> Option<&T> {
-        self.vec.last()
+    pub const fn top(&self) -> Option<&T> {
+        self.vec.as_slice().last()
     }
 
     /// Returns a mutable reference to the top element in the stack.
@@ -75,8 +262,116 @@ impl<T> Stack<T> {
     /// assert_eq!(stack.top_mut(), None);
     /// ```
     #[inline(always)]
-    pub fn top_mut(&mut self) -> Option<&mut T> {
-        self.vec.last_mut()
+    pub const fn top_mut(&mut self) -> Option<&mut T> {
+        self.vec.as_mut_slice().last_mut()
+    }
+
+    /// Splits the stack into the top `depth` elements and the rest, without copying.
+    ///
+    /// Both slices are ordered from the bottom up, as in the stack.
+    /// # Panics
+    /// Panics if `depth` exceeds the length of the stack.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.extend([1, 2, 3, 4]);
+    /// let (frame, rest) = stack.split_at_depth(3);
+    /// assert_eq!(frame, [2, 3, 4]);
+    /// assert_eq!(rest, [1]);
+    /// assert_eq!(stack.split_at_depth(0), (&[][..], &[1, 2, 3, 4][..]));
+    /// ```
+    #[inline(always)]
+    pub const fn split_at_depth(&self, depth: usize) -> (&[T], &[T]) {
+        let len = self.vec.len();
+        assert!(depth <= len, "depth exceeds the length of the stack");
+        let (rest, top) = self.vec.as_slice().split_at(len - depth);
+        (top, rest)
+    }
+
+    /// Splits the stack into mutable slices of the top `depth` elements and the rest, without
+    /// copying.
+    ///
+    /// Both slices are ordered from the bottom up, as in the stack.
+    /// # Panics
+    /// Panics if `depth` exceeds the length of the stack.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.extend([1, 2, 3, 4]);
+    /// let (frame, rest) = stack.split_at_depth_mut(2);
+    /// frame[0] += rest[1];
+    /// rest[0] = 0;
+    /// assert_eq!(*stack, [0, 2, 5, 4]);
+    /// ```
+    #[inline(always)]
+    pub const fn split_at_depth_mut(&mut self, depth: usize) -> (&mut [T], &mut [T]) {
+        let len = self.vec.len();
+        assert!(depth <= len, "depth exceeds the length of the stack");
+        let (rest, top) = self.vec.as_mut_slice().split_at_mut(len - depth);
+        (top, rest)
+    }
+
+    /// Returns an iterator over chunks of `size` elements, starting from the top of the stack.
+    ///
+    /// Each chunk is ordered from the bottom up, as in the stack, and the last chunk holds the
+    /// bottom of the stack and is shorter if the length doesn't divide evenly.
+    /// # Panics
+    /// Panics if `size` is zero.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.extend(1..=5);
+    /// let mut chunks = stack.chunks_from_top(2);
+    /// assert_eq!(chunks.next(), Some(&[4, 5][..]));
+    /// assert_eq!(chunks.next(), Some(&[2, 3][..]));
+    /// assert_eq!(chunks.next(), Some(&[1][..]));
+    /// assert_eq!(chunks.next(), None);
+    /// ```
+    #[inline(always)]
+    pub fn chunks_from_top(&self, size: usize) -> slice::RChunks<'_, T> {
+        self.vec.rchunks(size)
+    }
+
+    /// Returns an iterator over mutable chunks of `size` elements, starting from the top of the
+    /// stack.
+    ///
+    /// See [Stack::chunks_from_top].
+    /// # Panics
+    /// Panics if `size` is zero.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.extend(1..=5);
+    /// for (batch, chunk) in stack.chunks_from_top_mut(2).enumerate() {
+    ///     chunk.fill(batch);
+    /// }
+    /// assert_eq!(*stack, [2, 1, 1, 0, 0]);
+    /// ```
+    #[inline(always)]
+    pub fn chunks_from_top_mut(&mut self, size: usize) -> slice::RChunksMut<'_, T> {
+        self.vec.rchunks_mut(size)
+    }
+
+    /// Returns an iterator over the elements from the top of the stack down, each paired with
+    /// its depth, where the top is at depth zero.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.extend(["main", "parse", "expect"]);
+    /// let mut frames = stack.enumerate_from_top();
+    /// assert_eq!(frames.next(), Some((0, &"expect")));
+    /// assert_eq!(frames.next(), Some((1, &"parse")));
+    /// assert_eq!(frames.next(), Some((2, &"main")));
+    /// assert_eq!(frames.next(), None);
+    /// ```
+    #[inline(always)]
+    pub fn enumerate_from_top(&self) -> iter::Enumerate<iter::Rev<slice::Iter<'_, T>>> {
+        self.vec.iter().rev().enumerate()
     }
 
     /// Appends an element to the top of the stack.
@@ -91,7 +386,427 @@ impl<T> Stack<T> {
     /// ```
     #[inline(always)]
     pub fn push(&mut self, value: T) {
+        let capacity = self.vec.capacity();
         self.vec.push(value);
+        trace::pushed("Stack", self.vec.len(), capacity, self.vec.capacity());
+    }
+
+    /// Appends an element to the top of the stack, and returns a mutable reference to it.
+    ///
+    /// This saves looking the new top up again with [Stack::top_mut], such as to push a default
+    /// frame and then fill it in.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut frames: Stack<Vec<&str>> = Stack::new();
+    /// let frame = frames.push_mut(Vec::new());
+    /// frame.push("local");
+    /// assert_eq!(frames.top(), Some(&vec!["local"]));
+    /// ```
+    #[inline(always)]
+    pub fn push_mut(&mut self, value: T) -> &mut T {
+        let index = self.vec.len();
+        self.push(value);
+        &mut self.vec[index]
+    }
+
+    /// Appends an element to the top of the stack, or hands it back along with the error if the
+    /// stack had to grow but the memory couldn't be allocated.
+    ///
+    /// Unlike [Stack::push], this never aborts or panics on running out of memory, so a service
+    /// can degrade gracefully instead.
+    /// # Errors
+    /// Returns the [TryReserveError] and `value` if the memory couldn't be allocated, or the new
+    /// capacity would exceed `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// if let Err((error, value)) = stack.try_push(String::from("job")) {
+    ///     eprintln!("dropping {value}: {error}");
+    /// }
+    /// assert_eq!(stack.pop().as_deref(), Some("job"));
+    /// ```
+    #[inline(always)]
+    pub fn try_push(&mut self, value: T) -> Result<(), (TryReserveError, T)> {
+        let capacity = self.vec.capacity();
+        if let Err(error) = try_reserve(&mut self.vec, 1) {
+            return Err((error, value));
+        }
+        self.vec.push(value);
+        trace::pushed("Stack", self.vec.len(), capacity, self.vec.capacity());
+        Ok(())
+    }
+
+    /// Pushes every element of an iterator onto the stack, in order, and returns how many were
+    /// pushed.
+    ///
+    /// Room for as many elements as the iterator reports is reserved up front, and they are
+    /// written straight into it, which is faster than [Extend] for large batches. An iterator
+    /// which reports the wrong length still has every element pushed, just more slowly.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(0);
+    /// assert_eq!(stack.push_iter(1..4), 3);
+    /// assert_eq!(*stack, [0, 1, 2, 3]);
+    /// ```
+    pub fn push_iter<I>(&mut self, iter: I) -> usize
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let mut iter = iter.into_iter();
+        let start = self.vec.len();
+        let capacity = self.vec.capacity();
+        self.vec.reserve(iter.len());
+        let mut guard = SetLenOnDrop {
+            len: start,
+            vec: &mut self.vec,
+        };
+        while guard.len < guard.vec.capacity() {
+            let Some(value) = iter.next() else {
+                break;
+            };
+            // SAFETY: The slot is below the capacity and above the length, so it is allocated and
+            // holds no value.
+            unsafe { guard.vec.as_mut_ptr().add(guard.len).write(value) };
+            guard.len += 1;
+        }
+        drop(guard);
+        // Pushing whatever the iterator held beyond its reported length.
+        self.vec.extend(iter);
+        if self.vec.capacity() != capacity {
+            trace::reallocated("Stack", capacity, self.vec.capacity());
+        }
+        self.vec.len() - start
+    }
+
+    /// Pushes the values of an iterator of results onto the stack, in order, until the first
+    /// error, and returns how many were pushed.
+    ///
+    /// The values pushed before an error stay on the stack. See
+    /// [Stack::try_extend_or_rollback] to remove them instead.
+    /// # Errors
+    /// Returns the first error along with the number of values pushed before it.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// let parsed = "1 2 x 4".split(' ').map(str::parse::<u8>);
+    /// let (_, pushed) = stack.try_extend(parsed).unwrap_err();
+    /// assert_eq!(pushed, 2);
+    /// assert_eq!(*stack, [1, 2]);
+    /// ```
+    pub fn try_extend<E>(
+        &mut self,
+        iter: impl IntoIterator<Item = Result<T, E>>,
+    ) -> Result<usize, (E, usize)> {
+        let start = self.vec.len();
+        let capacity = self.vec.capacity();
+        let mut error = None;
+        self.vec.extend(
+            iter.into_iter()
+                .map_while(|result| result.map_err(|first| error = Some(first)).ok()),
+        );
+        if self.vec.capacity() != capacity {
+            trace::reallocated("Stack", capacity, self.vec.capacity());
+        }
+        let pushed = self.vec.len() - start;
+        match error {
+            Some(error) => Err((error, pushed)),
+            None => Ok(pushed),
+        }
+    }
+
+    /// Pushes the values of an iterator of results onto the stack, in order, and returns how
+    /// many were pushed, or removes them all again on the first error.
+    /// # Errors
+    /// Returns the first error, after truncating the stack back to the length it had.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(0);
+    /// let parsed = "1 2 x 4".split(' ').map(str::parse::<u8>);
+    /// assert!(stack.try_extend_or_rollback(parsed).is_err());
+    /// assert_eq!(*stack, [0]);
+    /// let parsed = "1 2 3".split(' ').map(str::parse::<u8>);
+    /// assert_eq!(stack.try_extend_or_rollback(parsed), Ok(3));
+    /// assert_eq!(*stack, [0, 1, 2, 3]);
+    /// ```
+    pub fn try_extend_or_rollback<E>(
+        &mut self,
+        iter: impl IntoIterator<Item = Result<T, E>>,
+    ) -> Result<usize, E> {
+        let start = self.vec.len();
+        self.try_extend(iter).map_err(|(error, _)| {
+            self.vec.truncate(start);
+            error
+        })
+    }
+
+    /// Pushes clones of the elements at the depths in `depths` onto the stack, in the order they
+    /// are in, without a temporary buffer.
+    ///
+    /// Depths count down from the top of the stack, which is at depth zero, so `..n` duplicates
+    /// the top `n` elements.
+    /// # Panics
+    /// Panics if the range of depths is decreasing or exceeds the length of the stack, or if the
+    /// new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.extend(['a', 'b', 'c']);
+    /// stack.extend_from_within(..2);
+    /// assert_eq!(*stack, ['a', 'b', 'c', 'b', 'c']);
+    /// stack.extend_from_within(3..=4);
+    /// assert_eq!(*stack, ['a', 'b', 'c', 'b', 'c', 'a', 'b']);
+    /// ```
+    pub fn extend_from_within(&mut self, depths: impl RangeBounds<usize>)
+    where
+        T: Clone,
+    {
+        let len = self.vec.len();
+        let shallowest = match depths.start_bound() {
+            Bound::Included(&depth) => depth,
+            Bound::Excluded(&depth) => depth.checked_add(1).expect("depth overflowed"),
+            Bound::Unbounded => 0,
+        };
+        let deepest = match depths.end_bound() {
+            Bound::Included(&depth) => depth.checked_add(1).expect("depth overflowed"),
+            Bound::Excluded(&depth) => depth,
+            Bound::Unbounded => len,
+        };
+        assert!(
+            shallowest <= deepest && deepest <= len,
+            "depths {shallowest}..{deepest} are out of range for a stack of length {len}"
+        );
+        let capacity = self.vec.capacity();
+        self.vec.extend_from_within(len - deepest..len - shallowest);
+        if self.vec.capacity() != capacity {
+            trace::reallocated("Stack", capacity, self.vec.capacity());
+        }
+    }
+
+    /// Replaces the contents of the stack with clones of the elements of `slice`, from the bottom
+    /// up, reusing its allocation.
+    ///
+    /// Elements already in the stack are assigned to with [Clone::clone_from], so they can reuse
+    /// their own allocations too, and the stack only reallocates if `slice` is longer than its
+    /// capacity.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.extend([String::from("a"), String::from("b"), String::from("c")]);
+    /// let capacity = stack.capacity();
+    /// stack.assign_from_slice(&[String::from("x"), String::from("y")]);
+    /// assert_eq!(*stack, ["x", "y"]);
+    /// assert_eq!(stack.capacity(), capacity);
+    /// ```
+    pub fn assign_from_slice(&mut self, slice: &[T])
+    where
+        T: Clone,
+    {
+        let capacity = self.vec.capacity();
+        self.vec.truncate(slice.len());
+        let (assigned, pushed) = slice.split_at(self.vec.len());
+        self.vec.clone_from_slice(assigned);
+        self.vec.extend_from_slice(pushed);
+        if self.vec.capacity() != capacity {
+            trace::reallocated("Stack", capacity, self.vec.capacity());
+        }
+    }
+
+    /// Replaces each element with the result of `f` on it, from the bottom to the top, without
+    /// allocating.
+    ///
+    /// If `f` panics, the stack is left empty, with every element dropped.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.extend([String::from("a"), String::from("b")]);
+    /// stack.map_in_place(|mut value| {
+    ///     value.push('!');
+    ///     value
+    /// });
+    /// assert_eq!(*stack, ["a!", "b!"]);
+    /// ```
+    #[inline(always)]
+    pub fn map_in_place(&mut self, f: impl FnMut(T) -> T) {
+        let stack = Self {
+            vec: mem::take(&mut self.vec),
+        };
+        *self = stack.map_in_place_to(f);
+    }
+
+    /// Converts each element with `f`, from the bottom to the top, reusing the stack's allocation
+    /// for the results.
+    ///
+    /// `T` and `U` must have the same size and alignment, which is checked at compile time. If `f`
+    /// panics, every element and result is dropped.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.extend([1u32, 2, 3]);
+    /// let capacity = stack.capacity();
+    /// let stack = stack.map_in_place_to(|value| value as f32 / 2.0);
+    /// assert_eq!(*stack, [0.5, 1.0, 1.5]);
+    /// assert_eq!(stack.capacity(), capacity);
+    /// ```
+    /// Converting to a type of another size doesn't compile.
+    /// ```compile_fail
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1u32);
+    /// let stack = stack.map_in_place_to(u64::from);
+    /// ```
+    pub fn map_in_place_to<U>(self, mut f: impl FnMut(T) -> U) -> Stack<U> {
+        const {
+            assert!(
+                mem::size_of::<T>() == mem::size_of::<U>()
+                    && mem::align_of::<T>() == mem::align_of::<U>(),
+                "elements can only be mapped in place to a type of the same size and alignment"
+            );
+        }
+        let mut vec = mem::ManuallyDrop::new(self.vec);
+        let mut guard = MapInPlace {
+            ptr: vec.as_mut_ptr(),
+            len: vec.len(),
+            capacity: vec.capacity(),
+            mapped: 0,
+            _types: PhantomData::<(T, U)>,
+        };
+        while guard.mapped < guard.len {
+            // SAFETY: The slot is below the length and hasn't been mapped, so it holds a `T`, which
+            // the guard won't drop once it is read, since it is then counted as taken.
+            let value = unsafe { guard.ptr.add(guard.mapped).read() };
+            guard.mapped += 1;
+            let result = f(value);
+            // SAFETY: `U` has the same layout as `T`, and the slot was just read from.
+            unsafe { guard.ptr.add(guard.mapped - 1).cast::<U>().write(result) };
+        }
+        let guard = mem::ManuallyDrop::new(guard);
+        // SAFETY: Every element was replaced with a `U`, and `U` has the same size and alignment
+        // as `T`, so the allocation fits a `Vec<U>` of the same capacity.
+        let vec = unsafe { Vec::from_raw_parts(guard.ptr.cast::<U>(), guard.len, guard.capacity) };
+        Stack { vec }
+    }
+
+    /// Converts each element with `f`, from the bottom to the top, stopping at the first error.
+    ///
+    /// On an error, the elements already converted and those not yet reached are all dropped.
+    /// # Errors
+    /// Returns the first error `f` returns.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.extend(["1", "2", "3"]);
+    /// let numbers = stack.clone().try_map(str::parse::<u8>);
+    /// assert_eq!(numbers.map(|mut numbers| numbers.pop()), Ok(Some(3)));
+    /// stack.push("four");
+    /// assert!(stack.try_map(str::parse::<u8>).is_err());
+    /// ```
+    #[inline(always)]
+    pub fn try_map<U, E>(self, f: impl FnMut(T) -> Result<U, E>) -> Result<Stack<U>, E> {
+        let vec = self.vec.into_iter().map(f).collect::<Result<_, _>>()?;
+        Ok(Stack { vec })
+    }
+
+    /// Inserts an element into a sorted stack, keeping it sorted with the greatest element on
+    /// top, and returns the depth it was inserted at.
+    ///
+    /// The position is found with a binary search, and the element is placed above any equal
+    /// elements. If the stack is not sorted, the position is unspecified but the element is still
+    /// inserted.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push_sorted(3);
+    /// stack.push_sorted(1);
+    /// assert_eq!(stack.push_sorted(2), 1);
+    /// assert_eq!(stack.pop(), Some(3));
+    /// assert_eq!(stack.pop(), Some(2));
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn push_sorted(&mut self, value: T) -> usize
+    where
+        T: Ord,
+    {
+        self.push_sorted_by(value, T::cmp)
+    }
+
+    /// Inserts an element into a stack sorted by `compare`, keeping it sorted with the greatest
+    /// element on top, and returns the depth it was inserted at.
+    ///
+    /// The position is found with a binary search, and the element is placed above any equal
+    /// elements. If the stack is not sorted, the position is unspecified but the element is still
+    /// inserted.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// // Keeping the smallest element on top.
+    /// stack.push_sorted_by(1, |a, b| b.cmp(a));
+    /// stack.push_sorted_by(3, |a, b| b.cmp(a));
+    /// stack.push_sorted_by(2, |a, b| b.cmp(a));
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    pub fn push_sorted_by(
+        &mut self,
+        value: T,
+        mut compare: impl FnMut(&T, &T) -> Ordering,
+    ) -> usize {
+        let index = self
+            .vec
+            .partition_point(|element| compare(element, &value) != Ordering::Greater);
+        let capacity = self.vec.capacity();
+        self.vec.insert(index, value);
+        trace::pushed("Stack", self.vec.len(), capacity, self.vec.capacity());
+        self.vec.len() - 1 - index
+    }
+
+    /// Inserts an element into a stack sorted by the key `f` extracts, keeping it sorted with the
+    /// greatest key on top, and returns the depth it was inserted at.
+    ///
+    /// The position is found with a binary search, and the element is placed above any elements
+    /// with equal keys. If the stack is not sorted, the position is unspecified but the element
+    /// is still inserted.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push_sorted_by_key((2, 'b'), |&(priority, _)| priority);
+    /// stack.push_sorted_by_key((1, 'a'), |&(priority, _)| priority);
+    /// assert_eq!(stack.top(), Some(&(2, 'b')));
+    /// ```
+    #[inline(always)]
+    pub fn push_sorted_by_key<K: Ord>(&mut self, value: T, mut f: impl FnMut(&T) -> K) -> usize {
+        self.push_sorted_by(value, |a, b| f(a).cmp(&f(b)))
     }
 
     /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
@@ -104,7 +819,32 @@ impl<T> Stack<T> {
     /// ```
     #[inline(always)]
     pub fn pop(&mut self) -> Option<T> {
-        self.vec.pop()
+        let value = self.vec.pop()?;
+        trace::popped("Stack", self.vec.len());
+        Some(value)
+    }
+
+    /// Removes the element at `depth` and returns it, moving the top element into its place, or
+    /// returns [None] if the stack is no deeper than `depth`.
+    ///
+    /// The top of the stack is at depth zero. This takes constant time, but doesn't keep the
+    /// order of the elements beneath the top.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.extend(['a', 'b', 'c', 'd']);
+    /// assert_eq!(stack.pop_at_swap(2), Some('b'));
+    /// assert_eq!(*stack, ['a', 'd', 'c']);
+    /// assert_eq!(stack.pop_at_swap(0), Some('c'));
+    /// assert_eq!(stack.pop_at_swap(2), None);
+    /// ```
+    #[inline(always)]
+    pub fn pop_at_swap(&mut self, depth: usize) -> Option<T> {
+        let index = self.vec.len().checked_sub(depth)?.checked_sub(1)?;
+        let value = self.vec.swap_remove(index);
+        trace::popped("Stack", self.vec.len());
+        Some(value)
     }
 
     /// Clears the stack, popping all values.
@@ -135,10 +875,56 @@ impl<T> Stack<T> {
     /// assert_eq!(stack.len(), 2);
     /// ```
     #[inline(always)]
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         self.vec.len()
     }
 
+    /// Returns `true` if the stack contains no elements.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns the number of elements the stack can hold without reallocating.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// const EMPTY: &Stack<i32> = &Stack::new();
+    /// const _: () = assert!(EMPTY.capacity() == 0 && EMPTY.top().is_none());
+    /// let mut stack = Stack::new();
+    /// stack.push(1);
+    /// assert!(stack.capacity() >= 1);
+    /// ```
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns the number of bytes the stack has allocated on the heap, including the spare
+    /// capacity.
+    ///
+    /// Memory the elements own themselves, like the buffers of strings, isn't counted.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::<u64>::new();
+    /// assert_eq!(stack.allocated_bytes(), 0);
+    /// stack.push(1);
+    /// assert_eq!(stack.allocated_bytes(), stack.capacity() * 8);
+    /// ```
+    #[inline(always)]
+    pub const fn allocated_bytes(&self) -> usize {
+        self.vec.capacity() * mem::size_of::<T>()
+    }
+
     /// Extracts a vector containing the entire stack.
     /// # Example
     /// ```
@@ -187,17 +973,390 @@ impl<T> DerefMut for Stack<T> {
         &mut self.vec
     }
 }
+impl<T> AsRef<[T]> for Stack<T> {
+    /// Views the elements of a `Stack<T>`, from its bottom to its top.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// fn sum(values: impl AsRef<[i32]>) -> i32 {
+    ///     values.as_ref().iter().sum()
+    /// }
+    /// let mut stack = Stack::new();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// assert_eq!(sum(&stack), 3);
+    /// ```
+    #[inline(always)]
+    fn as_ref(&self) -> &[T] {
+        &self.vec
+    }
+}
+impl<T> AsMut<[T]> for Stack<T> {
+    /// Mutably views the elements of a `Stack<T>`, from its bottom to its top.
+    #[inline(always)]
+    fn as_mut(&mut self) -> &mut [T] {
+        &mut self.vec
+    }
+}
+impl<T> Borrow<[T]> for Stack<T> {
+    /// Borrows the elements of a `Stack<T>`, from its bottom to its top.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// use std::collections::HashSet;
+    /// let mut stack = Stack::new();
+    /// stack.push(1);
+    /// let set = HashSet::from([stack]);
+    /// assert!(set.contains(&[1][..]));
+    /// ```
+    #[inline(always)]
+    fn borrow(&self) -> &[T] {
+        &self.vec
+    }
+}
+impl<T> BorrowMut<[T]> for Stack<T> {
+    /// Mutably borrows the elements of a `Stack<T>`, from its bottom to its top.
+    #[inline(always)]
+    fn borrow_mut(&mut self) -> &mut [T] {
+        &mut self.vec
+    }
+}
+impl<T> From<VecDeque<T>> for Stack<T> {
+    /// Converts a deque into a stack with its back on top, reusing its allocation where possible.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// use std::collections::VecDeque;
+    /// let mut stack = Stack::from(VecDeque::from([1, 2]));
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    #[inline(always)]
+    fn from(deque: VecDeque<T>) -> Self {
+        Self { vec: deque.into() }
+    }
+}
+impl<T> From<Stack<T>> for VecDeque<T> {
+    /// Converts a stack into a deque with its top at the back, without reallocating.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// use std::collections::VecDeque;
+    /// let mut stack = Stack::new();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// assert_eq!(VecDeque::from(stack).pop_front(), Some(1));
+    /// ```
+    #[inline(always)]
+    fn from(stack: Stack<T>) -> Self {
+        stack.vec.into()
+    }
+}
+impl<T: Ord> From<BinaryHeap<T>> for Stack<T> {
+    /// Converts a heap into a sorted stack with its greatest element on top, without
+    /// reallocating.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// use std::collections::BinaryHeap;
+    /// let mut stack = Stack::from(BinaryHeap::from([2, 3, 1]));
+    /// assert_eq!(stack.pop(), Some(3));
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    #[inline(always)]
+    fn from(heap: BinaryHeap<T>) -> Self {
+        Self {
+            vec: heap.into_sorted_vec(),
+        }
+    }
+}
+impl<T: Ord> From<Stack<T>> for BinaryHeap<T> {
+    /// Converts a stack into a heap in O(n) time, without reallocating.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// use std::collections::BinaryHeap;
+    /// let mut stack = Stack::new();
+    /// stack.push(3);
+    /// stack.push(1);
+    /// assert_eq!(BinaryHeap::from(stack).pop(), Some(3));
+    /// ```
+    #[inline(always)]
+    fn from(stack: Stack<T>) -> Self {
+        stack.vec.into()
+    }
+}
+impl<T> From<BTreeSet<T>> for Stack<T> {
+    /// Converts a set into a sorted stack with its greatest element on top.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// use std::collections::BTreeSet;
+    /// let mut stack = Stack::from(BTreeSet::from([2, 3, 1]));
+    /// assert_eq!(stack.pop(), Some(3));
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    #[inline(always)]
+    fn from(set: BTreeSet<T>) -> Self {
+        Self {
+            vec: set.into_iter().collect(),
+        }
+    }
+}
+impl<T: Ord> From<Stack<T>> for BTreeSet<T> {
+    /// Converts a stack into a set, discarding duplicate elements.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// use std::collections::BTreeSet;
+    /// let mut stack = Stack::new();
+    /// stack.push(2);
+    /// stack.push(1);
+    /// stack.push(2);
+    /// assert!(BTreeSet::from(stack).into_iter().eq([1, 2]));
+    /// ```
+    #[inline(always)]
+    fn from(stack: Stack<T>) -> Self {
+        stack.vec.into_iter().collect()
+    }
+}
 impl<T> Extend<T> for Stack<T> {
     /// Pushes a collection of values onto a stack.
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         // Moving all new values onto the stack.
+        let capacity = self.vec.capacity();
         self.vec.extend(iter);
+        if self.vec.capacity() != capacity {
+            trace::reallocated("Stack", capacity, self.vec.capacity());
+        }
     }
 }
 impl<'a, T: 'a + Copy> Extend<&'a T> for Stack<T> {
     /// Pushes a collection of values onto a stack.
     fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
         // Copying all new values onto the stack.
+        let capacity = self.vec.capacity();
         self.vec.extend(iter);
+        if self.vec.capacity() != capacity {
+            trace::reallocated("Stack", capacity, self.vec.capacity());
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for Stack<T> {
+    /// Serializes a stack as a sequence, from its bottom to its top.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// assert_eq!(serde_json::to_string(&stack).unwrap(), "[1,2]");
+    /// ```
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.vec.serialize(serializer)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Stack<T> {
+    /// Deserializes a stack from a sequence, from its bottom to its top.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack: Stack<i32> = serde_json::from_str("[1,2]").unwrap();
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <Vec<T> as Deserialize>::deserialize(deserializer).map(|vec| Self { vec })
+    }
+}
+#[cfg(feature = "borsh")]
+impl<T: BorshSerialize> BorshSerialize for Stack<T> {
+    /// Serializes a stack in the same format as a vector, from its bottom to its top.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1u8);
+    /// stack.push(2);
+    /// assert_eq!(borsh::to_vec(&stack).unwrap(), [2, 0, 0, 0, 1, 2]);
+    /// ```
+    fn serialize<W: Write>(&self, writer: &mut W) -> IoResult<()> {
+        self.vec.serialize(writer)
+    }
+}
+#[cfg(feature = "borsh")]
+impl<T: BorshDeserialize> BorshDeserialize for Stack<T> {
+    /// Deserializes a stack in the same format as a vector, from its bottom to its top.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack: Stack<u8> = borsh::from_slice(&[2, 0, 0, 0, 1, 2]).unwrap();
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    fn deserialize_reader<R: Read>(reader: &mut R) -> IoResult<Self> {
+        Vec::deserialize_reader(reader).map(|vec| Self { vec })
+    }
+}
+#[cfg(feature = "rkyv")]
+impl<T: Archive> Archive for Stack<T> {
+    /// A stack is archived as a vector, from its bottom to its top, and can be read in place.
+    type Archived = ArchivedVec<T::Archived>;
+    /// The resolver for an archived stack.
+    type Resolver = VecResolver;
+
+    /// Creates the archived version of a stack at the given position.
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedVec::resolve_from_slice(&self.vec, resolver, out);
+    }
+}
+#[cfg(feature = "rkyv")]
+impl<T: rkyv::Serialize<S>, S: Fallible + Allocator + Writer + ?Sized> rkyv::Serialize<S>
+    for Stack<T>
+{
+    /// Writes the elements of a stack to the serializer.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1u32);
+    /// stack.push(2);
+    /// let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&stack).unwrap();
+    /// let archived = rkyv::access::<rkyv::Archived<Stack<u32>>, rkyv::rancor::Error>(&bytes);
+    /// assert_eq!(archived.unwrap().last().map(|value| value.to_native()), Some(2));
+    /// ```
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_from_slice(&self.vec, serializer)
+    }
+}
+#[cfg(feature = "rkyv")]
+impl<T, D> rkyv::Deserialize<Stack<T>, D> for ArchivedVec<T::Archived>
+where
+    T: Archive,
+    [T::Archived]: DeserializeUnsized<[T], D>,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    /// Deserializes an archived stack.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1u32);
+    /// let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&stack).unwrap();
+    /// let mut stack = rkyv::from_bytes::<Stack<u32>, rkyv::rancor::Error>(&bytes).unwrap();
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    fn deserialize(&self, deserializer: &mut D) -> Result<Stack<T>, D::Error> {
+        rkyv::Deserialize::<Vec<T>, D>::deserialize(self, deserializer).map(|vec| Stack { vec })
+    }
+}
+#[cfg(feature = "arbitrary")]
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for Stack<T> {
+    /// Generates an arbitrary stack, from its bottom to its top.
+    /// # Example
+    /// ```
+    /// use arbitrary::{Arbitrary, Unstructured};
+    /// use hay::Stack;
+    /// let mut u = Unstructured::new(&[1, 2, 3, 4]);
+    /// let stack = Stack::<u8>::arbitrary(&mut u).unwrap();
+    /// assert!(stack.len() <= 4);
+    /// ```
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Vec::arbitrary(u).map(|vec| Self { vec })
+    }
+
+    /// Generates an arbitrary stack from all of the remaining data.
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> arbitrary::Result<Self> {
+        Vec::arbitrary_take_rest(u).map(|vec| Self { vec })
+    }
+
+    /// Returns the number of bytes an arbitrary stack takes.
+    #[inline(always)]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        Vec::<T>::size_hint(depth)
+    }
+}
+#[cfg(feature = "quickcheck")]
+impl<T: quickcheck::Arbitrary> quickcheck::Arbitrary for Stack<T> {
+    /// Generates an arbitrary stack, from its bottom to its top.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// fn prop(mut stack: Stack<u8>, value: u8) -> bool {
+    ///     stack.push(value);
+    ///     stack.pop() == Some(value)
+    /// }
+    /// quickcheck::quickcheck(prop as fn(Stack<u8>, u8) -> bool);
+    /// ```
+    fn arbitrary(g: &mut Gen) -> Self {
+        Self {
+            vec: <Vec<T> as quickcheck::Arbitrary>::arbitrary(g),
+        }
+    }
+
+    /// Shrinks a stack by removing elements and shrinking the ones left, keeping them in the
+    /// order they were pushed.
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.vec.shrink().map(|vec| Self { vec }))
+    }
+}
+#[cfg(feature = "defmt")]
+impl<T: defmt::Format> defmt::Format for Stack<T> {
+    /// Formats a stack as its length followed by its elements, from its bottom to its top.
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=[?]}", self.vec.as_slice());
+    }
+}
+
+/// Reserves room for at least `additional` more elements in a vector, failing instead if it has
+/// to allocate and a failure has been injected.
+#[inline(always)]
+fn try_reserve<T>(vec: &mut Vec<T>, additional: usize) -> Result<(), TryReserveError> {
+    #[cfg(feature = "fault-injection")]
+    if additional > vec.capacity() - vec.len() {
+        fault::inject()?;
+    }
+    vec.try_reserve(additional)
+}
+
+/// Sets the length of a vector when dropped, so the elements written into its spare capacity
+/// are kept even if writing the rest panics.
+struct SetLenOnDrop<'a, T> {
+    vec: &'a mut Vec<T>,
+    len: usize,
+}
+impl<T> Drop for SetLenOnDrop<'_, T> {
+    /// Sets the length of the vector.
+    #[inline(always)]
+    fn drop(&mut self) {
+        // SAFETY: Every slot below the length was written to.
+        unsafe { self.vec.set_len(self.len) };
+    }
+}
+
+/// Cleans up after [Stack::map_in_place_to] if the mapping panics, dropping the results written
+/// so far, the elements not yet taken, and the allocation.
+struct MapInPlace<T, U> {
+    ptr: *mut T,
+    len: usize,
+    capacity: usize,
+    /// The number of elements taken, all but the last of which have been replaced with results.
+    mapped: usize,
+    _types: PhantomData<(T, U)>,
+}
+impl<T, U> Drop for MapInPlace<T, U> {
+    /// Drops the results, the elements not yet taken, and the allocation.
+    fn drop(&mut self) {
+        // SAFETY: The slots below the last one taken hold results, and the slots above it hold
+        // elements, while the slot itself holds neither.
+        unsafe {
+            let results = self.mapped.saturating_sub(1);
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr.cast::<U>(), results));
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                self.ptr.add(self.mapped),
+                self.len - self.mapped,
+            ));
+            drop(Vec::from_raw_parts(self.ptr, 0, self.capacity));
+        }
     }
 }
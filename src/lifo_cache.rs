@@ -0,0 +1,309 @@
+use crate::invariant::check_invariants;
+use alloc::collections::VecDeque;
+#[cfg(feature = "serde")]
+use alloc::vec::Vec;
+#[cfg(feature = "arbitrary")]
+use arbitrary::{Arbitrary, Unstructured};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A stack holding at most a fixed number of elements, where the most recently pushed elements
+/// win.
+///
+/// Pushing onto a full cache evicts the element at the bottom, which is handed back to the
+/// caller.
+/// # Example
+/// ```
+/// use hay::LifoCache;
+/// let mut cache = LifoCache::new(2);
+/// assert_eq!(cache.push(1), None);
+/// assert_eq!(cache.push(2), None);
+/// assert_eq!(cache.push(3), Some(1));
+/// assert_eq!(cache.pop(), Some(3));
+/// assert_eq!(cache.pop(), Some(2));
+/// assert_eq!(cache.pop(), None);
+/// ```
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LifoCache<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+}
+impl<T> LifoCache<T> {
+    /// Constructs a new, empty `LifoCache<T>` which holds at most `capacity` elements.
+    ///
+    /// The cache will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let mut cache: LifoCache<i32> = LifoCache::new(16);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            items: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the maximum number of elements the cache holds.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let cache: LifoCache<i32> = LifoCache::new(16);
+    /// assert_eq!(cache.capacity(), 16);
+    /// ```
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the maximum number of elements the cache holds, evicting elements from the bottom
+    /// until it fits.
+    ///
+    /// The evicted elements are returned from the bottom of the cache up. They are removed even
+    /// if the iterator is dropped before it is fully consumed.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let mut cache = LifoCache::new(3);
+    /// cache.extend([1, 2, 3]);
+    /// assert!(cache.set_capacity(1).eq([1, 2]));
+    /// assert_eq!(cache.top(), Some(&3));
+    /// ```
+    pub fn set_capacity(&mut self, capacity: usize) -> impl Iterator<Item = T> + '_ {
+        self.capacity = capacity;
+        let excess = self.items.len().saturating_sub(capacity);
+        self.items.drain(..excess)
+    }
+
+    /// Returns a reference to the top element in the cache.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let mut cache = LifoCache::new(2);
+    /// cache.push(1);
+    /// assert_eq!(cache.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn top(&self) -> Option<&T> {
+        self.items.back()
+    }
+
+    /// Returns a mutable reference to the top element in the cache.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let mut cache = LifoCache::new(2);
+    /// cache.push(1);
+    /// *cache.top_mut().unwrap() = 2;
+    /// assert_eq!(cache.pop(), Some(2));
+    /// ```
+    #[inline(always)]
+    pub fn top_mut(&mut self) -> Option<&mut T> {
+        self.items.back_mut()
+    }
+
+    /// Returns a reference to the bottom element in the cache, which is the next to be evicted.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let mut cache = LifoCache::new(2);
+    /// cache.push(1);
+    /// cache.push(2);
+    /// assert_eq!(cache.bottom(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn bottom(&self) -> Option<&T> {
+        self.items.front()
+    }
+
+    /// Appends an element to the top of the cache, returning the bottom element if it had to be
+    /// evicted to make room.
+    ///
+    /// If the capacity of the cache is zero, `value` itself is returned.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let mut cache = LifoCache::new(1);
+    /// assert_eq!(cache.push(1), None);
+    /// assert_eq!(cache.push(2), Some(1));
+    /// ```
+    pub fn push(&mut self, value: T) -> Option<T> {
+        if self.capacity == 0 {
+            return Some(value);
+        }
+        let evicted = if self.items.len() >= self.capacity {
+            self.items.pop_front()
+        } else {
+            None
+        };
+        self.items.push_back(value);
+        check_invariants!(self);
+        evicted
+    }
+
+    /// Appends an element to the top of the cache, passing the bottom element to `on_evict` if
+    /// it had to be evicted to make room.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let mut cache = LifoCache::new(1);
+    /// let mut evicted = Vec::new();
+    /// cache.push_with(1, |value| evicted.push(value));
+    /// cache.push_with(2, |value| evicted.push(value));
+    /// assert_eq!(evicted, [1]);
+    /// ```
+    #[inline(always)]
+    pub fn push_with(&mut self, value: T, on_evict: impl FnOnce(T)) {
+        if let Some(evicted) = self.push(value) {
+            on_evict(evicted);
+        }
+    }
+
+    /// Removes the element at the top of the cache and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let mut cache = LifoCache::new(2);
+    /// cache.push(1);
+    /// assert_eq!(cache.pop(), Some(1));
+    /// assert_eq!(cache.pop(), None);
+    /// ```
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        self.items.pop_back()
+    }
+
+    /// Clears the cache, popping all values.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the cache.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let mut cache = LifoCache::new(2);
+    /// cache.push(1);
+    /// cache.clear();
+    /// assert!(cache.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Returns the number of elements in the cache.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let mut cache = LifoCache::new(2);
+    /// cache.push(1);
+    /// assert_eq!(cache.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the cache contains no elements.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let mut cache = LifoCache::new(2);
+    /// assert!(cache.is_empty());
+    /// cache.push(1);
+    /// assert!(!cache.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns `true` if the next push will evict an element.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let mut cache = LifoCache::new(1);
+    /// assert!(!cache.is_full());
+    /// cache.push(1);
+    /// assert!(cache.is_full());
+    /// ```
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.items.len() >= self.capacity
+    }
+
+    /// Returns an iterator over the elements, from the bottom of the cache up.
+    /// # Example
+    /// ```
+    /// use hay::LifoCache;
+    /// let mut cache = LifoCache::new(2);
+    /// cache.extend([1, 2, 3]);
+    /// assert!(cache.iter().eq(&[2, 3]));
+    /// ```
+    #[inline(always)]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.items.iter()
+    }
+
+    /// Panics if the cache holds more elements than its capacity.
+    #[cfg(feature = "strict-debug")]
+    #[track_caller]
+    fn check_invariants(&self) {
+        assert!(
+            self.items.len() <= self.capacity,
+            "`LifoCache` holds {} elements, beyond its capacity of {}",
+            self.items.len(),
+            self.capacity,
+        );
+    }
+}
+impl<T> Extend<T> for LifoCache<T> {
+    /// Pushes a collection of values onto a cache, discarding any evicted elements.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for LifoCache<T> {
+    /// Serializes a cache as its capacity followed by the sequence of its elements, from its
+    /// bottom to its top.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.capacity, &self.items).serialize(serializer)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for LifoCache<T> {
+    /// Deserializes a cache from its capacity followed by the sequence of its elements, from its
+    /// bottom to its top.
+    ///
+    /// Elements beyond the capacity are evicted from the bottom, as if they had been pushed.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (capacity, items) = <(usize, Vec<T>)>::deserialize(deserializer)?;
+        let mut cache = Self::new(capacity);
+        cache.extend(items);
+        Ok(cache)
+    }
+}
+#[cfg(feature = "arbitrary")]
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for LifoCache<T> {
+    /// Generates an arbitrary cache of an arbitrary capacity, filled from its bottom to its top.
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut cache = Self::new(u.arbitrary()?);
+        for value in u.arbitrary_iter()? {
+            cache.push(value?);
+        }
+        Ok(cache)
+    }
+
+    /// Returns the number of bytes an arbitrary cache takes.
+    #[inline(always)]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        arbitrary::size_hint::and(usize::size_hint(depth), (0, None))
+    }
+}
@@ -0,0 +1,422 @@
+//! Checks that delimiters are balanced, keeping the open ones on a [Stack].
+//!
+//! Delimiters are given as pairs of openers and closers, of any type which can be compared, so
+//! the same code checks brackets in a string, tags in a token stream, or anything else which
+//! nests. [check] checks a whole sequence at once, and a [Matcher] checks one fed piece by
+//! piece. A [TagMatcher] checks named tags, like those of XML or HTML, as they stream in.
+//! # Example
+//! ```
+//! use hay::matching::{self, Error, BRACKETS};
+//! assert_eq!(matching::check(BRACKETS, "f(a[0], {b})".chars()), Ok(()));
+//! assert_eq!(
+//!     matching::check(BRACKETS, "f(a[0)]".chars()),
+//!     Err(Error::Mismatched {
+//!         position: 5,
+//!         found: ')',
+//!         expected: ']',
+//!         open_position: 3,
+//!     }),
+//! );
+//! ```
+use crate::Stack;
+use core::fmt::{self, Debug, Display};
+
+/// Parentheses, square brackets, and curly braces.
+pub const BRACKETS: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}')];
+
+/// The error returned when delimiters aren't balanced.
+///
+/// Positions count the items fed to the [Matcher] from zero, such as the `char`s of a string.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error<T> {
+    /// A closer didn't match the innermost open delimiter.
+    Mismatched {
+        /// The position of the closer.
+        position: usize,
+        /// The closer found.
+        found: T,
+        /// The closer of the innermost open delimiter.
+        expected: T,
+        /// The position of the innermost open delimiter.
+        open_position: usize,
+    },
+    /// A closer was found with no delimiter open.
+    UnexpectedClose {
+        /// The position of the closer.
+        position: usize,
+        /// The closer found.
+        found: T,
+    },
+    /// A delimiter was still open at the end.
+    Unclosed {
+        /// The position of the innermost open delimiter.
+        open_position: usize,
+        /// The closer it expected.
+        expected: T,
+    },
+}
+impl<T: Debug> Display for Error<T> {
+    /// Formats the error.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mismatched {
+                position,
+                found,
+                expected,
+                open_position,
+            } => write!(
+                f,
+                "found {found:?} at {position}, but expected {expected:?} to close the delimiter at \
+                 {open_position}"
+            ),
+            Self::UnexpectedClose { position, found } => {
+                write!(f, "found {found:?} at {position}, but nothing is open")
+            }
+            Self::Unclosed {
+                open_position,
+                expected,
+            } => write!(
+                f,
+                "delimiter at {open_position} is unclosed, expected {expected:?}"
+            ),
+        }
+    }
+}
+impl<T: Debug> core::error::Error for Error<T> {}
+
+/// A checker of balanced delimiters, fed one item at a time.
+///
+/// A pair may use the same item to open and close, like quotes, in which case it closes when it
+/// is the innermost open delimiter and opens otherwise.
+/// # Example
+/// ```
+/// use hay::matching::{Error, Matcher, BRACKETS};
+/// let mut matcher = Matcher::new(BRACKETS);
+/// for chunk in ["{ \"a\": [1, ", "2] "] {
+///     matcher.feed(chunk.chars())?;
+/// }
+/// assert_eq!(matcher.depth(), 1);
+/// assert_eq!(matcher.expected(), Some(&'}'));
+/// matcher.feed("}".chars())?;
+/// matcher.finish()?;
+/// # Ok::<(), Error<char>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Matcher<'a, T> {
+    pairs: &'a [(T, T)],
+    open: Stack<(usize, usize)>,
+    position: usize,
+}
+impl<'a, T: PartialEq + Clone> Matcher<'a, T> {
+    /// Constructs a new `Matcher<T>` for the given pairs of openers and closers.
+    ///
+    /// The matcher will not allocate until a delimiter is opened.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(pairs: &'a [(T, T)]) -> Self {
+        Self {
+            pairs,
+            open: Stack::new(),
+            position: 0,
+        }
+    }
+
+    /// Checks the next item, opening or closing a delimiter if it is one.
+    /// # Errors
+    /// Returns [Error::Mismatched] or [Error::UnexpectedClose] if the item is a closer which
+    /// doesn't match the innermost open delimiter, in which case it is skipped.
+    /// # Example
+    /// ```
+    /// use hay::matching::{Error, Matcher, BRACKETS};
+    /// let mut matcher = Matcher::new(BRACKETS);
+    /// assert_eq!(
+    ///     matcher.push(')'),
+    ///     Err(Error::UnexpectedClose { position: 0, found: ')' }),
+    /// );
+    /// ```
+    pub fn push(&mut self, item: T) -> Result<(), Error<T>> {
+        let position = self.position;
+        self.position += 1;
+        if let Some(&(pair, _)) = self.open.top() {
+            if item == self.pairs[pair].1 {
+                self.open.pop();
+                return Ok(());
+            }
+        }
+        if let Some(pair) = self.pairs.iter().position(|(open, _)| *open == item) {
+            self.open.push((pair, position));
+            return Ok(());
+        }
+        if !self.pairs.iter().any(|(_, close)| *close == item) {
+            return Ok(());
+        }
+        Err(match self.open.top() {
+            Some(&(pair, open_position)) => Error::Mismatched {
+                position,
+                found: item,
+                expected: self.pairs[pair].1.clone(),
+                open_position,
+            },
+            None => Error::UnexpectedClose {
+                position,
+                found: item,
+            },
+        })
+    }
+
+    /// Checks each item in turn, stopping at the first error.
+    /// # Errors
+    /// Returns the first error [Matcher::push] returns.
+    #[inline(always)]
+    pub fn feed<I: IntoIterator<Item = T>>(&mut self, items: I) -> Result<(), Error<T>> {
+        items.into_iter().try_for_each(|item| self.push(item))
+    }
+
+    /// Checks that every delimiter has been closed.
+    /// # Errors
+    /// Returns [Error::Unclosed] with the innermost delimiter still open, if any.
+    /// # Example
+    /// ```
+    /// use hay::matching::{Error, Matcher, BRACKETS};
+    /// let mut matcher = Matcher::new(BRACKETS);
+    /// matcher.feed("([".chars()).unwrap();
+    /// assert_eq!(
+    ///     matcher.finish(),
+    ///     Err(Error::Unclosed { open_position: 1, expected: ']' }),
+    /// );
+    /// ```
+    pub fn finish(&self) -> Result<(), Error<T>> {
+        match self.open.top() {
+            Some(&(pair, open_position)) => Err(Error::Unclosed {
+                open_position,
+                expected: self.pairs[pair].1.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the closer of the innermost open delimiter, if any.
+    #[inline(always)]
+    pub fn expected(&self) -> Option<&T> {
+        self.open.top().map(|&(pair, _)| &self.pairs[pair].1)
+    }
+
+    /// Returns the number of delimiters open.
+    #[inline(always)]
+    pub const fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    /// Returns the position the next item will be at.
+    #[inline(always)]
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Forgets every open delimiter and starts counting positions from zero again.
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.open.clear();
+        self.position = 0;
+    }
+}
+
+/// Checks that the delimiters in `items` are balanced.
+/// # Errors
+/// Returns the first [Error] found.
+/// # Example
+/// ```
+/// use hay::matching;
+/// let tags = [("<b>", "</b>"), ("<i>", "</i>")];
+/// assert!(matching::check(&tags, ["<b>", "<i>", "hi", "</i>", "</b>"]).is_ok());
+/// assert!(matching::check(&tags, ["<b>", "<i>", "</b>"]).is_err());
+/// ```
+#[inline(always)]
+pub fn check<T: PartialEq + Clone>(
+    pairs: &[(T, T)],
+    items: impl IntoIterator<Item = T>,
+) -> Result<(), Error<T>> {
+    let mut matcher = Matcher::new(pairs);
+    matcher.feed(items)?;
+    matcher.finish()
+}
+
+/// A tag of XML or HTML-like markup, with any attributes ignored.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Tag<N> {
+    /// An opening tag, like `<p class="note">`.
+    Open(N),
+    /// A closing tag, like `</p>`.
+    Close(N),
+    /// A self-closing tag, like `<br/>`, which doesn't need closing.
+    Empty(N),
+}
+impl<'a> Tag<&'a str> {
+    /// Parses a tag from its text, from `<` to `>` inclusive, returning its name and ignoring
+    /// any attributes, or [None] if it isn't an opening, closing, or self-closing tag.
+    ///
+    /// Comments, declarations, and processing instructions, like `<!-- -->`, `<!DOCTYPE html>`,
+    /// and `<?xml?>`, aren't tags.
+    /// # Example
+    /// ```
+    /// use hay::matching::Tag;
+    /// assert_eq!(Tag::parse("<a href=\"/\">"), Some(Tag::Open("a")));
+    /// assert_eq!(Tag::parse("</a >"), Some(Tag::Close("a")));
+    /// assert_eq!(Tag::parse("<img src=x />"), Some(Tag::Empty("img")));
+    /// assert_eq!(Tag::parse("<!-- a -->"), None);
+    /// ```
+    pub fn parse(text: &'a str) -> Option<Self> {
+        let inner = text.strip_prefix('<')?.strip_suffix('>')?;
+        let (inner, close) = match inner.strip_prefix('/') {
+            Some(inner) => (inner, true),
+            None => (inner, false),
+        };
+        let (inner, empty) = match inner.strip_suffix('/') {
+            Some(inner) if !close => (inner, true),
+            _ => (inner, false),
+        };
+        let end = inner
+            .find(|c: char| c.is_whitespace() || c == '/')
+            .unwrap_or(inner.len());
+        let name = &inner[..end];
+        if name.is_empty() || name.starts_with(['!', '?']) {
+            return None;
+        }
+        Some(match (close, empty) {
+            (true, _) => Self::Close(name),
+            (false, true) => Self::Empty(name),
+            (false, false) => Self::Open(name),
+        })
+    }
+}
+
+/// A checker of balanced tags, fed one [Tag] at a time along with its position, such as its
+/// byte offset in the markup.
+///
+/// Errors are reported as [Error]s with the names of the tags, where the position of the
+/// innermost open tag is the one it was fed at.
+/// # Example
+/// ```
+/// use hay::matching::{Error, Tag, TagMatcher};
+/// let mut matcher = TagMatcher::new();
+/// let markup = "<ul><li>one<li>two</ul>";
+/// // Feeding each tag at its byte offset.
+/// let mut tags = markup.match_indices('<').map(|(start, _)| {
+///     let end = start + markup[start..].find('>').unwrap() + 1;
+///     (Tag::parse(&markup[start..end]).unwrap(), start)
+/// });
+/// for (tag, position) in tags.by_ref().take(3) {
+///     matcher.push(tag, position).unwrap();
+/// }
+/// let (close, position) = tags.next().unwrap();
+/// assert_eq!(
+///     matcher.push(close, position),
+///     Err(Error::Mismatched {
+///         position: 18,
+///         found: "ul",
+///         expected: "li",
+///         open_position: 11,
+///     }),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct TagMatcher<N> {
+    open: Stack<(N, usize)>,
+}
+impl<N: PartialEq + Clone> TagMatcher<N> {
+    /// Constructs a new `TagMatcher<N>`.
+    ///
+    /// The matcher will not allocate until a tag is opened.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self { open: Stack::new() }
+    }
+
+    /// Checks the next tag, at the given position, opening or closing it.
+    /// # Errors
+    /// Returns [Error::Mismatched] or [Error::UnexpectedClose] if the tag is a closing one which
+    /// doesn't match the innermost open tag, in which case it is skipped.
+    pub fn push(&mut self, tag: Tag<N>, position: usize) -> Result<(), Error<N>> {
+        let name = match tag {
+            Tag::Open(name) => {
+                self.open.push((name, position));
+                return Ok(());
+            }
+            Tag::Close(name) => name,
+            Tag::Empty(_) => return Ok(()),
+        };
+        match self.open.top() {
+            Some((open, _)) if *open == name => {
+                self.open.pop();
+                Ok(())
+            }
+            Some((open, open_position)) => Err(Error::Mismatched {
+                position,
+                found: name,
+                expected: open.clone(),
+                open_position: *open_position,
+            }),
+            None => Err(Error::UnexpectedClose {
+                position,
+                found: name,
+            }),
+        }
+    }
+
+    /// Checks that every tag has been closed.
+    /// # Errors
+    /// Returns [Error::Unclosed] with the innermost tag still open, if any.
+    /// # Example
+    /// ```
+    /// use hay::matching::{Error, Tag, TagMatcher};
+    /// let mut matcher = TagMatcher::new();
+    /// matcher.push(Tag::Open("html"), 0).unwrap();
+    /// matcher.push(Tag::Open("body"), 6).unwrap();
+    /// assert_eq!(
+    ///     matcher.finish(),
+    ///     Err(Error::Unclosed { open_position: 6, expected: "body" }),
+    /// );
+    /// ```
+    pub fn finish(&self) -> Result<(), Error<N>> {
+        match self.open.top() {
+            Some((open, open_position)) => Err(Error::Unclosed {
+                open_position: *open_position,
+                expected: open.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns an iterator over the names of the open tags, from the outermost to the innermost.
+    #[inline(always)]
+    pub fn path(&self) -> impl DoubleEndedIterator<Item = &N> + ExactSizeIterator {
+        self.open.iter().map(|(name, _)| name)
+    }
+
+    /// Returns the name of the innermost open tag, if any.
+    #[inline(always)]
+    pub fn expected(&self) -> Option<&N> {
+        self.open.top().map(|(name, _)| name)
+    }
+
+    /// Returns the number of tags open.
+    #[inline(always)]
+    pub const fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    /// Forgets every open tag.
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.open.clear();
+    }
+}
+impl<N: PartialEq + Clone> Default for TagMatcher<N> {
+    /// Constructs a new `TagMatcher<N>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,207 @@
+use crate::Stack;
+use core::ops::Deref;
+
+/// Callbacks a [MeteredStack] makes as it is used, for wiring its depth and allocations into
+/// any metrics system.
+///
+/// Every method does nothing by default, so only the interesting ones need implementing. They
+/// take `&self`, so counters are usually atomics or cells.
+/// # Example
+/// ```
+/// use hay::StackMetrics;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// #[derive(Default)]
+/// struct Counters {
+///     pushes: AtomicUsize,
+///     reallocations: AtomicUsize,
+/// }
+/// impl StackMetrics for Counters {
+///     fn on_push(&self, _len: usize) {
+///         self.pushes.fetch_add(1, Ordering::Relaxed);
+///     }
+///
+///     fn on_realloc(&self, _old_capacity: usize, _capacity: usize) {
+///         self.reallocations.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+/// ```
+#[allow(unused_variables)]
+pub trait StackMetrics {
+    /// Called after an element is pushed, with the new length of the stack.
+    #[inline(always)]
+    fn on_push(&self, len: usize) {}
+
+    /// Called after an element is popped, with the new length of the stack.
+    #[inline(always)]
+    fn on_pop(&self, len: usize) {}
+
+    /// Called after the stack reallocates, with its old and new capacities.
+    #[inline(always)]
+    fn on_realloc(&self, old_capacity: usize, capacity: usize) {}
+}
+impl StackMetrics for () {}
+impl<M: StackMetrics + ?Sized> StackMetrics for &M {
+    /// Forwards to the referenced metrics.
+    #[inline(always)]
+    fn on_push(&self, len: usize) {
+        (**self).on_push(len);
+    }
+
+    /// Forwards to the referenced metrics.
+    #[inline(always)]
+    fn on_pop(&self, len: usize) {
+        (**self).on_pop(len);
+    }
+
+    /// Forwards to the referenced metrics.
+    #[inline(always)]
+    fn on_realloc(&self, old_capacity: usize, capacity: usize) {
+        (**self).on_realloc(old_capacity, capacity);
+    }
+}
+
+/// A stack which reports its pushes, pops, and reallocations to a [StackMetrics].
+/// # Example
+/// ```
+/// use hay::{MeteredStack, StackMetrics};
+/// use std::cell::Cell;
+/// #[derive(Default)]
+/// struct Depth(Cell<usize>);
+/// impl StackMetrics for Depth {
+///     fn on_push(&self, len: usize) {
+///         self.0.set(self.0.get().max(len));
+///     }
+/// }
+/// let depth = Depth::default();
+/// let mut stack = MeteredStack::with_metrics(&depth);
+/// stack.push(1);
+/// stack.push(2);
+/// stack.pop();
+/// assert_eq!(depth.0.get(), 2);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MeteredStack<T, M: StackMetrics> {
+    stack: Stack<T>,
+    metrics: M,
+}
+impl<T, M: StackMetrics> MeteredStack<T, M> {
+    /// Constructs a new, empty `MeteredStack<T, M>` reporting to `metrics`.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::MeteredStack;
+    /// let stack: MeteredStack<i32, ()> = MeteredStack::with_metrics(());
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn with_metrics(metrics: M) -> Self {
+        Self {
+            stack: Stack::new(),
+            metrics,
+        }
+    }
+
+    /// Returns a reference to the metrics the stack reports to.
+    #[inline(always)]
+    pub const fn metrics(&self) -> &M {
+        &self.metrics
+    }
+
+    /// Returns a reference to the top element in the stack.
+    #[inline(always)]
+    pub const fn top(&self) -> Option<&T> {
+        self.stack.top()
+    }
+
+    /// Appends an element to the top of the stack, reporting the push and any reallocation.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::MeteredStack;
+    /// let mut stack = MeteredStack::with_metrics(());
+    /// stack.push(1);
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    pub fn push(&mut self, value: T) {
+        let capacity = self.stack.capacity();
+        self.stack.push(value);
+        self.reallocated(capacity);
+        self.metrics.on_push(self.stack.len());
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty,
+    /// reporting the pop.
+    /// # Example
+    /// ```
+    /// use hay::MeteredStack;
+    /// let mut stack = MeteredStack::with_metrics(());
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.stack.pop()?;
+        self.metrics.on_pop(self.stack.len());
+        Some(value)
+    }
+
+    /// Clears the stack, popping all values without reporting each pop.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+
+    /// Returns the number of elements in the stack.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Consumes the stack, returning the elements and the metrics.
+    /// # Example
+    /// ```
+    /// use hay::MeteredStack;
+    /// let mut stack = MeteredStack::with_metrics(());
+    /// stack.push(1);
+    /// let (mut stack, ()) = stack.into_parts();
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn into_parts(self) -> (Stack<T>, M) {
+        (self.stack, self.metrics)
+    }
+
+    /// Reports a reallocation if the capacity changed from `old_capacity`.
+    #[inline(always)]
+    fn reallocated(&self, old_capacity: usize) {
+        let capacity = self.stack.capacity();
+        if capacity != old_capacity {
+            self.metrics.on_realloc(old_capacity, capacity);
+        }
+    }
+}
+impl<T, M: StackMetrics> Deref for MeteredStack<T, M> {
+    /// The resulting type when dereferencing `MeteredStack<T, M>`.
+    type Target = [T];
+
+    /// Dereferences a `MeteredStack<T, M>`.
+    fn deref(&self) -> &Self::Target {
+        &self.stack
+    }
+}
+impl<T, M: StackMetrics> Extend<T> for MeteredStack<T, M> {
+    /// Pushes a collection of values onto a stack, reporting each push and any reallocation.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
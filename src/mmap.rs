@@ -0,0 +1,263 @@
+use bytemuck::Pod;
+use core::{
+    fmt::{self, Debug},
+    marker::PhantomData,
+    mem,
+};
+use memmap2::{MmapMut, MmapOptions};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Error, ErrorKind},
+    path::Path,
+};
+
+/// The magic bytes opening a stack file.
+const MAGIC: [u8; 4] = *b"hayM";
+/// The size of the header, holding the magic bytes, the size of an element as a little-endian
+/// `u32`, and the length of the stack as a little-endian `u64`.
+const HEADER: usize = 16;
+/// The number of bytes the file is grown by at least.
+const MIN_GROWTH: usize = 64 * 1024;
+
+/// A stack of plain-old-data elements stored in a memory-mapped file, which grows by remapping.
+///
+/// The elements live in the page cache rather than the heap, so gigantic stacks don't pressure
+/// the allocator, and they survive restarts of the process. Changes reach the file as the
+/// operating system writes pages back, or when [MmapStack::flush] is called.
+/// # Example
+/// ```
+/// use hay::MmapStack;
+/// let path = std::env::temp_dir().join("hay-mmap-example");
+/// # let _ = std::fs::remove_file(&path);
+/// // SAFETY: Nothing else touches the file.
+/// let mut stack = unsafe { MmapStack::<u64>::open(&path) }.unwrap();
+/// stack.push(1).unwrap();
+/// stack.push(2).unwrap();
+/// drop(stack);
+/// let mut stack = unsafe { MmapStack::<u64>::open(&path) }.unwrap();
+/// assert_eq!(stack.pop(), Some(2));
+/// assert_eq!(stack.pop(), Some(1));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct MmapStack<T: Pod> {
+    file: File,
+    map: MmapMut,
+    len: usize,
+    elements: PhantomData<T>,
+}
+impl<T: Pod> MmapStack<T> {
+    /// The offset of the first element in the file, which keeps every element aligned since the
+    /// map starts on a page boundary.
+    const OFFSET: usize = if mem::align_of::<T>() > HEADER {
+        mem::align_of::<T>()
+    } else {
+        HEADER
+    };
+
+    /// Opens the stack stored in the file at `path`, creating an empty one if the file doesn't
+    /// exist or is empty.
+    /// # Safety
+    /// The file must not be modified by anything else, including another `MmapStack`, while the
+    /// stack is open, since its elements are read straight from the mapped memory.
+    /// # Errors
+    /// Returns an error if the file can't be opened or mapped, if `T` is zero-sized, or one of
+    /// kind [ErrorKind::InvalidData] if the file holds something other than a stack of elements
+    /// the size of `T`.
+    pub unsafe fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        if mem::size_of::<T>() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "zero-sized elements can't be stored",
+            ));
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let fresh = file.metadata()?.len() == 0;
+        if fresh {
+            file.set_len(Self::OFFSET as u64)?;
+        }
+        // SAFETY: The caller promises nothing else modifies the file.
+        let mut map = unsafe { MmapMut::map_mut(&file)? };
+        if fresh {
+            map[..4].copy_from_slice(&MAGIC);
+            map[4..8].copy_from_slice(&(mem::size_of::<T>() as u32).to_le_bytes());
+        }
+        if map.len() < HEADER || map[..4] != MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "not a stack file"));
+        }
+        if u32::from_le_bytes(map[4..8].try_into().unwrap()) as usize != mem::size_of::<T>() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "stack file holds elements of a different size",
+            ));
+        }
+        let len = u64::from_le_bytes(map[8..16].try_into().unwrap());
+        let mut stack = Self {
+            file,
+            map,
+            len: 0,
+            elements: PhantomData,
+        };
+        match usize::try_from(len) {
+            Ok(len) if len <= stack.capacity() => stack.len = len,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "stack file is shorter than its length",
+                ))
+            }
+        }
+        Ok(stack)
+    }
+
+    /// Returns the number of elements the file can hold without growing.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.map.len().saturating_sub(Self::OFFSET) / mem::size_of::<T>()
+    }
+
+    /// Returns a reference to the top element in the stack.
+    #[inline(always)]
+    pub fn top(&self) -> Option<&T> {
+        self.as_slice().last()
+    }
+
+    /// Returns a mutable reference to the top element in the stack.
+    #[inline(always)]
+    pub fn top_mut(&mut self) -> Option<&mut T> {
+        self.as_mut_slice().last_mut()
+    }
+
+    /// Appends an element to the top of the stack, growing the file if it is full.
+    /// # Errors
+    /// Returns an error if the file can't be grown or remapped, in which case the stack is left
+    /// unchanged.
+    /// # Example
+    /// ```
+    /// use hay::MmapStack;
+    /// let path = std::env::temp_dir().join("hay-mmap-push");
+    /// # let _ = std::fs::remove_file(&path);
+    /// // SAFETY: Nothing else touches the file.
+    /// let mut stack = unsafe { MmapStack::<u32>::open(&path) }.unwrap();
+    /// stack.push(7).unwrap();
+    /// assert_eq!(stack.top(), Some(&7));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn push(&mut self, value: T) -> io::Result<()> {
+        if self.len == self.capacity() {
+            self.grow()?;
+        }
+        let start = Self::OFFSET + self.len * mem::size_of::<T>();
+        self.map[start..start + mem::size_of::<T>()].copy_from_slice(bytemuck::bytes_of(&value));
+        self.set_len(self.len + 1);
+        Ok(())
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    ///
+    /// The file keeps its size, so pushing again doesn't need to grow it.
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        let value = *self.top()?;
+        self.set_len(self.len - 1);
+        Some(value)
+    }
+
+    /// Clears the stack, popping all values.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.set_len(0);
+    }
+
+    /// Returns the number of elements in the stack.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Extracts a slice of the elements, from the bottom of the stack up.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        let end = Self::OFFSET + self.len * mem::size_of::<T>();
+        bytemuck::cast_slice(&self.map[Self::OFFSET..end])
+    }
+
+    /// Extracts a mutable slice of the elements, from the bottom of the stack up.
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        let end = Self::OFFSET + self.len * mem::size_of::<T>();
+        bytemuck::cast_slice_mut(&mut self.map[Self::OFFSET..end])
+    }
+
+    /// Shrinks the file to fit the elements on the stack.
+    /// # Errors
+    /// Returns an error if the file can't be shrunk or remapped.
+    pub fn shrink_to_fit(&mut self) -> io::Result<()> {
+        self.resize(self.len)
+    }
+
+    /// Writes every change to the stack back to the file, blocking until it is done.
+    /// # Errors
+    /// Returns an error if the changes can't be written.
+    #[inline(always)]
+    pub fn flush(&self) -> io::Result<()> {
+        self.map.flush()
+    }
+
+    /// Sets the length of the stack, recording it in the header.
+    #[inline(always)]
+    fn set_len(&mut self, len: usize) {
+        self.len = len;
+        self.map[8..16].copy_from_slice(&(len as u64).to_le_bytes());
+    }
+
+    /// Grows the file to hold at least twice as many elements.
+    #[cold]
+    fn grow(&mut self) -> io::Result<()> {
+        let capacity = self
+            .capacity()
+            .checked_mul(2)
+            .and_then(|capacity| capacity.checked_add(MIN_GROWTH / mem::size_of::<T>() + 1))
+            .ok_or_else(|| Error::new(ErrorKind::OutOfMemory, "capacity overflow"))?;
+        self.resize(capacity)
+    }
+
+    /// Resizes the file to hold exactly `capacity` elements, and remaps it.
+    ///
+    /// The mapping never covers more than the file, even if resizing fails halfway, since
+    /// touching a page past its end kills the process.
+    fn resize(&mut self, capacity: usize) -> io::Result<()> {
+        let len = capacity
+            .checked_mul(mem::size_of::<T>())
+            .and_then(|len| len.checked_add(Self::OFFSET))
+            .ok_or_else(|| Error::new(ErrorKind::OutOfMemory, "capacity overflow"))?;
+        self.map.flush()?;
+        if len < self.map.len() {
+            // SAFETY: The caller of `open` promised nothing else modifies the file, and the file
+            // is still longer than the new mapping.
+            self.map = unsafe { MmapOptions::new().len(len).map_mut(&self.file)? };
+            self.file.set_len(len as u64)?;
+        } else {
+            self.file.set_len(len as u64)?;
+            // SAFETY: The caller of `open` promised nothing else modifies the file.
+            self.map = unsafe { MmapMut::map_mut(&self.file)? };
+        }
+        Ok(())
+    }
+}
+impl<T: Pod + Debug> Debug for MmapStack<T> {
+    /// Formats the elements, from the bottom of the stack up.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
@@ -0,0 +1,179 @@
+use crate::Stack;
+use core::{
+    cell::RefCell,
+    fmt::{self, Debug},
+    mem::{self, ManuallyDrop},
+    ops::{Deref, DerefMut},
+};
+
+/// A pool of reusable objects, kept on a free list which hands out the most recently released
+/// object first, while it is still warm in the cache.
+///
+/// Objects taken with [Pool::acquire] go back into the pool when their [PoolHandle] is dropped,
+/// as they are, so any state they hold should be reset after acquiring them. New objects are
+/// constructed by the pool's factory whenever it has none idle. Like [StackPool](crate::StackPool),
+/// the pool isn't shared between threads.
+/// # Example
+/// ```
+/// use hay::Pool;
+/// let pool = Pool::new(|| String::with_capacity(64));
+/// let mut buffer = pool.acquire();
+/// buffer.push_str("hello");
+/// drop(buffer);
+/// let mut buffer = pool.acquire();
+/// // The same buffer came back, so it should be reset before use.
+/// assert_eq!(*buffer, "hello");
+/// buffer.clear();
+/// assert_eq!(pool.idle(), 0);
+/// ```
+pub struct Pool<T, F = fn() -> T> {
+    free: RefCell<Stack<T>>,
+    factory: F,
+    limit: usize,
+}
+impl<T, F: Fn() -> T> Pool<T, F> {
+    /// Constructs a new, empty `Pool<T, F>` which constructs objects with `factory`, and keeps
+    /// every object released to it.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(factory: F) -> Self {
+        Self::with_limit(usize::MAX, factory)
+    }
+
+    /// Constructs a new, empty `Pool<T, F>` which constructs objects with `factory`, and keeps at
+    /// most `limit` idle objects, dropping any released beyond that.
+    /// # Example
+    /// ```
+    /// use hay::Pool;
+    /// let pool = Pool::with_limit(1, Vec::<u8>::new);
+    /// let (a, b) = (pool.acquire(), pool.acquire());
+    /// drop((a, b));
+    /// assert_eq!(pool.idle(), 1);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn with_limit(limit: usize, factory: F) -> Self {
+        Self {
+            free: RefCell::new(Stack::new()),
+            factory,
+            limit,
+        }
+    }
+
+    /// Takes the most recently released object from the pool, or constructs a new one if the
+    /// pool has none idle.
+    #[inline(always)]
+    pub fn acquire(&self) -> PoolHandle<'_, T, F> {
+        let value = self.free.borrow_mut().pop();
+        PoolHandle {
+            pool: self,
+            value: ManuallyDrop::new(value.unwrap_or_else(&self.factory)),
+        }
+    }
+
+    /// Adds an object to the pool, unless the pool already holds its limit of idle objects.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::Pool;
+    /// let pool = Pool::new(|| 0);
+    /// pool.release(7);
+    /// let released = pool.acquire();
+    /// assert_eq!(*released, 7);
+    /// assert_eq!(*pool.acquire(), 0);
+    /// ```
+    pub fn release(&self, value: T) {
+        let mut free = self.free.borrow_mut();
+        if free.len() < self.limit {
+            free.push(value);
+            return;
+        }
+        // Dropping the object after the borrow, so its destructor can't observe the pool
+        // borrowed.
+        drop(free);
+        drop(value);
+    }
+
+    /// Returns the number of idle objects in the pool.
+    #[inline(always)]
+    pub fn idle(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    /// Drops every idle object.
+    #[inline(always)]
+    pub fn clear(&self) {
+        // Taking the objects out first, so dropping them can't observe the pool borrowed.
+        drop(mem::replace(&mut *self.free.borrow_mut(), Stack::new()));
+    }
+}
+impl<T: Default> Default for Pool<T> {
+    /// Constructs a new, empty `Pool<T>` which constructs objects with [Default::default].
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new(T::default)
+    }
+}
+impl<T: Debug, F> Debug for Pool<T, F> {
+    /// Formats the idle objects and the limit.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("free", &self.free)
+            .field("limit", &self.limit)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An object acquired from a [Pool], which goes back into the pool when dropped.
+pub struct PoolHandle<'a, T, F: Fn() -> T = fn() -> T> {
+    pool: &'a Pool<T, F>,
+    value: ManuallyDrop<T>,
+}
+impl<T, F: Fn() -> T> PoolHandle<'_, T, F> {
+    /// Detaches the object from its pool, so it isn't released when dropped.
+    /// # Example
+    /// ```
+    /// use hay::{Pool, PoolHandle};
+    /// let pool = Pool::new(|| 1);
+    /// let value = PoolHandle::into_inner(pool.acquire());
+    /// assert_eq!(value, 1);
+    /// assert_eq!(pool.idle(), 0);
+    /// ```
+    #[inline(always)]
+    pub fn into_inner(this: Self) -> T {
+        let mut this = ManuallyDrop::new(this);
+        // SAFETY: The handle is never used or dropped again, so the value is taken only once.
+        unsafe { ManuallyDrop::take(&mut this.value) }
+    }
+}
+impl<T, F: Fn() -> T> Deref for PoolHandle<'_, T, F> {
+    type Target = T;
+
+    /// Dereferences to the acquired object.
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+impl<T, F: Fn() -> T> DerefMut for PoolHandle<'_, T, F> {
+    /// Mutably dereferences to the acquired object.
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+impl<T: Debug, F: Fn() -> T> Debug for PoolHandle<'_, T, F> {
+    /// Formats the acquired object.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PoolHandle").field(&*self.value).finish()
+    }
+}
+impl<T, F: Fn() -> T> Drop for PoolHandle<'_, T, F> {
+    /// Releases the object back to its pool.
+    fn drop(&mut self) {
+        // SAFETY: The handle is being dropped, so the value is never used again.
+        let value = unsafe { ManuallyDrop::take(&mut self.value) };
+        self.pool.release(value);
+    }
+}
@@ -0,0 +1,313 @@
+use crate::Stack;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use core::{fmt, marker::PhantomData};
+use core::{mem, ops::Deref};
+#[cfg(feature = "serde")]
+use serde::{
+    de::{self, EnumAccess, VariantAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// An operation on a stack, as recorded by an [OpLog].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Op<T> {
+    /// An element was pushed.
+    Push(T),
+    /// The top element was popped.
+    Pop,
+    /// The stack was cleared.
+    Clear,
+}
+impl<T> Op<T> {
+    /// Applies the operation to `stack`.
+    ///
+    /// Popping an empty stack does nothing, just as it did when the operation was recorded.
+    /// # Example
+    /// ```
+    /// use hay::{Op, Stack};
+    /// let mut stack = Stack::new();
+    /// Op::Push(1).apply(&mut stack);
+    /// Op::Push(2).apply(&mut stack);
+    /// Op::Pop.apply(&mut stack);
+    /// assert_eq!(*stack, [1]);
+    /// ```
+    #[inline(always)]
+    pub fn apply(self, stack: &mut Stack<T>) {
+        match self {
+            Self::Push(value) => stack.push(value),
+            Self::Pop => drop(stack.pop()),
+            Self::Clear => stack.clear(),
+        }
+    }
+}
+impl<T> Stack<T> {
+    /// Applies each operation in `ops` to the stack in order, reconstructing the state a
+    /// recorded stack was in.
+    /// # Example
+    /// ```
+    /// use hay::{Op, Stack};
+    /// let mut stack = Stack::new();
+    /// stack.replay([Op::Push(1), Op::Clear, Op::Push(2), Op::Push(3), Op::Pop]);
+    /// assert_eq!(*stack, [2]);
+    /// ```
+    pub fn replay<I: IntoIterator<Item = Op<T>>>(&mut self, ops: I) {
+        for op in ops {
+            op.apply(self);
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for Op<T> {
+    /// Serializes an operation as an enum variant, carrying the element for a push.
+    /// # Example
+    /// ```
+    /// use hay::Op;
+    /// assert_eq!(serde_json::to_string(&Op::Push(1)).unwrap(), r#"{"Push":1}"#);
+    /// assert_eq!(serde_json::to_string(&Op::<i32>::Pop).unwrap(), r#""Pop""#);
+    /// ```
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Push(value) => serializer.serialize_newtype_variant("Op", 0, "Push", value),
+            Self::Pop => serializer.serialize_unit_variant("Op", 1, "Pop"),
+            Self::Clear => serializer.serialize_unit_variant("Op", 2, "Clear"),
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Op<T> {
+    /// Deserializes an operation from an enum variant.
+    /// # Example
+    /// ```
+    /// use hay::Op;
+    /// let ops: Vec<Op<i32>> = serde_json::from_str(r#"[{"Push":1},"Pop","Clear"]"#).unwrap();
+    /// assert_eq!(ops, [Op::Push(1), Op::Pop, Op::Clear]);
+    /// ```
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// The names of the variants, in order.
+        const VARIANTS: &[&str] = &["Push", "Pop", "Clear"];
+        /// The variants, without the element.
+        enum Variant {
+            Push,
+            Pop,
+            Clear,
+        }
+        impl<'de> Deserialize<'de> for Variant {
+            /// Deserializes a variant from its name or index.
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                /// Visits the name or index of a variant.
+                struct VariantVisitor;
+                impl Visitor<'_> for VariantVisitor {
+                    /// The variant visited.
+                    type Value = Variant;
+
+                    /// Describes the expected variant.
+                    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str("a variant of `Op`")
+                    }
+
+                    /// Visits the index of a variant.
+                    fn visit_u64<E: de::Error>(self, index: u64) -> Result<Variant, E> {
+                        match index {
+                            0 => Ok(Variant::Push),
+                            1 => Ok(Variant::Pop),
+                            2 => Ok(Variant::Clear),
+                            _ => Err(E::invalid_value(
+                                de::Unexpected::Unsigned(index),
+                                &"a variant index below 3",
+                            )),
+                        }
+                    }
+
+                    /// Visits the name of a variant.
+                    fn visit_str<E: de::Error>(self, name: &str) -> Result<Variant, E> {
+                        match name {
+                            "Push" => Ok(Variant::Push),
+                            "Pop" => Ok(Variant::Pop),
+                            "Clear" => Ok(Variant::Clear),
+                            _ => Err(E::unknown_variant(name, VARIANTS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(VariantVisitor)
+            }
+        }
+        /// Visits an operation.
+        struct OpVisitor<T>(PhantomData<T>);
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for OpVisitor<T> {
+            /// The operation visited.
+            type Value = Op<T>;
+
+            /// Describes the expected operation.
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an `Op`")
+            }
+
+            /// Visits the variant of an operation, and the element of a push.
+            fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Op<T>, A::Error> {
+                match data.variant()? {
+                    (Variant::Push, variant) => variant.newtype_variant().map(Op::Push),
+                    (Variant::Pop, variant) => variant.unit_variant().map(|()| Op::Pop),
+                    (Variant::Clear, variant) => variant.unit_variant().map(|()| Op::Clear),
+                }
+            }
+        }
+        deserializer.deserialize_enum("Op", VARIANTS, OpVisitor(PhantomData))
+    }
+}
+
+/// A stack which records every operation made on it, so they can be serialized and replayed
+/// onto a fresh stack with [Stack::replay].
+///
+/// Replaying a log reproduces the exact sequence of states the stack went through, for
+/// reproducing bugs deterministically or rebuilding state from events.
+/// # Example
+/// ```
+/// use hay::{OpLog, Stack};
+/// let mut log = OpLog::new();
+/// log.push(1);
+/// log.push(2);
+/// log.pop();
+/// let mut stack = Stack::new();
+/// stack.replay(log.take_ops());
+/// assert_eq!(*stack, *log);
+/// ```
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct OpLog<T> {
+    stack: Stack<T>,
+    ops: Vec<Op<T>>,
+}
+impl<T> OpLog<T> {
+    /// Constructs a new, empty `OpLog<T>`.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::OpLog;
+    /// let log: OpLog<i32> = OpLog::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            stack: Stack::new(),
+            ops: Vec::new(),
+        }
+    }
+
+    /// Returns a reference to the top element in the stack.
+    #[inline(always)]
+    pub const fn top(&self) -> Option<&T> {
+        self.stack.top()
+    }
+
+    /// Appends an element to the top of the stack, recording a clone of it.
+    /// # Panics
+    /// Panics if the new capacity of the stack or the log exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::{Op, OpLog};
+    /// let mut log = OpLog::new();
+    /// log.push(1);
+    /// assert_eq!(log.ops(), [Op::Push(1)]);
+    /// ```
+    #[inline(always)]
+    pub fn push(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        self.ops.push(Op::Push(value.clone()));
+        self.stack.push(value);
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty,
+    /// recording the pop either way.
+    /// # Example
+    /// ```
+    /// use hay::{Op, OpLog};
+    /// let mut log = OpLog::new();
+    /// log.push(1);
+    /// assert_eq!(log.pop(), Some(1));
+    /// assert_eq!(log.ops(), [Op::Push(1), Op::Pop]);
+    /// ```
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        self.ops.push(Op::Pop);
+        self.stack.pop()
+    }
+
+    /// Clears the stack, popping all values, and records it.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.ops.push(Op::Clear);
+        self.stack.clear();
+    }
+
+    /// Returns the number of elements in the stack.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Returns the operations recorded so far, oldest first.
+    #[inline(always)]
+    pub fn ops(&self) -> &[Op<T>] {
+        &self.ops
+    }
+
+    /// Takes the operations recorded so far, oldest first, leaving the log empty while the stack
+    /// keeps its elements.
+    /// # Example
+    /// ```
+    /// use hay::{Op, OpLog};
+    /// let mut log = OpLog::new();
+    /// log.push(1);
+    /// assert_eq!(log.take_ops(), [Op::Push(1)]);
+    /// assert!(log.ops().is_empty());
+    /// assert_eq!(log.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn take_ops(&mut self) -> Vec<Op<T>> {
+        mem::take(&mut self.ops)
+    }
+
+    /// Consumes the log, returning the stack and the operations recorded.
+    #[inline(always)]
+    pub fn into_parts(self) -> (Stack<T>, Vec<Op<T>>) {
+        (self.stack, self.ops)
+    }
+}
+impl<T> Deref for OpLog<T> {
+    /// The resulting type when dereferencing `OpLog<T>`.
+    type Target = [T];
+
+    /// Dereferences an `OpLog<T>`.
+    fn deref(&self) -> &Self::Target {
+        &self.stack
+    }
+}
+impl<T: Clone> From<Stack<T>> for OpLog<T> {
+    /// Starts recording a stack, logging its elements as pushes so replaying the log from an
+    /// empty stack rebuilds it.
+    /// # Example
+    /// ```
+    /// use hay::{OpLog, Stack};
+    /// let mut stack = Stack::new();
+    /// stack.push(1);
+    /// let mut log = OpLog::from(stack);
+    /// log.pop();
+    /// let mut replayed = Stack::new();
+    /// replayed.replay(log.take_ops());
+    /// assert!(replayed.is_empty());
+    /// ```
+    fn from(stack: Stack<T>) -> Self {
+        let ops = stack.iter().cloned().map(Op::Push).collect();
+        Self { stack, ops }
+    }
+}
@@ -0,0 +1,85 @@
+//! Integration with `rayon`, for filling and consuming stacks in parallel.
+use crate::Stack;
+use core::mem;
+use rayon::{
+    iter::{IntoParallelIterator, ParallelExtend},
+    slice, vec,
+};
+
+impl<T: Send> ParallelExtend<T> for Stack<T> {
+    /// Pushes a parallel collection of values onto a stack, in the order they are produced.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// use rayon::prelude::*;
+    /// let mut stack = Stack::new();
+    /// stack.par_extend((0..4).into_par_iter());
+    /// assert_eq!(stack.pop(), Some(3));
+    /// ```
+    fn par_extend<I: IntoParallelIterator<Item = T>>(&mut self, par_iter: I) {
+        self.vec.par_extend(par_iter);
+    }
+}
+impl<'a, T: 'a + Copy + Send + Sync> ParallelExtend<&'a T> for Stack<T> {
+    /// Pushes a parallel collection of values onto a stack, in the order they are produced.
+    fn par_extend<I: IntoParallelIterator<Item = &'a T>>(&mut self, par_iter: I) {
+        self.vec.par_extend(par_iter);
+    }
+}
+impl<T: Send> IntoParallelIterator for Stack<T> {
+    type Iter = vec::IntoIter<T>;
+    type Item = T;
+
+    /// Consumes a stack in parallel, indexed from its bottom to its top.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// use rayon::prelude::*;
+    /// let mut stack = Stack::new();
+    /// stack.extend([1, 2, 3]);
+    /// assert_eq!(stack.into_par_iter().sum::<i32>(), 6);
+    /// ```
+    fn into_par_iter(self) -> Self::Iter {
+        self.vec.into_par_iter()
+    }
+}
+impl<'a, T: Sync> IntoParallelIterator for &'a Stack<T> {
+    type Iter = slice::Iter<'a, T>;
+    type Item = &'a T;
+
+    /// Iterates over a stack in parallel, indexed from its bottom to its top.
+    fn into_par_iter(self) -> Self::Iter {
+        (&self.vec).into_par_iter()
+    }
+}
+impl<'a, T: Send> IntoParallelIterator for &'a mut Stack<T> {
+    type Iter = slice::IterMut<'a, T>;
+    type Item = &'a mut T;
+
+    /// Mutably iterates over a stack in parallel, indexed from its bottom to its top.
+    fn into_par_iter(self) -> Self::Iter {
+        (&mut self.vec).into_par_iter()
+    }
+}
+impl<T: Send> Stack<T> {
+    /// Removes every element from the stack, returning a parallel iterator over them which frees
+    /// their buffer once it is done.
+    ///
+    /// The stack is left empty without an allocation, ready to be reused. The iterator is indexed
+    /// from the bottom to the top, but when order doesn't matter the elements can be consumed on
+    /// as many threads as rayon has.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// use rayon::prelude::*;
+    /// let mut stack = Stack::new();
+    /// stack.extend(0..1000u64);
+    /// assert_eq!(stack.par_drain().map(|value| value * 2).sum::<u64>(), 999_000);
+    /// assert!(stack.is_empty());
+    /// assert_eq!(stack.capacity(), 0);
+    /// ```
+    #[inline(always)]
+    pub fn par_drain(&mut self) -> vec::IntoIter<T> {
+        mem::take(&mut self.vec).into_par_iter()
+    }
+}
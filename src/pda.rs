@@ -0,0 +1,239 @@
+//! A deterministic pushdown automaton, recognizing input with a finite set of states and a
+//! [Stack] of symbols.
+//!
+//! An automaton is built from rules, each moving from one state to another on an input symbol,
+//! or on none, while replacing the symbol on top of the stack. Running input through it reports
+//! whether the input was accepted, along with the rules applied on the way.
+//! # Example
+//! ```
+//! use hay::pda::{Halt, Pda};
+//! // Recognizes balanced runs of a's followed by as many b's.
+//! let pda = Pda::new("reading a")
+//!     .rule("reading a", Some('a'), None, "reading a", ['A'])
+//!     .rule("reading a", Some('b'), Some('A'), "reading b", [])
+//!     .rule("reading b", Some('b'), Some('A'), "reading b", [])
+//!     .accept_empty_stack();
+//! assert!(pda.run("aabb".chars()).accepted());
+//! assert_eq!(pda.run("aab".chars()).halt, Halt::Rejected);
+//! assert_eq!(pda.run("abab".chars()).halt, Halt::Stuck);
+//! ```
+use crate::Stack;
+use alloc::vec::Vec;
+
+/// A rule of a [Pda].
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Rule<S, I, G> {
+    from: S,
+    input: Option<I>,
+    pop: Option<G>,
+    to: S,
+    push: Vec<G>,
+}
+
+/// A deterministic pushdown automaton, with states of type `S`, input symbols of type `I`, and
+/// stack symbols of type `G`.
+///
+/// At each step the first rule which applies is taken, in the order the rules were added, so
+/// rules which consume no input should come after the ones they would otherwise shadow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pda<S, I, G> {
+    start: S,
+    bottom: Option<G>,
+    rules: Vec<Rule<S, I, G>>,
+    accepting: Vec<S>,
+    empty_stack: bool,
+}
+impl<S: PartialEq + Clone, I: PartialEq, G: PartialEq + Clone> Pda<S, I, G> {
+    /// Constructs a new `Pda<S, I, G>` starting in the given state, with an empty stack, no
+    /// rules, and no way to accept.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(start: S) -> Self {
+        Self {
+            start,
+            bottom: None,
+            rules: Vec::new(),
+            accepting: Vec::new(),
+            empty_stack: false,
+        }
+    }
+
+    /// Starts each run with a symbol on the stack, such as a marker for its bottom.
+    #[must_use]
+    #[inline(always)]
+    pub fn bottom(mut self, symbol: G) -> Self {
+        self.bottom = Some(symbol);
+        self
+    }
+
+    /// Adds a rule moving from state `from` to state `to`.
+    ///
+    /// The rule applies when `input` is the next input symbol, which it consumes, or always if
+    /// it is [None]. It also requires `pop` to be on top of the stack, which it pops, unless it
+    /// is [None]. Finally, it pushes the symbols in `push`, leaving the last on top.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    #[must_use]
+    pub fn rule(
+        mut self,
+        from: S,
+        input: Option<I>,
+        pop: Option<G>,
+        to: S,
+        push: impl IntoIterator<Item = G>,
+    ) -> Self {
+        self.rules.push(Rule {
+            from,
+            input,
+            pop,
+            to,
+            push: push.into_iter().collect(),
+        });
+        self
+    }
+
+    /// Accepts input which ends in the given state.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    #[must_use]
+    #[inline(always)]
+    pub fn accept(mut self, state: S) -> Self {
+        self.accepting.push(state);
+        self
+    }
+
+    /// Accepts input which ends with the stack empty, in any state.
+    #[must_use]
+    #[inline(always)]
+    pub const fn accept_empty_stack(mut self) -> Self {
+        self.empty_stack = true;
+        self
+    }
+
+    /// Runs input through the automaton from its start state, until it accepts or no rule
+    /// applies.
+    ///
+    /// Rules which consume no input keep being taken even after the input runs out, until the
+    /// automaton accepts, so a loop of them never halts. [Pda::run_limited] bounds the run.
+    #[inline(always)]
+    pub fn run(&self, input: impl IntoIterator<Item = I>) -> Run<S, G> {
+        self.run_limited(input, usize::MAX)
+    }
+
+    /// Runs input through the automaton like [Pda::run], but taking at most `steps` rules.
+    /// # Example
+    /// ```
+    /// use hay::pda::{Halt, Pda};
+    /// let pda = Pda::new(0).rule(0, None, None, 0, ['x']).accept(1);
+    /// let run = pda.run_limited("".chars(), 10);
+    /// assert_eq!(run.halt, Halt::StepLimit);
+    /// assert_eq!(run.stack.len(), 10);
+    /// ```
+    pub fn run_limited(&self, input: impl IntoIterator<Item = I>, steps: usize) -> Run<S, G> {
+        let mut input = input.into_iter().peekable();
+        let mut run = Run {
+            halt: Halt::Rejected,
+            state: self.start.clone(),
+            stack: Stack::new(),
+            consumed: 0,
+            trace: Vec::new(),
+        };
+        run.stack.extend(self.bottom.clone());
+        loop {
+            let next = input.peek();
+            if next.is_none() && self.accepts(&run) {
+                run.halt = Halt::Accepted;
+                return run;
+            }
+            if run.trace.len() == steps {
+                run.halt = Halt::StepLimit;
+                return run;
+            }
+            let Some(index) = self.rules.iter().position(|rule| {
+                rule.from == run.state
+                    && rule
+                        .pop
+                        .as_ref()
+                        .is_none_or(|pop| run.stack.top() == Some(pop))
+                    && match (&rule.input, next) {
+                        (None, _) => true,
+                        (Some(expected), Some(next)) => expected == next,
+                        (Some(_), None) => false,
+                    }
+            }) else {
+                run.halt = match next {
+                    Some(_) => Halt::Stuck,
+                    None => Halt::Rejected,
+                };
+                return run;
+            };
+            let rule = &self.rules[index];
+            if rule.input.is_some() {
+                input.next();
+                run.consumed += 1;
+            }
+            if rule.pop.is_some() {
+                run.stack.pop();
+            }
+            run.stack.extend(rule.push.iter().cloned());
+            run.state = rule.to.clone();
+            run.trace.push(index);
+        }
+    }
+
+    /// Returns `true` if the automaton accepts in the state and with the stack of `run`.
+    #[inline(always)]
+    fn accepts(&self, run: &Run<S, G>) -> bool {
+        self.accepting.contains(&run.state) || self.empty_stack && run.stack.is_empty()
+    }
+}
+
+/// Why a [Pda] stopped running.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Halt {
+    /// Every input symbol was consumed, and the automaton accepted.
+    Accepted,
+    /// Every input symbol was consumed, but the automaton didn't accept and no rule applied.
+    Rejected,
+    /// No rule applied to the next input symbol.
+    Stuck,
+    /// The automaton took as many steps as it was allowed to.
+    StepLimit,
+}
+
+/// The result of running input through a [Pda].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Run<S, G> {
+    /// Why the automaton stopped.
+    pub halt: Halt,
+    /// The state the automaton stopped in.
+    pub state: S,
+    /// The stack the automaton stopped with.
+    pub stack: Stack<G>,
+    /// The number of input symbols consumed.
+    pub consumed: usize,
+    /// The rules taken, in order, each as its index among the rules of the automaton.
+    pub trace: Vec<usize>,
+}
+impl<S, G> Run<S, G> {
+    /// Returns `true` if the automaton accepted the input.
+    /// # Example
+    /// ```
+    /// use hay::pda::Pda;
+    /// // Recognizes balanced parentheses, accepting once only the bottom marker is left.
+    /// let pda = Pda::new("open")
+    ///     .bottom('$')
+    ///     .rule("open", Some('('), None, "open", ['('])
+    ///     .rule("open", Some(')'), Some('('), "open", [])
+    ///     .rule("open", None, Some('$'), "done", [])
+    ///     .accept("done");
+    /// let run = pda.run("(())".chars());
+    /// assert!(run.accepted());
+    /// assert_eq!(run.trace, [0, 0, 1, 1, 2]);
+    /// assert!(!pda.run("(()".chars()).accepted());
+    /// ```
+    #[inline(always)]
+    pub const fn accepted(&self) -> bool {
+        matches!(self.halt, Halt::Accepted)
+    }
+}
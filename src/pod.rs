@@ -0,0 +1,55 @@
+use crate::Stack;
+use bytemuck::{Pod, PodCastError};
+
+impl<T: Pod> Stack<T> {
+    /// Views the elements of the stack as raw bytes, from its bottom to its top.
+    ///
+    /// Nothing is copied, so the bytes can be handed straight to network or disk I/O.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1u16.to_le());
+    /// stack.push(2u16.to_le());
+    /// assert_eq!(stack.as_bytes().len(), 4);
+    /// ```
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        bytemuck::cast_slice(&self.vec)
+    }
+
+    /// Views the elements of the stack as mutable raw bytes, from its bottom to its top.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(0u8);
+    /// stack.as_bytes_mut()[0] = 1;
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        bytemuck::cast_slice_mut(&mut self.vec)
+    }
+
+    /// Constructs a stack from raw bytes holding its elements, from its bottom to its top.
+    ///
+    /// The bytes are cast in place and copied into the stack's own allocation once.
+    /// # Errors
+    /// Returns an error if `bytes` isn't aligned for `T` or its length isn't a multiple of the
+    /// size of `T`.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack: Stack<u8> = Stack::from_bytes(&[1, 2]).unwrap();
+    /// assert_eq!(stack.pop(), Some(2));
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert!(Stack::<u16>::from_bytes(&[0; 3]).is_err());
+    /// ```
+    #[inline(always)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PodCastError> {
+        bytemuck::try_cast_slice(bytes).map(|elements| Self {
+            vec: elements.to_vec(),
+        })
+    }
+}
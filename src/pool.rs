@@ -0,0 +1,191 @@
+use crate::Stack;
+use alloc::vec::Vec;
+use core::{
+    cell::RefCell,
+    mem,
+    ops::{Deref, DerefMut},
+};
+
+/// A pool of reusable stacks, which keeps their allocations around between uses.
+///
+/// Stacks taken with [StackPool::get] go back into the pool when dropped, cleared but with
+/// their capacity retained. The pool isn't shared between threads, so the fast path is to give
+/// each thread its own in a `thread_local!`.
+/// # Example
+/// ```
+/// use hay::StackPool;
+/// thread_local! {
+///     static POOL: StackPool<u32> = const { StackPool::new() };
+/// }
+/// POOL.with(|pool| {
+///     let mut stack = pool.get();
+///     stack.push(1);
+///     assert_eq!(stack.pop(), Some(1));
+///     stack.push(2);
+/// });
+/// POOL.with(|pool| {
+///     let stack = pool.get();
+///     assert!(stack.is_empty());
+///     assert!(stack.as_vec().capacity() >= 1);
+/// });
+/// ```
+#[derive(Debug)]
+pub struct StackPool<T> {
+    free: RefCell<Vec<Stack<T>>>,
+    limit: usize,
+}
+impl<T> StackPool<T> {
+    /// Constructs a new, empty `StackPool<T>` which keeps every stack returned to it.
+    /// # Example
+    /// ```
+    /// use hay::StackPool;
+    /// let pool: StackPool<i32> = StackPool::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self::with_limit(usize::MAX)
+    }
+
+    /// Constructs a new, empty `StackPool<T>` which keeps at most `limit` idle stacks, dropping
+    /// any returned beyond that.
+    /// # Example
+    /// ```
+    /// use hay::StackPool;
+    /// let pool = StackPool::with_limit(1);
+    /// let (mut a, mut b) = (pool.get(), pool.get());
+    /// a.push(1);
+    /// b.push(2);
+    /// drop((a, b));
+    /// assert_eq!(pool.idle(), 1);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn with_limit(limit: usize) -> Self {
+        Self {
+            free: RefCell::new(Vec::new()),
+            limit,
+        }
+    }
+
+    /// Takes an empty stack from the pool, or constructs a new one if the pool has none idle.
+    /// # Example
+    /// ```
+    /// use hay::StackPool;
+    /// let pool = StackPool::new();
+    /// let mut stack = pool.get();
+    /// stack.push(1);
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn get(&self) -> Pooled<'_, T> {
+        let stack = self.free.borrow_mut().pop().unwrap_or_else(Stack::new);
+        Pooled { pool: self, stack }
+    }
+
+    /// Clears a stack and adds it to the pool, unless the pool already holds its limit of idle
+    /// stacks.
+    /// # Example
+    /// ```
+    /// use hay::{Stack, StackPool};
+    /// let pool = StackPool::new();
+    /// let mut stack = Stack::new();
+    /// stack.extend(0..16);
+    /// pool.put(stack);
+    /// let stack = pool.get();
+    /// assert!(stack.is_empty());
+    /// assert!(stack.as_vec().capacity() >= 16);
+    /// ```
+    pub fn put(&self, mut stack: Stack<T>) {
+        // Clearing first, so dropping the elements can't observe the pool borrowed.
+        stack.clear();
+        let mut free = self.free.borrow_mut();
+        if free.len() < self.limit {
+            free.push(stack);
+        }
+    }
+
+    /// Returns the number of idle stacks in the pool.
+    /// # Example
+    /// ```
+    /// use hay::StackPool;
+    /// let pool = StackPool::new();
+    /// pool.get().push(1);
+    /// assert_eq!(pool.idle(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn idle(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    /// Drops every idle stack, releasing their allocations.
+    /// # Example
+    /// ```
+    /// use hay::StackPool;
+    /// let pool = StackPool::new();
+    /// pool.get().push(1);
+    /// pool.clear();
+    /// assert_eq!(pool.idle(), 0);
+    /// ```
+    #[inline(always)]
+    pub fn clear(&self) {
+        // Taking the stacks out first, so dropping them can't observe the pool borrowed.
+        drop(mem::take(&mut *self.free.borrow_mut()));
+    }
+}
+impl<T> Default for StackPool<T> {
+    /// Constructs a new, empty `StackPool<T>` which keeps every stack returned to it.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A stack taken from a [StackPool], which goes back into the pool when dropped.
+#[derive(Debug)]
+pub struct Pooled<'a, T> {
+    pool: &'a StackPool<T>,
+    stack: Stack<T>,
+}
+impl<T> Pooled<'_, T> {
+    /// Detaches the stack from its pool, so it isn't returned when dropped.
+    /// # Example
+    /// ```
+    /// use hay::{Pooled, StackPool};
+    /// let pool = StackPool::new();
+    /// let mut stack = pool.get();
+    /// stack.push(1);
+    /// let stack = Pooled::into_inner(stack);
+    /// assert_eq!(pool.idle(), 0);
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn into_inner(mut this: Self) -> Stack<T> {
+        mem::replace(&mut this.stack, Stack::new())
+    }
+}
+impl<T> Deref for Pooled<'_, T> {
+    type Target = Stack<T>;
+
+    /// Dereferences to the pooled stack.
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.stack
+    }
+}
+impl<T> DerefMut for Pooled<'_, T> {
+    /// Mutably dereferences to the pooled stack.
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.stack
+    }
+}
+impl<T> Drop for Pooled<'_, T> {
+    /// Returns the stack to its pool, unless it was detached or never allocated.
+    fn drop(&mut self) {
+        let stack = mem::replace(&mut self.stack, Stack::new());
+        if stack.vec.capacity() != 0 {
+            self.pool.put(stack);
+        }
+    }
+}
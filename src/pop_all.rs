@@ -0,0 +1,49 @@
+use crate::Stack;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+/// A consuming iterator over the elements of a [Stack] in the order they would be popped, from
+/// the top down, returned by [Stack::pop_all].
+#[derive(Clone, Debug)]
+pub struct PopAll<T> {
+    vec: Vec<T>,
+}
+impl<T> Iterator for PopAll<T> {
+    /// The type of the elements being popped.
+    type Item = T;
+
+    /// Pops the next element, freeing the buffer once the last one is popped.
+    #[inline(always)]
+    fn next(&mut self) -> Option<T> {
+        let value = self.vec.pop();
+        if self.vec.is_empty() {
+            self.vec = Vec::new();
+        }
+        value
+    }
+
+    /// Returns the exact number of elements left to pop.
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.vec.len(), Some(self.vec.len()))
+    }
+}
+impl<T> ExactSizeIterator for PopAll<T> {}
+impl<T> FusedIterator for PopAll<T> {}
+impl<T> Stack<T> {
+    /// Consumes the stack, returning an iterator which pops every element from the top down and
+    /// frees the buffer once it is done.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.extend([1, 2, 3]);
+    /// let popped = stack.pop_all();
+    /// assert_eq!(popped.len(), 3);
+    /// assert!(popped.eq([3, 2, 1]));
+    /// ```
+    #[inline(always)]
+    pub fn pop_all(self) -> PopAll<T> {
+        PopAll { vec: self.vec }
+    }
+}
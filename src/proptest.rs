@@ -0,0 +1,50 @@
+//! Strategies for generating stacks in `proptest` property tests.
+//!
+//! Generated stacks shrink by dropping elements and shrinking the ones left, always keeping the
+//! remaining elements in the order they were pushed.
+//! # Example
+//! ```
+//! use hay::proptest::stack;
+//! use proptest::prelude::*;
+//! proptest!(|(mut s in stack(any::<u8>(), 1..16))| {
+//!     let top = *s.top().unwrap();
+//!     prop_assert_eq!(s.pop(), Some(top));
+//! });
+//! ```
+use crate::Stack;
+use alloc::vec::Vec;
+use proptest::{
+    arbitrary::Arbitrary,
+    collection::{self, SizeRange, VecStrategy},
+    strategy::{Map, Strategy},
+};
+
+/// The strategy returned by [stack], generating stacks of the values of `S`.
+pub type StackStrategy<S> =
+    Map<VecStrategy<S>, fn(Vec<<S as Strategy>::Value>) -> Stack<<S as Strategy>::Value>>;
+
+/// Returns a strategy generating stacks of values from `element`, with a length in `size`.
+///
+/// Elements are generated from the bottom of the stack to its top.
+/// # Example
+/// ```
+/// use hay::proptest::stack;
+/// use proptest::{prelude::*, strategy::ValueTree, test_runner::TestRunner};
+/// let mut runner = TestRunner::default();
+/// let tree = stack(0..10u8, 3).new_tree(&mut runner).unwrap();
+/// assert_eq!(tree.current().len(), 3);
+/// ```
+#[inline(always)]
+pub fn stack<S: Strategy>(element: S, size: impl Into<SizeRange>) -> StackStrategy<S> {
+    collection::vec(element, size).prop_map(|vec| Stack { vec })
+}
+
+impl<T: Arbitrary> Arbitrary for Stack<T> {
+    type Parameters = (SizeRange, T::Parameters);
+    type Strategy = StackStrategy<T::Strategy>;
+
+    /// Returns a strategy generating arbitrary stacks, with a length in the given range.
+    fn arbitrary_with((size, parameters): Self::Parameters) -> Self::Strategy {
+        stack(T::arbitrary_with(parameters), size)
+    }
+}
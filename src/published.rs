@@ -0,0 +1,80 @@
+//! A slot holding the latest snapshot of a stack, which readers can load without locking.
+use crate::{
+    hazard::Domain,
+    sync::{const_fn, AtomicPtr, Ordering},
+};
+use alloc::{boxed::Box, sync::Arc};
+use core::{fmt, ptr};
+
+/// The latest published snapshot, loaded under a hazard pointer so it stays alive while its
+/// reference count is incremented.
+pub(crate) struct Published<T> {
+    current: AtomicPtr<Arc<[T]>>,
+    hazards: Domain<Arc<[T]>>,
+}
+// SAFETY: Snapshots are only ever stored when `T` is `Send` and `Sync`, so sharing and dropping
+// them from any thread is sound.
+unsafe impl<T: Send> Send for Published<T> {}
+// SAFETY: Snapshots are only ever stored when `T` is `Send` and `Sync`, so sharing and dropping
+// them from any thread is sound.
+unsafe impl<T: Send> Sync for Published<T> {}
+impl<T> Published<T> {
+    const_fn! {
+        /// Constructs a new slot holding no snapshot.
+        #[inline(always)]
+        pub(crate) fn new() -> Self {
+            Self {
+                current: AtomicPtr::new(ptr::null_mut()),
+                hazards: Domain::new(),
+            }
+        }
+    }
+
+    /// Returns the latest snapshot, or [None] if none has been stored.
+    pub(crate) fn load(&self) -> Option<Arc<[T]>> {
+        let guard = self.hazards.acquire();
+        let current = guard.protect(&self.current);
+        // SAFETY: The hazard pointer keeps the snapshot from being freed while it is cloned.
+        let snapshot = unsafe { current.as_ref() }.map(Arc::clone);
+        drop(guard);
+        snapshot
+    }
+
+    /// Replaces the latest snapshot.
+    pub(crate) fn store(&self, snapshot: Arc<[T]>)
+    where
+        T: Send + Sync,
+    {
+        let snapshot = Box::into_raw(Box::new(snapshot));
+        // Sequentially consistent, so the scan when retiring can't miss a reader's hazard.
+        let previous = self.current.swap(snapshot, Ordering::SeqCst);
+        if !previous.is_null() {
+            // SAFETY: The previous snapshot was boxed by this method, and is no longer reachable
+            // now it has been swapped out. Its reference is meant to be dropped along with it.
+            unsafe { self.hazards.acquire().retire(previous) };
+        }
+    }
+}
+impl<T> Default for Published<T> {
+    /// Constructs a new slot holding no snapshot.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> fmt::Debug for Published<T> {
+    /// Formats the slot, without its snapshot.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Published").finish_non_exhaustive()
+    }
+}
+impl<T> Drop for Published<T> {
+    /// Drops the latest snapshot.
+    fn drop(&mut self) {
+        let current = self.current.load(Ordering::Relaxed);
+        if !current.is_null() {
+            // SAFETY: The slot is exclusively borrowed, so no reader is protecting the snapshot.
+            drop(unsafe { Box::from_raw(current) });
+        }
+    }
+}
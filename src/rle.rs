@@ -0,0 +1,268 @@
+use crate::{invariant::check_invariants, Stack};
+#[cfg(feature = "serde")]
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+/// A run-length encoded stack.
+///
+/// Runs of equal consecutive values are stored once, alongside the length of the run, while the
+/// stack still behaves as if every value had been pushed individually.
+/// # Example
+/// ```
+/// use hay::RleStack;
+/// let mut stack = RleStack::new();
+/// stack.push('a');
+/// stack.push('a');
+/// stack.push('b');
+/// assert_eq!(stack.len(), 3);
+/// assert_eq!(stack.run_count(), 2);
+/// assert_eq!(stack.pop(), Some('b'));
+/// assert_eq!(stack.pop(), Some('a'));
+/// assert_eq!(stack.pop(), Some('a'));
+/// assert_eq!(stack.pop(), None);
+/// ```
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct RleStack<T> {
+    runs: Stack<(T, usize)>,
+    len: usize,
+}
+impl<T> RleStack<T> {
+    /// Constructs a new, empty `RleStack<T>`.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::RleStack;
+    /// let mut stack: RleStack<i32> = RleStack::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            runs: Stack::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns a reference to the top element in the stack.
+    /// # Example
+    /// ```
+    /// use hay::RleStack;
+    /// let mut stack = RleStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn top(&self) -> Option<&T> {
+        self.runs.top().map(|(value, _)| value)
+    }
+
+    /// Returns the number of elements on the stack, counting every element of every run.
+    /// # Example
+    /// ```
+    /// use hay::RleStack;
+    /// let mut stack = RleStack::new();
+    /// stack.push_n(1, 3);
+    /// assert_eq!(stack.len(), 3);
+    /// ```
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    /// # Example
+    /// ```
+    /// use hay::RleStack;
+    /// let mut stack = RleStack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of runs stored in the stack.
+    /// # Example
+    /// ```
+    /// use hay::RleStack;
+    /// let mut stack = RleStack::new();
+    /// stack.push_n(1, 100);
+    /// stack.push(2);
+    /// assert_eq!(stack.run_count(), 2);
+    /// ```
+    #[inline(always)]
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Returns the runs of the stack as `(value, count)` pairs, from the bottom of the stack up.
+    /// # Example
+    /// ```
+    /// use hay::RleStack;
+    /// let mut stack = RleStack::new();
+    /// stack.extend([1, 1, 2]);
+    /// assert_eq!(stack.runs(), &[(1, 2), (2, 1)]);
+    /// ```
+    #[inline(always)]
+    pub fn runs(&self) -> &[(T, usize)] {
+        &self.runs
+    }
+
+    /// Clears the stack, popping all values.
+    /// # Example
+    /// ```
+    /// use hay::RleStack;
+    /// let mut stack = RleStack::new();
+    /// stack.push(1);
+    /// stack.clear();
+    /// assert!(stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.runs.clear();
+        self.len = 0;
+        check_invariants!(self);
+    }
+
+    /// Panics if the runs don't add up to the length, or if any of them is empty.
+    #[cfg(feature = "strict-debug")]
+    #[track_caller]
+    fn check_invariants(&self) {
+        let mut len = 0usize;
+        for (run, (_, count)) in self.runs.iter().enumerate() {
+            assert_ne!(*count, 0, "`RleStack` run {run} is empty");
+            len += count;
+        }
+        assert_eq!(
+            len, self.len,
+            "`RleStack` runs hold {len} elements, but its length is {}",
+            self.len,
+        );
+    }
+}
+impl<T: Eq> RleStack<T> {
+    /// Appends an element to the top of the stack, extending the top run if it holds an equal
+    /// value.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`, or if the length overflows a `usize`.
+    /// # Example
+    /// ```
+    /// use hay::RleStack;
+    /// let mut stack = RleStack::new();
+    /// stack.push(1);
+    /// stack.push(1);
+    /// assert_eq!(stack.runs(), &[(1, 2)]);
+    /// ```
+    #[inline(always)]
+    pub fn push(&mut self, value: T) {
+        self.push_n(value, 1);
+    }
+
+    /// Appends `count` copies of an element to the top of the stack.
+    ///
+    /// Does nothing if `count` is zero.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`, or if the length overflows a `usize`.
+    /// # Example
+    /// ```
+    /// use hay::RleStack;
+    /// let mut stack = RleStack::new();
+    /// stack.push_n('x', 1_000_000);
+    /// assert_eq!(stack.len(), 1_000_000);
+    /// assert_eq!(stack.run_count(), 1);
+    /// ```
+    pub fn push_n(&mut self, value: T, count: usize) {
+        if count == 0 {
+            return;
+        }
+        self.len = self.len.checked_add(count).expect("length overflow");
+        match self.runs.top_mut() {
+            // Extending the current run.
+            Some((top, run)) if *top == value => *run += count,
+            // Starting a new run.
+            _ => self.runs.push((value, count)),
+        }
+        check_invariants!(self);
+    }
+}
+impl<T: Clone> RleStack<T> {
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    ///
+    /// The value is cloned out of its run unless it is the last element of that run.
+    /// # Example
+    /// ```
+    /// use hay::RleStack;
+    /// let mut stack = RleStack::new();
+    /// stack.push_n(1, 2);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        let (value, run) = self.runs.top_mut()?;
+        self.len -= 1;
+        if *run > 1 {
+            *run -= 1;
+            let value = value.clone();
+            check_invariants!(self);
+            return Some(value);
+        }
+        let (value, _) = self.runs.pop()?;
+        check_invariants!(self);
+        Some(value)
+    }
+}
+impl<T: Eq> Extend<T> for RleStack<T> {
+    /// Pushes a collection of values onto a stack.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+impl<'a, T: 'a + Eq + Copy> Extend<&'a T> for RleStack<T> {
+    /// Pushes a collection of values onto a stack.
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        for &value in iter {
+            self.push(value);
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for RleStack<T> {
+    /// Serializes a stack as a sequence of `(value, count)` runs, from its bottom to its top.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.runs.serialize(serializer)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de> + Eq> Deserialize<'de> for RleStack<T> {
+    /// Deserializes a stack from a sequence of `(value, count)` runs, from its bottom to its top.
+    ///
+    /// Adjacent runs of equal values are merged, and empty runs are skipped.
+    /// # Errors
+    /// Returns an error if the runs are longer than a `usize` can count.
+    /// # Example
+    /// ```
+    /// use hay::RleStack;
+    /// let stack: RleStack<i32> = serde_json::from_str("[[1,2],[1,1],[2,0],[3,1]]").unwrap();
+    /// assert_eq!(stack.len(), 4);
+    /// assert_eq!(stack.run_count(), 2);
+    /// let json = format!("[[1,{}],[2,1]]", usize::MAX);
+    /// assert!(serde_json::from_str::<RleStack<i32>>(&json).is_err());
+    /// ```
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut stack = Self::new();
+        for (value, count) in Vec::<(T, usize)>::deserialize(deserializer)? {
+            if stack.len.checked_add(count).is_none() {
+                return Err(D::Error::custom("length overflow"));
+            }
+            stack.push_n(value, count);
+        }
+        Ok(stack)
+    }
+}
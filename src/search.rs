@@ -0,0 +1,64 @@
+use crate::Stack;
+
+/// The number of bytes compared at once, which is as wide as the widest common vector registers.
+const CHUNK_BYTES: usize = 64;
+
+/// Returns the index of the last element of `values` equal to `value`, comparing `LANES` of them
+/// at once.
+///
+/// Each chunk is compared without branching, so the compiler turns the comparisons into vector
+/// instructions, and only a chunk with a match is searched element by element.
+#[inline(always)]
+fn rposition<T: Copy + Eq, const LANES: usize>(values: &[T], value: T) -> Option<usize> {
+    let chunks = values.rchunks_exact(LANES);
+    let rest = chunks.remainder();
+    for (chunk, index) in chunks.zip((0..values.len() / LANES).rev()) {
+        let found = chunk
+            .iter()
+            .fold(false, |found, &element| found | (element == value));
+        if found {
+            let offset = chunk.iter().rposition(|&element| element == value)?;
+            return Some(rest.len() + index * LANES + offset);
+        }
+    }
+    rest.iter().rposition(|&element| element == value)
+}
+
+macro_rules! impl_search {
+    ($($t:ty),*) => {$(
+        impl Stack<$t> {
+            /// Returns `true` if the stack contains `value`, comparing many elements at once.
+            /// # Example
+            /// ```
+            /// use hay::Stack;
+            #[doc = concat!("let mut stack = Stack::<", stringify!($t), ">::new();")]
+            /// stack.extend(0..100);
+            /// assert!(stack.contains(&42));
+            /// assert!(!stack.contains(&100));
+            /// ```
+            #[inline(always)]
+            pub fn contains(&self, value: &$t) -> bool {
+                self.position_from_top(value).is_some()
+            }
+
+            /// Returns the depth of the topmost element equal to `value`, where the top is at
+            /// depth zero, comparing many elements at once.
+            /// # Example
+            /// ```
+            /// use hay::Stack;
+            #[doc = concat!("let mut stack = Stack::<", stringify!($t), ">::new();")]
+            /// stack.extend([7, 1, 7, 2, 3]);
+            /// assert_eq!(stack.position_from_top(&7), Some(2));
+            /// assert_eq!(stack.position_from_top(&3), Some(0));
+            /// assert_eq!(stack.position_from_top(&9), None);
+            /// ```
+            #[inline(always)]
+            pub fn position_from_top(&self, value: &$t) -> Option<usize> {
+                const LANES: usize = CHUNK_BYTES / core::mem::size_of::<$t>();
+                let index = rposition::<$t, LANES>(&self.vec, *value)?;
+                Some(self.vec.len() - 1 - index)
+            }
+        }
+    )*};
+}
+impl_search!(u8, u16, u32, u64);
@@ -0,0 +1,137 @@
+use crate::ConcurrentStack;
+use alloc::boxed::Box;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Hands out a distinct number to every thread, used to pick its local shard.
+static NEXT_THREAD: AtomicUsize = AtomicUsize::new(0);
+std::thread_local! {
+    /// The number of the current thread.
+    static THREAD: usize = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+}
+
+/// A concurrent stack split into shards to reduce contention.
+///
+/// Each thread pushes onto and pops from its own local shard, only stealing from the other
+/// shards once its own is empty. Order is strictly LIFO within a shard but relaxed across them,
+/// which is the right trade-off for parallel depth-first workloads.
+/// # Example
+/// ```
+/// use hay::ShardedStack;
+/// let stack = ShardedStack::with_shards(4);
+/// std::thread::scope(|scope| {
+///     for i in 0..4 {
+///         let stack = &stack;
+///         scope.spawn(move || stack.push(i));
+///     }
+/// });
+/// let mut popped = Vec::new();
+/// while let Some(value) = stack.pop() {
+///     popped.push(value);
+/// }
+/// popped.sort();
+/// assert_eq!(popped, [0, 1, 2, 3]);
+/// ```
+#[derive(Debug)]
+pub struct ShardedStack<T> {
+    shards: Box<[ConcurrentStack<T>]>,
+}
+impl<T> ShardedStack<T> {
+    /// Constructs a new, empty `ShardedStack<T>` with one shard per available CPU.
+    /// # Example
+    /// ```
+    /// use hay::ShardedStack;
+    /// let stack: ShardedStack<i32> = ShardedStack::new();
+    /// assert!(stack.shard_count() >= 1);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::with_shards(thread::available_parallelism().map_or(1, usize::from))
+    }
+
+    /// Constructs a new, empty `ShardedStack<T>` with `shards` shards.
+    /// # Panics
+    /// Panics if `shards` is zero.
+    /// # Example
+    /// ```
+    /// use hay::ShardedStack;
+    /// let stack: ShardedStack<i32> = ShardedStack::with_shards(8);
+    /// assert_eq!(stack.shard_count(), 8);
+    /// ```
+    #[must_use]
+    pub fn with_shards(shards: usize) -> Self {
+        assert!(shards != 0, "a sharded stack needs at least one shard");
+        Self {
+            shards: (0..shards).map(|_| ConcurrentStack::new()).collect(),
+        }
+    }
+
+    /// Returns the number of shards the stack is split into.
+    /// # Example
+    /// ```
+    /// use hay::ShardedStack;
+    /// let stack: ShardedStack<i32> = ShardedStack::with_shards(2);
+    /// assert_eq!(stack.shard_count(), 2);
+    /// ```
+    #[inline(always)]
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the index of the current thread's local shard.
+    #[inline(always)]
+    fn local_shard(&self) -> usize {
+        THREAD.with(|&thread| thread % self.shards.len())
+    }
+
+    /// Appends an element to the top of the current thread's local shard.
+    /// # Example
+    /// ```
+    /// use hay::ShardedStack;
+    /// let stack = ShardedStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn push(&self, value: T) {
+        self.shards[self.local_shard()].push(value);
+    }
+
+    /// Removes the element at the top of the current thread's local shard and returns it,
+    /// stealing from the other shards if it is empty, or [None] if every shard is empty.
+    /// # Example
+    /// ```
+    /// use hay::ShardedStack;
+    /// let stack = ShardedStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    pub fn pop(&self) -> Option<T> {
+        let local = self.local_shard();
+        let shards = self.shards.len();
+        (0..shards).find_map(|offset| self.shards[(local + offset) % shards].pop())
+    }
+
+    /// Returns `true` if every shard contained no elements at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::ShardedStack;
+    /// let stack = ShardedStack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(ConcurrentStack::is_empty)
+    }
+}
+impl<T> Default for ShardedStack<T> {
+    /// Constructs a new, empty `ShardedStack<T>` with one shard per available CPU.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
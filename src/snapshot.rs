@@ -0,0 +1,127 @@
+use crate::Stack;
+use serde::{Deserialize, Serialize};
+
+impl<T: Serialize> Stack<T> {
+    /// Writes a snapshot of the stack into `buf` in the `postcard` wire format, returning the
+    /// part of `buf` which was written.
+    ///
+    /// Nothing is allocated, so this is suitable for persisting a stack to flash on `no_std`
+    /// targets.
+    /// # Errors
+    /// Returns an error if `buf` is too small to hold the snapshot.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1u8);
+    /// stack.push(2);
+    /// let mut buf = [0; 8];
+    /// assert_eq!(stack.snapshot(&mut buf).unwrap(), [2, 1, 2]);
+    /// assert!(stack.snapshot(&mut [0; 2]).is_err());
+    /// ```
+    #[inline(always)]
+    pub fn snapshot<'a>(&self, buf: &'a mut [u8]) -> Result<&'a mut [u8], postcard::Error> {
+        postcard::to_slice(self, buf)
+    }
+}
+impl<'a, T: Deserialize<'a>> Stack<T> {
+    /// Restores a stack from a snapshot in the `postcard` wire format, as written by
+    /// [Stack::snapshot].
+    /// # Errors
+    /// Returns an error if `buf` doesn't hold a valid snapshot.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack: Stack<u8> = Stack::restore(&[2, 1, 2]).unwrap();
+    /// assert_eq!(stack.pop(), Some(2));
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn restore(buf: &'a [u8]) -> Result<Self, postcard::Error> {
+        postcard::from_bytes(buf)
+    }
+}
+/// The magic bytes opening a saved stack.
+#[cfg(feature = "std")]
+const MAGIC: [u8; 4] = *b"hay\0";
+/// The version of the format stacks are saved in.
+#[cfg(feature = "std")]
+const VERSION: u8 = 1;
+#[cfg(feature = "std")]
+impl<T: Serialize> Stack<T> {
+    /// Saves the stack to `writer` in a small versioned binary format, so it can be resumed with
+    /// [Stack::load_from] after a restart.
+    ///
+    /// The format is the magic bytes `hay\0`, a version byte, the length of the payload as a
+    /// little-endian `u64`, and the payload, which is the stack in the `postcard` wire format.
+    /// # Errors
+    /// Returns an error if an element fails to serialize or `writer` fails.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(1u8);
+    /// let mut file = Vec::new();
+    /// stack.save_to(&mut file).unwrap();
+    /// assert_eq!(file, [b'h', b'a', b'y', 0, 1, 2, 0, 0, 0, 0, 0, 0, 0, 1, 1]);
+    /// ```
+    pub fn save_to(&self, mut writer: impl std::io::Write) -> std::io::Result<()> {
+        let payload = postcard::to_allocvec(self).map_err(std::io::Error::other)?;
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&(payload.len() as u64).to_le_bytes())?;
+        writer.write_all(&payload)?;
+        writer.flush()
+    }
+}
+#[cfg(feature = "std")]
+impl<T: serde::de::DeserializeOwned> Stack<T> {
+    /// Loads a stack from `reader`, as saved by [Stack::save_to].
+    ///
+    /// Only the saved stack is read, so several can be loaded from one reader in turn.
+    /// # Errors
+    /// Returns an error of kind [std::io::ErrorKind::InvalidData] if `reader` doesn't hold a
+    /// stack in a supported version of the format, or any error `reader` fails with.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// let mut stack = Stack::new();
+    /// stack.push(String::from("job"));
+    /// let mut file = Vec::new();
+    /// stack.save_to(&mut file).unwrap();
+    /// let mut stack: Stack<String> = Stack::load_from(&file[..]).unwrap();
+    /// assert_eq!(stack.pop().as_deref(), Some("job"));
+    /// assert!(Stack::<String>::load_from(&b"nope"[..]).is_err());
+    /// ```
+    pub fn load_from(mut reader: impl std::io::Read) -> std::io::Result<Self> {
+        use std::io::{Error, ErrorKind, Read};
+        let mut header = [0; 13];
+        reader
+            .read_exact(&mut header)
+            .map_err(|error| match error.kind() {
+                ErrorKind::UnexpectedEof => {
+                    Error::new(ErrorKind::InvalidData, "truncated stack header")
+                }
+                _ => error,
+            })?;
+        if header[..4] != MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "not a saved stack"));
+        }
+        if header[4] != VERSION {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unsupported stack format version {}", header[4]),
+            ));
+        }
+        let len = u64::from_le_bytes(header[5..].try_into().unwrap());
+        let mut payload = Vec::new();
+        reader.take(len).read_to_end(&mut payload)?;
+        if payload.len() as u64 != len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "truncated stack payload",
+            ));
+        }
+        postcard::from_bytes(&payload).map_err(|error| Error::new(ErrorKind::InvalidData, error))
+    }
+}
@@ -0,0 +1,243 @@
+use crate::Stack;
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Debug},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Error, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+/// A spill file, removed once the stack is done with it.
+struct SpillFile {
+    file: File,
+    path: PathBuf,
+}
+impl Drop for SpillFile {
+    /// Removes the file, ignoring failures since nothing else refers to it.
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// A stack keeping at most a fixed number of its top elements in memory, spilling older ones to
+/// a temporary file and reloading them as the stack is popped down to them.
+///
+/// Elements are spilled in segments of half the budget, encoded in the `postcard` wire format,
+/// so pushes and pops only touch the file once every half a budget's worth of elements.
+/// # Example
+/// ```
+/// use hay::SpillStack;
+/// let mut stack = SpillStack::new(4);
+/// for value in 0..10 {
+///     stack.push(value)?;
+/// }
+/// assert!(stack.in_memory() <= 4);
+/// assert_eq!(stack.len(), 10);
+/// for value in (0..10).rev() {
+///     assert_eq!(stack.pop()?, Some(value));
+/// }
+/// assert_eq!(stack.pop()?, None);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct SpillStack<T> {
+    hot: Stack<T>,
+    budget: usize,
+    dir: PathBuf,
+    file: Option<SpillFile>,
+    segments: Stack<(u64, usize)>,
+    spilled: usize,
+}
+impl<T: Serialize + DeserializeOwned> SpillStack<T> {
+    /// Constructs a new, empty `SpillStack<T>` keeping at most `budget` elements in memory,
+    /// spilling to the system's temporary directory.
+    ///
+    /// Nothing is allocated or created until elements are pushed.
+    /// # Panics
+    /// Panics if `budget` is less than two, since segments hold half of it.
+    #[must_use]
+    #[inline(always)]
+    pub fn new(budget: usize) -> Self {
+        Self::with_dir(budget, std::env::temp_dir())
+    }
+
+    /// Constructs a new, empty `SpillStack<T>` keeping at most `budget` elements in memory,
+    /// spilling to a file in `dir`.
+    /// # Panics
+    /// Panics if `budget` is less than two, since segments hold half of it.
+    /// # Example
+    /// ```
+    /// use hay::SpillStack;
+    /// let stack: SpillStack<u32> = SpillStack::with_dir(1024, std::env::temp_dir());
+    /// ```
+    #[must_use]
+    pub fn with_dir(budget: usize, dir: impl AsRef<Path>) -> Self {
+        assert!(
+            budget >= 2,
+            "the budget of a `SpillStack` must be at least two"
+        );
+        Self {
+            hot: Stack::new(),
+            budget,
+            dir: dir.as_ref().to_path_buf(),
+            file: None,
+            segments: Stack::new(),
+            spilled: 0,
+        }
+    }
+
+    /// Appends an element to the top of the stack, spilling the oldest elements in memory if the
+    /// budget is exceeded.
+    /// # Errors
+    /// Returns an error if the spill file can't be created or written, in which case the
+    /// element is still pushed and stays in memory.
+    pub fn push(&mut self, value: T) -> io::Result<()> {
+        self.hot.push(value);
+        if self.hot.len() > self.budget {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty,
+    /// reloading the most recently spilled segment if none are left in memory.
+    /// # Errors
+    /// Returns an error if the spill file can't be read, in which case the stack is left
+    /// unchanged.
+    pub fn pop(&mut self) -> io::Result<Option<T>> {
+        if self.hot.is_empty() {
+            self.reload()?;
+        }
+        Ok(self.hot.pop())
+    }
+
+    /// Returns a reference to the top element in the stack, reloading the most recently spilled
+    /// segment if none are left in memory.
+    /// # Errors
+    /// Returns an error if the spill file can't be read.
+    pub fn top(&mut self) -> io::Result<Option<&T>> {
+        if self.hot.is_empty() {
+            self.reload()?;
+        }
+        Ok(self.hot.top())
+    }
+
+    /// Clears the stack, popping all values and discarding the spill file.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.hot.clear();
+        self.segments.clear();
+        self.spilled = 0;
+        self.file = None;
+    }
+
+    /// Returns the number of elements in the stack, including spilled ones.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.hot.len() + self.spilled
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements held in memory.
+    #[inline(always)]
+    pub fn in_memory(&self) -> usize {
+        self.hot.len()
+    }
+
+    /// Returns the number of elements spilled to the file.
+    #[inline(always)]
+    pub fn spilled(&self) -> usize {
+        self.spilled
+    }
+
+    /// Returns the number of bytes the stack has allocated on the heap, counting the elements
+    /// held in memory and the index of the segments spilled to the file, including their spare
+    /// capacity.
+    ///
+    /// Memory the elements own themselves, like the buffers of strings, isn't counted, and
+    /// neither is the spill file.
+    #[inline(always)]
+    pub fn allocated_bytes(&self) -> usize {
+        self.hot.allocated_bytes() + self.segments.allocated_bytes()
+    }
+
+    /// Writes the oldest half of the budget of elements in memory to the end of the spill file.
+    fn spill(&mut self) -> io::Result<()> {
+        let count = self.budget / 2;
+        let payload = postcard::to_allocvec(&self.hot[..count]).map_err(Error::other)?;
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => self.file.insert(create_spill_file(&self.dir)?),
+        };
+        let offset = file.file.seek(SeekFrom::End(0))?;
+        if let Err(error) = file.file.write_all(&payload) {
+            // Dropping the partial segment, so the file only holds whole ones.
+            let _ = file.file.set_len(offset);
+            return Err(error);
+        }
+        self.hot.vec.drain(..count);
+        self.segments.push((offset, payload.len()));
+        self.spilled += count;
+        Ok(())
+    }
+
+    /// Reads the most recently spilled segment back into memory, and truncates it from the file.
+    fn reload(&mut self) -> io::Result<()> {
+        let (Some(&(offset, len)), Some(file)) = (self.segments.top(), &mut self.file) else {
+            return Ok(());
+        };
+        let mut payload = alloc::vec![0; len];
+        file.file.seek(SeekFrom::Start(offset))?;
+        file.file.read_exact(&mut payload)?;
+        let segment: Vec<T> = postcard::from_bytes(&payload)
+            .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+        file.file.set_len(offset)?;
+        self.segments.pop();
+        self.spilled -= segment.len();
+        self.hot.extend(segment);
+        Ok(())
+    }
+}
+impl<T: Debug> Debug for SpillStack<T> {
+    /// Formats the elements in memory, along with the number of spilled ones.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpillStack")
+            .field("in_memory", &self.hot)
+            .field("spilled", &self.spilled)
+            .field("budget", &self.budget)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Creates a new, uniquely named spill file in `dir`.
+fn create_spill_file(dir: &Path) -> io::Result<SpillFile> {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    loop {
+        let name = std::format!(
+            "hay-spill-{}-{}",
+            process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = dir.join(name);
+        match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(file) => return Ok(SpillFile { file, path }),
+            // Trying the next name, since a stale file from an old process has this one.
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => {}
+            Err(error) => return Err(error),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! A minimal spin lock, for sharing state between threads without `std`.
+use core::{
+    cell::UnsafeCell,
+    hint,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// A mutex which spins until it can take the lock.
+///
+/// Only suitable for critical sections which are short and never block.
+#[derive(Debug, Default)]
+pub(crate) struct SpinMutex<T> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+// SAFETY: The lock only lets one thread at a time touch the value.
+unsafe impl<T: Send> Send for SpinMutex<T> {}
+// SAFETY: The lock only lets one thread at a time touch the value.
+unsafe impl<T: Send> Sync for SpinMutex<T> {}
+impl<T> SpinMutex<T> {
+    /// Constructs a new, unlocked `SpinMutex<T>`.
+    #[inline(always)]
+    pub(crate) const fn new(value: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Takes the lock, spinning until it is free.
+    pub(crate) fn lock(&self) -> SpinGuard<'_, T> {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            while self.locked.load(Ordering::Relaxed) {
+                hint::spin_loop();
+            }
+        }
+        SpinGuard { mutex: self }
+    }
+
+    /// Returns a mutable reference to the value, which needs no locking.
+    #[inline(always)]
+    pub(crate) fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Consumes the mutex, returning the value.
+    #[inline(always)]
+    pub(crate) fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+/// Ownership of the lock of a [SpinMutex], released when dropped.
+pub(crate) struct SpinGuard<'a, T> {
+    mutex: &'a SpinMutex<T>,
+}
+impl<T> Deref for SpinGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: Holding the lock gives exclusive access to the value.
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+impl<T> DerefMut for SpinGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: Holding the lock gives exclusive access to the value.
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+impl<T> Drop for SpinGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.locked.store(false, Ordering::Release);
+    }
+}
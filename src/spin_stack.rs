@@ -0,0 +1,191 @@
+use crate::{spin::SpinMutex, trace, ArrayStack, Full};
+use core::fmt::{self, Debug};
+
+/// A fixed-capacity stack which can be declared as a `static` and shared between threads,
+/// guarded by a spin lock.
+///
+/// It needs neither `std` nor a critical section implementation, and is all zeroes when empty
+/// so it is placed in `.bss`. Since waiting threads spin, it is unsuited to sharing with
+/// interrupt handlers, which should use `StaticStack` instead.
+/// # Example
+/// ```
+/// use hay::SpinStack;
+/// static EVENTS: SpinStack<u8, 4> = SpinStack::new();
+/// EVENTS.push(1);
+/// EVENTS.push(2);
+/// assert_eq!(EVENTS.pop(), Some(2));
+/// assert_eq!(EVENTS.pop(), Some(1));
+/// assert_eq!(EVENTS.pop(), None);
+/// ```
+pub struct SpinStack<T, const N: usize> {
+    stack: SpinMutex<ArrayStack<T, N>>,
+}
+impl<T, const N: usize> SpinStack<T, N> {
+    /// Constructs a new, empty `SpinStack<T, N>`.
+    /// # Example
+    /// ```
+    /// use hay::SpinStack;
+    /// static STACK: SpinStack<u32, 16> = SpinStack::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            stack: SpinMutex::new(ArrayStack::new()),
+        }
+    }
+
+    /// Runs `f` with exclusive access to the underlying stack, holding the lock until it returns.
+    ///
+    /// Keep `f` short, since other threads spin until it returns. Calling back into the stack
+    /// from within `f` deadlocks.
+    /// # Example
+    /// ```
+    /// use hay::SpinStack;
+    /// static STACK: SpinStack<u8, 4> = SpinStack::new();
+    /// STACK.with(|stack| stack.extend([1, 2, 3]));
+    /// assert_eq!(STACK.with(|stack| stack.iter().sum::<u8>()), 6);
+    /// ```
+    #[inline(always)]
+    pub fn with<R>(&self, f: impl FnOnce(&mut ArrayStack<T, N>) -> R) -> R {
+        f(&mut self.stack.lock())
+    }
+
+    /// Returns a mutable reference to the underlying stack, without locking since the borrow is
+    /// already exclusive.
+    /// # Example
+    /// ```
+    /// use hay::SpinStack;
+    /// let mut stack: SpinStack<u8, 4> = SpinStack::new();
+    /// stack.get_mut().push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn get_mut(&mut self) -> &mut ArrayStack<T, N> {
+        self.stack.get_mut()
+    }
+
+    /// Consumes the wrapper, returning the underlying stack.
+    /// # Example
+    /// ```
+    /// use hay::SpinStack;
+    /// let stack: SpinStack<u8, 4> = SpinStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.into_inner().pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn into_inner(self) -> ArrayStack<T, N> {
+        self.stack.into_inner()
+    }
+
+    /// Appends an element to the top of the stack.
+    /// # Panics
+    /// Panics if the stack is full.
+    /// # Example
+    /// ```
+    /// use hay::SpinStack;
+    /// static STACK: SpinStack<u8, 4> = SpinStack::new();
+    /// STACK.push(1);
+    /// assert_eq!(STACK.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn push(&self, value: T) {
+        self.with(|stack| stack.push(value));
+    }
+
+    /// Appends an element to the top of the stack, or hands it back if the stack is full.
+    /// # Errors
+    /// Returns [Full] with the value if the stack is full.
+    /// # Example
+    /// ```
+    /// use hay::{Full, SpinStack};
+    /// static STACK: SpinStack<u8, 1> = SpinStack::new();
+    /// assert_eq!(STACK.try_push(1), Ok(()));
+    /// assert_eq!(STACK.try_push(2), Err(Full(2)));
+    /// ```
+    #[inline(always)]
+    pub fn try_push(&self, value: T) -> Result<(), Full<T>> {
+        self.with(|stack| stack.try_push(value))
+            .inspect_err(|_| trace::overflowed("SpinStack", N))
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::SpinStack;
+    /// static STACK: SpinStack<u8, 4> = SpinStack::new();
+    /// STACK.push(1);
+    /// assert_eq!(STACK.pop(), Some(1));
+    /// assert_eq!(STACK.pop(), None);
+    /// ```
+    #[inline(always)]
+    pub fn pop(&self) -> Option<T> {
+        self.with(ArrayStack::pop)
+    }
+
+    /// Clears the stack, popping all values.
+    ///
+    /// The values are dropped after the lock is released.
+    /// # Example
+    /// ```
+    /// use hay::SpinStack;
+    /// static STACK: SpinStack<u8, 4> = SpinStack::new();
+    /// STACK.push(1);
+    /// STACK.clear();
+    /// assert!(STACK.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn clear(&self) {
+        drop(self.with(core::mem::take));
+    }
+
+    /// Returns the number of elements in the stack at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::SpinStack;
+    /// static STACK: SpinStack<u8, 4> = SpinStack::new();
+    /// STACK.push(1);
+    /// assert_eq!(STACK.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.with(|stack| stack.len())
+    }
+
+    /// Returns `true` if the stack contained no elements at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::SpinStack;
+    /// static STACK: SpinStack<u8, 4> = SpinStack::new();
+    /// assert!(STACK.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.with(|stack| stack.is_empty())
+    }
+}
+impl<T, const N: usize> Default for SpinStack<T, N> {
+    /// Constructs a new, empty `SpinStack<T, N>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, const N: usize> Debug for SpinStack<T, N> {
+    /// Formats a `SpinStack<T, N>` without its elements, which may change at any moment.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpinStack")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish_non_exhaustive()
+    }
+}
+impl<T, const N: usize> From<ArrayStack<T, N>> for SpinStack<T, N> {
+    /// Wraps a fixed-capacity stack so it can be shared.
+    #[inline(always)]
+    fn from(stack: ArrayStack<T, N>) -> Self {
+        Self {
+            stack: SpinMutex::new(stack),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{Stack, StackError};
+
+/// The stack manipulation words of Forth, which fail with [StackError::Underflow] and leave the
+/// stack unchanged if it holds too few elements.
+///
+/// Each word is documented with its stack effect, listing the elements it takes and leaves from
+/// the bottom up, so `( a b -- b a )` swaps the top element `b` with the one below it.
+/// # Example
+/// ```
+/// use hay::{Stack, StackError, StackOps};
+/// let mut stack = Stack::new();
+/// stack.extend([1, 2, 3]);
+/// stack.rot()?;
+/// stack.over()?;
+/// assert_eq!(*stack, [2, 3, 1, 3]);
+/// stack.drop()?;
+/// stack.tuck()?;
+/// assert_eq!(*stack, [2, 1, 3, 1]);
+/// stack.nip()?;
+/// assert_eq!(*stack, [2, 1, 1]);
+/// stack.clear();
+/// assert_eq!(stack.dup(), Err(StackError::Underflow { needed: 1, len: 0 }));
+/// # Ok::<(), StackError>(())
+/// ```
+pub trait StackOps<T> {
+    /// Copies the top element: `( a -- a a )`.
+    /// # Errors
+    /// Returns [StackError::Underflow] if the stack is empty.
+    fn dup(&mut self) -> Result<(), StackError>
+    where
+        T: Clone;
+
+    /// Discards the top element: `( a -- )`.
+    /// # Errors
+    /// Returns [StackError::Underflow] if the stack is empty.
+    fn drop(&mut self) -> Result<(), StackError>;
+
+    /// Swaps the top two elements: `( a b -- b a )`.
+    /// # Errors
+    /// Returns [StackError::Underflow] if the stack holds fewer than two elements.
+    fn swap(&mut self) -> Result<(), StackError>;
+
+    /// Copies the element below the top one: `( a b -- a b a )`.
+    /// # Errors
+    /// Returns [StackError::Underflow] if the stack holds fewer than two elements.
+    fn over(&mut self) -> Result<(), StackError>
+    where
+        T: Clone;
+
+    /// Rotates the third element up to the top: `( a b c -- b c a )`.
+    /// # Errors
+    /// Returns [StackError::Underflow] if the stack holds fewer than three elements.
+    fn rot(&mut self) -> Result<(), StackError>;
+
+    /// Discards the element below the top one: `( a b -- b )`.
+    /// # Errors
+    /// Returns [StackError::Underflow] if the stack holds fewer than two elements.
+    fn nip(&mut self) -> Result<(), StackError>;
+
+    /// Copies the top element below the one under it: `( a b -- b a b )`.
+    /// # Errors
+    /// Returns [StackError::Underflow] if the stack holds fewer than two elements.
+    fn tuck(&mut self) -> Result<(), StackError>
+    where
+        T: Clone;
+}
+impl<T> StackOps<T> for Stack<T> {
+    /// Copies the top element: `( a -- a a )`.
+    #[inline(always)]
+    fn dup(&mut self) -> Result<(), StackError>
+    where
+        T: Clone,
+    {
+        let start = self.needing(1)?;
+        self.vec.push(self.vec[start].clone());
+        Ok(())
+    }
+
+    /// Discards the top element: `( a -- )`.
+    #[inline(always)]
+    fn drop(&mut self) -> Result<(), StackError> {
+        self.needing(1)?;
+        self.vec.pop();
+        Ok(())
+    }
+
+    /// Swaps the top two elements: `( a b -- b a )`.
+    #[inline(always)]
+    fn swap(&mut self) -> Result<(), StackError> {
+        let start = self.needing(2)?;
+        self.vec.swap(start, start + 1);
+        Ok(())
+    }
+
+    /// Copies the element below the top one: `( a b -- a b a )`.
+    #[inline(always)]
+    fn over(&mut self) -> Result<(), StackError>
+    where
+        T: Clone,
+    {
+        let start = self.needing(2)?;
+        self.vec.push(self.vec[start].clone());
+        Ok(())
+    }
+
+    /// Rotates the third element up to the top: `( a b c -- b c a )`.
+    #[inline(always)]
+    fn rot(&mut self) -> Result<(), StackError> {
+        let start = self.needing(3)?;
+        self.vec[start..].rotate_left(1);
+        Ok(())
+    }
+
+    /// Discards the element below the top one: `( a b -- b )`.
+    #[inline(always)]
+    fn nip(&mut self) -> Result<(), StackError> {
+        let start = self.needing(2)?;
+        self.vec.swap_remove(start);
+        Ok(())
+    }
+
+    /// Copies the top element below the one under it: `( a b -- b a b )`.
+    #[inline(always)]
+    fn tuck(&mut self) -> Result<(), StackError>
+    where
+        T: Clone,
+    {
+        let start = self.needing(2)?;
+        self.vec.insert(start, self.vec[start + 1].clone());
+        Ok(())
+    }
+}
+impl<T> Stack<T> {
+    /// Returns the index of the lowest of the top `needed` elements, or an underflow error if
+    /// the stack holds fewer.
+    #[inline(always)]
+    fn needing(&self, needed: usize) -> Result<usize, StackError> {
+        self.vec
+            .len()
+            .checked_sub(needed)
+            .ok_or(StackError::Underflow {
+                needed,
+                len: self.vec.len(),
+            })
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{trace, ArrayStack, Full};
+use core::{
+    cell::RefCell,
+    fmt::{self, Debug},
+};
+use critical_section::Mutex;
+
+/// A fixed-capacity stack which can be declared as a `static` and shared between interrupt
+/// handlers and the main loop.
+///
+/// Every operation runs inside a short critical section, so it is safe to call from anywhere,
+/// including single-core targets without atomics.
+/// # Example
+/// ```
+/// use hay::StaticStack;
+/// static EVENTS: StaticStack<u8, 4> = StaticStack::new();
+/// EVENTS.push(1);
+/// EVENTS.push(2);
+/// assert_eq!(EVENTS.pop(), Some(2));
+/// assert_eq!(EVENTS.pop(), Some(1));
+/// assert_eq!(EVENTS.pop(), None);
+/// ```
+pub struct StaticStack<T, const N: usize> {
+    stack: Mutex<RefCell<ArrayStack<T, N>>>,
+}
+impl<T, const N: usize> StaticStack<T, N> {
+    /// Constructs a new, empty `StaticStack<T, N>`.
+    /// # Example
+    /// ```
+    /// use hay::StaticStack;
+    /// static STACK: StaticStack<u32, 16> = StaticStack::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            stack: Mutex::new(RefCell::new(ArrayStack::new())),
+        }
+    }
+
+    /// Runs `f` with exclusive access to the underlying stack, inside a single critical section.
+    ///
+    /// Keep `f` short, since interrupts are held off until it returns.
+    /// # Panics
+    /// Panics if called again from within `f`.
+    /// # Example
+    /// ```
+    /// use hay::StaticStack;
+    /// static STACK: StaticStack<u8, 4> = StaticStack::new();
+    /// STACK.with(|stack| stack.extend([1, 2, 3]));
+    /// assert_eq!(STACK.with(|stack| stack.iter().sum::<u8>()), 6);
+    /// ```
+    #[inline(always)]
+    pub fn with<R>(&self, f: impl FnOnce(&mut ArrayStack<T, N>) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.stack.borrow_ref_mut(cs)))
+    }
+
+    /// Returns a mutable reference to the underlying stack, without a critical section since the
+    /// borrow is already exclusive.
+    /// # Example
+    /// ```
+    /// use hay::StaticStack;
+    /// let mut stack: StaticStack<u8, 4> = StaticStack::new();
+    /// stack.get_mut().push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn get_mut(&mut self) -> &mut ArrayStack<T, N> {
+        self.stack.get_mut().get_mut()
+    }
+
+    /// Consumes the wrapper, returning the underlying stack.
+    /// # Example
+    /// ```
+    /// use hay::StaticStack;
+    /// let stack: StaticStack<u8, 4> = StaticStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.into_inner().pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn into_inner(self) -> ArrayStack<T, N> {
+        self.stack.into_inner().into_inner()
+    }
+
+    /// Appends an element to the top of the stack.
+    /// # Panics
+    /// Panics if the stack is full.
+    /// # Example
+    /// ```
+    /// use hay::StaticStack;
+    /// static STACK: StaticStack<u8, 4> = StaticStack::new();
+    /// STACK.push(1);
+    /// assert_eq!(STACK.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn push(&self, value: T) {
+        self.with(|stack| stack.push(value));
+    }
+
+    /// Appends an element to the top of the stack, or hands it back if the stack is full.
+    /// # Errors
+    /// Returns [Full] with the value if the stack is full.
+    /// # Example
+    /// ```
+    /// use hay::{Full, StaticStack};
+    /// static STACK: StaticStack<u8, 1> = StaticStack::new();
+    /// assert_eq!(STACK.try_push(1), Ok(()));
+    /// assert_eq!(STACK.try_push(2), Err(Full(2)));
+    /// ```
+    #[inline(always)]
+    pub fn try_push(&self, value: T) -> Result<(), Full<T>> {
+        self.with(|stack| stack.try_push(value))
+            .inspect_err(|_| trace::overflowed("StaticStack", N))
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::StaticStack;
+    /// static STACK: StaticStack<u8, 4> = StaticStack::new();
+    /// STACK.push(1);
+    /// assert_eq!(STACK.pop(), Some(1));
+    /// assert_eq!(STACK.pop(), None);
+    /// ```
+    #[inline(always)]
+    pub fn pop(&self) -> Option<T> {
+        self.with(ArrayStack::pop)
+    }
+
+    /// Clears the stack, popping all values.
+    ///
+    /// The values are dropped outside of the critical section.
+    /// # Example
+    /// ```
+    /// use hay::StaticStack;
+    /// static STACK: StaticStack<u8, 4> = StaticStack::new();
+    /// STACK.push(1);
+    /// STACK.clear();
+    /// assert!(STACK.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn clear(&self) {
+        drop(self.with(core::mem::take));
+    }
+
+    /// Returns the number of elements in the stack at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::StaticStack;
+    /// static STACK: StaticStack<u8, 4> = StaticStack::new();
+    /// STACK.push(1);
+    /// assert_eq!(STACK.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.with(|stack| stack.len())
+    }
+
+    /// Returns `true` if the stack contained no elements at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::StaticStack;
+    /// static STACK: StaticStack<u8, 4> = StaticStack::new();
+    /// assert!(STACK.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.with(|stack| stack.is_empty())
+    }
+}
+impl<T, const N: usize> Default for StaticStack<T, N> {
+    /// Constructs a new, empty `StaticStack<T, N>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, const N: usize> Debug for StaticStack<T, N> {
+    /// Formats a `StaticStack<T, N>` without its elements, which may change at any moment.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticStack")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish_non_exhaustive()
+    }
+}
+impl<T, const N: usize> From<ArrayStack<T, N>> for StaticStack<T, N> {
+    /// Wraps a fixed-capacity stack so it can be shared.
+    #[inline(always)]
+    fn from(stack: ArrayStack<T, N>) -> Self {
+        Self {
+            stack: Mutex::new(RefCell::new(stack)),
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use crate::{invariant::check_invariants, Stack};
+use alloc::string::String;
+#[cfg(feature = "serde")]
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Display, Write},
+    ops::Deref,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A stack of string segments stored in one contiguous string.
+///
+/// Each push adds a new segment, which is removed again as a whole when popped. Writing to the
+/// stack through [fmt::Write] appends to the top segment, so formatted output can be built up
+/// and then unwound scope by scope.
+/// # Example
+/// ```
+/// use hay::StrStack;
+/// let mut path = StrStack::new();
+/// path.push_str("/usr");
+/// path.push_str("/local");
+/// path.push_fmt(format_args!("/{}", "bin"));
+/// assert_eq!(path.as_str(), "/usr/local/bin");
+/// assert_eq!(path.pop().as_deref(), Some("/bin"));
+/// assert_eq!(path.as_str(), "/usr/local");
+/// ```
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StrStack {
+    buf: String,
+    ends: Stack<usize>,
+}
+impl StrStack {
+    /// Constructs a new, empty `StrStack`.
+    ///
+    /// The stack will not allocate until segments are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::StrStack;
+    /// let mut stack = StrStack::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            buf: String::new(),
+            ends: Stack::new(),
+        }
+    }
+
+    /// Returns the start of the top segment.
+    #[inline(always)]
+    fn top_start(&self) -> usize {
+        let segments = self.ends.len();
+        if segments < 2 {
+            0
+        } else {
+            self.ends[segments - 2]
+        }
+    }
+
+    /// Panics if the segment ends don't chain through the buffer in order, each on a character
+    /// boundary and the last at its end.
+    #[cfg(feature = "strict-debug")]
+    #[track_caller]
+    fn check_invariants(&self) {
+        let mut start = 0;
+        for (segment, &end) in self.ends.iter().enumerate() {
+            assert!(
+                start <= end && self.buf.is_char_boundary(end),
+                "`StrStack` segment {segment} ends at {end}, which is before its start at {start} \
+                 or not on a character boundary of a {}-byte buffer",
+                self.buf.len(),
+            );
+            start = end;
+        }
+        assert_eq!(
+            start,
+            self.buf.len(),
+            "`StrStack` segments end at {start}, but its buffer holds {} bytes",
+            self.buf.len(),
+        );
+    }
+
+    /// Returns the top segment in the stack.
+    /// # Example
+    /// ```
+    /// use hay::StrStack;
+    /// let mut stack = StrStack::new();
+    /// stack.push_str("a");
+    /// stack.push_str("b");
+    /// assert_eq!(stack.top(), Some("b"));
+    /// ```
+    #[inline(always)]
+    pub fn top(&self) -> Option<&str> {
+        let end = *self.ends.top()?;
+        Some(&self.buf[self.top_start()..end])
+    }
+
+    /// Pushes a segment onto the top of the stack.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// use hay::StrStack;
+    /// let mut stack = StrStack::new();
+    /// stack.push_str("hay");
+    /// assert_eq!(stack.top(), Some("hay"));
+    /// ```
+    #[inline(always)]
+    pub fn push_str(&mut self, segment: &str) {
+        self.buf.push_str(segment);
+        self.ends.push(self.buf.len());
+        check_invariants!(self);
+    }
+
+    /// Pushes a segment containing formatted output onto the top of the stack.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes, or if a formatting trait
+    /// implementation returns an error.
+    /// # Example
+    /// ```
+    /// use hay::StrStack;
+    /// let mut stack = StrStack::new();
+    /// stack.push_fmt(format_args!("{}-{}", 1, 2));
+    /// assert_eq!(stack.top(), Some("1-2"));
+    /// ```
+    pub fn push_fmt(&mut self, args: fmt::Arguments<'_>) {
+        self.ends.push(self.buf.len());
+        self.write_fmt(args)
+            .expect("a formatting trait implementation returned an error");
+        check_invariants!(self);
+    }
+
+    /// Removes the segment at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::StrStack;
+    /// let mut stack = StrStack::new();
+    /// stack.push_str("a");
+    /// assert_eq!(stack.pop().as_deref(), Some("a"));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<String> {
+        let start = self.top_start();
+        self.ends.pop()?;
+        let segment = self.buf.split_off(start);
+        check_invariants!(self);
+        Some(segment)
+    }
+
+    /// Pops segments until at most `len` remain.
+    ///
+    /// Does nothing if the stack already holds `len` or fewer segments.
+    /// # Example
+    /// ```
+    /// use hay::StrStack;
+    /// let mut stack = StrStack::new();
+    /// stack.push_str("a");
+    /// stack.push_str("b");
+    /// stack.push_str("c");
+    /// stack.truncate(1);
+    /// assert_eq!(stack.as_str(), "a");
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.ends.len() {
+            return;
+        }
+        self.ends.as_mut_vec().truncate(len);
+        self.buf.truncate(self.ends.top().copied().unwrap_or(0));
+        check_invariants!(self);
+    }
+
+    /// Clears the stack, popping all segments.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the stack.
+    /// # Example
+    /// ```
+    /// use hay::StrStack;
+    /// let mut stack = StrStack::new();
+    /// stack.push_str("a");
+    /// stack.clear();
+    /// assert_eq!(stack.as_str(), "");
+    /// ```
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.buf.clear();
+        self.ends.clear();
+        check_invariants!(self);
+    }
+
+    /// Returns the number of segments on the stack.
+    /// # Example
+    /// ```
+    /// use hay::StrStack;
+    /// let mut stack = StrStack::new();
+    /// stack.push_str("ab");
+    /// stack.push_str("c");
+    /// assert_eq!(stack.len(), 2);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns `true` if the stack contains no segments.
+    /// # Example
+    /// ```
+    /// use hay::StrStack;
+    /// let mut stack = StrStack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push_str("");
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Extracts a string slice containing every segment, from the bottom of the stack up.
+    /// # Example
+    /// ```
+    /// use hay::StrStack;
+    /// let mut stack = StrStack::new();
+    /// stack.push_str("a");
+    /// stack.push_str("b");
+    /// assert_eq!(stack.as_str(), "ab");
+    /// ```
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        &self.buf
+    }
+
+    /// Returns the number of bytes the stack has allocated on the heap, counting the buffer
+    /// holding the segments and the stack of where each ends, including their spare capacity.
+    /// # Example
+    /// ```
+    /// use hay::StrStack;
+    /// let mut stack = StrStack::new();
+    /// assert_eq!(stack.allocated_bytes(), 0);
+    /// stack.push_str("hello");
+    /// assert!(stack.allocated_bytes() >= 5 + std::mem::size_of::<usize>());
+    /// ```
+    #[inline(always)]
+    pub fn allocated_bytes(&self) -> usize {
+        self.buf.capacity() + self.ends.allocated_bytes()
+    }
+}
+impl Deref for StrStack {
+    /// The resulting type when dereferencing `StrStack`.
+    type Target = str;
+
+    /// Dereferences a `StrStack`.
+    fn deref(&self) -> &Self::Target {
+        &self.buf
+    }
+}
+impl Display for StrStack {
+    /// Formats every segment, from the bottom of the stack up.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.buf)
+    }
+}
+impl Write for StrStack {
+    /// Appends `s` to the top segment, pushing a new segment if the stack is empty.
+    /// # Example
+    /// ```
+    /// use hay::StrStack;
+    /// use core::fmt::Write;
+    /// let mut stack = StrStack::new();
+    /// stack.push_str("a");
+    /// write!(stack, "{}", 1).unwrap();
+    /// assert_eq!(stack.top(), Some("a1"));
+    /// ```
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.buf.push_str(s);
+        match self.ends.top_mut() {
+            Some(end) => *end = self.buf.len(),
+            None => self.ends.push(self.buf.len()),
+        }
+        check_invariants!(self);
+        Ok(())
+    }
+}
+#[cfg(feature = "serde")]
+impl Serialize for StrStack {
+    /// Serializes a stack as a sequence of its segments, from its bottom to its top.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut start = 0;
+        serializer.collect_seq(self.ends.iter().map(|&end| {
+            let segment = &self.buf[start..end];
+            start = end;
+            segment
+        }))
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for StrStack {
+    /// Deserializes a stack from a sequence of segments, from its bottom to its top.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut stack = Self::new();
+        for segment in Vec::<String>::deserialize(deserializer)? {
+            stack.push_str(&segment);
+        }
+        Ok(stack)
+    }
+}
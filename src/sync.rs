@@ -0,0 +1,26 @@
+//! The atomics used by the lock-free stacks, swapped for `loom`'s under `cfg(loom)` so tests can
+//! explore every interleaving of their operations.
+#[cfg(not(loom))]
+pub(crate) use core::{
+    hint,
+    sync::atomic::{fence, AtomicBool, AtomicIsize, AtomicPtr, AtomicUsize, Ordering},
+};
+#[cfg(loom)]
+pub(crate) use loom::{
+    hint,
+    sync::atomic::{fence, AtomicBool, AtomicIsize, AtomicPtr, AtomicUsize, Ordering},
+};
+
+/// Defines a `const` function, which is only a regular function under `cfg(loom)` since loom's
+/// atomics can't be constructed in a constant context.
+macro_rules! const_fn {
+    ($(#[$attr:meta])* $vis:vis fn $($rest:tt)*) => {
+        $(#[$attr])*
+        #[cfg(not(loom))]
+        $vis const fn $($rest)*
+        $(#[$attr])*
+        #[cfg(loom)]
+        $vis fn $($rest)*
+    };
+}
+pub(crate) use const_fn;
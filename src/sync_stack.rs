@@ -0,0 +1,307 @@
+use crate::{published::Published, sync::const_fn, Stack};
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// A stack guarded by a mutex, where popping threads can block until an element is pushed.
+///
+/// This makes it usable as a LIFO work queue between threads without busy polling. Threads which
+/// only need to look at the stack, like a dashboard, can read the latest published snapshot
+/// without ever taking the lock.
+/// # Example
+/// ```
+/// use hay::SyncStack;
+/// let stack = SyncStack::new();
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| stack.push(1));
+///     assert_eq!(stack.pop_wait(), 1);
+/// });
+/// ```
+#[derive(Debug, Default)]
+pub struct SyncStack<T> {
+    stack: Mutex<Stack<T>>,
+    pushed: Condvar,
+    /// Notified whenever the length of the stack changes, for watching threads.
+    changed: Condvar,
+    snapshot: Published<T>,
+}
+impl<T> SyncStack<T> {
+    const_fn! {
+        /// Constructs a new, empty `SyncStack<T>`.
+        ///
+        /// The stack will not allocate until elements are pushed onto it.
+        /// # Example
+        /// ```
+        /// use hay::SyncStack;
+        /// let stack: SyncStack<i32> = SyncStack::new();
+        /// ```
+        #[must_use]
+        #[inline(always)]
+        pub fn new() -> Self {
+            Self {
+                stack: Mutex::new(Stack::new()),
+                pushed: Condvar::new(),
+                changed: Condvar::new(),
+                snapshot: Published::new(),
+            }
+        }
+    }
+
+    /// Locks the stack, ignoring poisoning since every operation leaves the stack consistent.
+    #[inline(always)]
+    fn lock(&self) -> MutexGuard<'_, Stack<T>> {
+        self.stack.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Appends an element to the top of the stack, waking up a thread waiting to pop.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::SyncStack;
+    /// let stack = SyncStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    pub fn push(&self, value: T) {
+        self.lock().push(value);
+        self.pushed.notify_one();
+        self.changed.notify_all();
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    ///
+    /// This never blocks waiting for an element.
+    /// # Example
+    /// ```
+    /// use hay::SyncStack;
+    /// let stack = SyncStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    pub fn pop(&self) -> Option<T> {
+        let value = self.lock().pop()?;
+        self.changed.notify_all();
+        Some(value)
+    }
+
+    /// Removes the element at the top of the stack and returns it, blocking the current thread
+    /// until an element is pushed if the stack is empty.
+    /// # Example
+    /// ```
+    /// use hay::SyncStack;
+    /// let stack = SyncStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop_wait(), 1);
+    /// ```
+    pub fn pop_wait(&self) -> T {
+        let mut stack = self.lock();
+        loop {
+            if let Some(value) = stack.pop() {
+                drop(stack);
+                self.changed.notify_all();
+                return value;
+            }
+            stack = self
+                .pushed
+                .wait(stack)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Removes the element at the top of the stack and returns it, blocking the current thread
+    /// for up to `timeout` until an element is pushed if the stack is empty.
+    ///
+    /// Returns [None] if no element became available before the timeout elapsed.
+    /// # Example
+    /// ```
+    /// use hay::SyncStack;
+    /// use std::time::Duration;
+    /// let stack = SyncStack::new();
+    /// assert_eq!(stack.pop_timeout(Duration::from_millis(1)), None);
+    /// stack.push(1);
+    /// assert_eq!(stack.pop_timeout(Duration::from_millis(1)), Some(1));
+    /// ```
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<T> {
+        let deadline = Instant::now().checked_add(timeout);
+        let mut stack = self.lock();
+        loop {
+            if let Some(value) = stack.pop() {
+                drop(stack);
+                self.changed.notify_all();
+                return Some(value);
+            }
+            let remaining = match deadline {
+                Some(deadline) => deadline.checked_duration_since(Instant::now())?,
+                // The deadline is too far away to represent, so this may as well wait forever.
+                None => timeout,
+            };
+            stack = self
+                .pushed
+                .wait_timeout(stack, remaining)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    /// Clears the stack, popping all values.
+    /// # Example
+    /// ```
+    /// use hay::SyncStack;
+    /// let stack = SyncStack::new();
+    /// stack.push(1);
+    /// stack.clear();
+    /// assert!(stack.is_empty());
+    /// ```
+    pub fn clear(&self) {
+        self.lock().clear();
+        self.changed.notify_all();
+    }
+
+    /// Blocks the current thread until `predicate` returns `true` for the length of the stack,
+    /// returning that length.
+    ///
+    /// The predicate is checked again every time the length changes, which makes it easy to
+    /// react to the depth crossing a threshold in either direction.
+    /// # Example
+    /// ```
+    /// use hay::SyncStack;
+    /// let stack = SyncStack::new();
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| {
+    ///         for i in 0..4 {
+    ///             stack.push(i);
+    ///         }
+    ///     });
+    ///     assert_eq!(stack.watch(|len| len >= 4), 4);
+    /// });
+    /// ```
+    pub fn watch(&self, mut predicate: impl FnMut(usize) -> bool) -> usize {
+        let mut stack = self.lock();
+        loop {
+            let len = stack.len();
+            if predicate(len) {
+                return len;
+            }
+            stack = self
+                .changed
+                .wait(stack)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Copies the elements on the stack into a new snapshot, from bottom to top, and publishes it
+    /// for [SyncStack::snapshot] to return.
+    ///
+    /// The lock is only held while the elements are cloned, and readers of older snapshots never
+    /// hold it up.
+    /// # Example
+    /// ```
+    /// use hay::SyncStack;
+    /// let stack = SyncStack::new();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// assert_eq!(*stack.publish_snapshot(), [1, 2]);
+    /// ```
+    pub fn publish_snapshot(&self) -> Arc<[T]>
+    where
+        T: Clone + Send + Sync,
+    {
+        let snapshot: Arc<[T]> = Arc::from(&**self.lock());
+        self.snapshot.store(Arc::clone(&snapshot));
+        snapshot
+    }
+
+    /// Returns the latest snapshot published by [SyncStack::publish_snapshot], or [None] if none
+    /// has been published.
+    ///
+    /// This never takes the lock, so reading and iterating the snapshot never blocks the threads
+    /// pushing and popping, however long it takes.
+    /// # Example
+    /// ```
+    /// use hay::SyncStack;
+    /// let stack = SyncStack::new();
+    /// assert!(stack.snapshot().is_none());
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| {
+    ///         for i in 0..100 {
+    ///             stack.push(i);
+    ///             stack.publish_snapshot();
+    ///         }
+    ///     });
+    ///     // Watching the stack grow without slowing its producer down.
+    ///     while stack.snapshot().map_or(0, |snapshot| snapshot.len()) < 100 {}
+    /// });
+    /// assert_eq!(stack.snapshot().unwrap().last(), Some(&99));
+    /// ```
+    #[inline(always)]
+    pub fn snapshot(&self) -> Option<Arc<[T]>> {
+        self.snapshot.load()
+    }
+
+    /// Returns the number of elements on the stack at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::SyncStack;
+    /// let stack = SyncStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if the stack contained no elements at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::SyncStack;
+    /// let stack = SyncStack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Consumes the `SyncStack<T>`, returning the underlying stack.
+    /// # Example
+    /// ```
+    /// use hay::SyncStack;
+    /// let stack = SyncStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.into_inner().pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn into_inner(self) -> Stack<T> {
+        self.stack
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+impl<T> From<Stack<T>> for SyncStack<T> {
+    /// Wraps a stack so it can be shared between threads.
+    #[inline(always)]
+    fn from(stack: Stack<T>) -> Self {
+        Self {
+            stack: Mutex::new(stack),
+            pushed: Condvar::new(),
+            changed: Condvar::new(),
+            snapshot: Published::new(),
+        }
+    }
+}
+impl<T> Extend<T> for SyncStack<T> {
+    /// Pushes a collection of values onto a stack.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        // No thread can be waiting while the stack is exclusively borrowed.
+        self.stack
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend(iter);
+    }
+}
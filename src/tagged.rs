@@ -0,0 +1,244 @@
+use crate::Stack;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A stack where each element carries a metadata value, pushed and popped together with it.
+///
+/// # Example
+/// ```
+/// use hay::TaggedStack;
+/// let mut stack = TaggedStack::new();
+/// stack.push('(', 0..1);
+/// stack.push('[', 4..5);
+/// assert_eq!(stack.top_meta(), Some(&(4..5)));
+/// assert_eq!(stack.pop(), Some(('[', 4..5)));
+/// assert_eq!(stack.pop(), Some(('(', 0..1)));
+/// assert_eq!(stack.pop(), None);
+/// ```
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TaggedStack<T, M> {
+    stack: Stack<(T, M)>,
+}
+impl<T, M> TaggedStack<T, M> {
+    /// Constructs a new, empty `TaggedStack<T, M>`.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack: TaggedStack<i32, &str> = TaggedStack::new();
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            stack: Stack::new(),
+        }
+    }
+
+    /// Returns references to the top element in the stack and its metadata.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack = TaggedStack::new();
+    /// stack.push(1, "one");
+    /// assert_eq!(stack.top(), Some((&1, &"one")));
+    /// ```
+    #[inline(always)]
+    pub fn top(&self) -> Option<(&T, &M)> {
+        self.stack.top().map(|(value, meta)| (value, meta))
+    }
+
+    /// Returns mutable references to the top element in the stack and its metadata.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack = TaggedStack::new();
+    /// stack.push(1, 10);
+    /// if let Some((value, cost)) = stack.top_mut() {
+    ///     *value += 1;
+    ///     *cost += 1;
+    /// }
+    /// assert_eq!(stack.pop(), Some((2, 11)));
+    /// ```
+    #[inline(always)]
+    pub fn top_mut(&mut self) -> Option<(&mut T, &mut M)> {
+        self.stack.top_mut().map(|(value, meta)| (value, meta))
+    }
+
+    /// Returns a reference to the top element in the stack.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack = TaggedStack::new();
+    /// stack.push(1, "one");
+    /// assert_eq!(stack.top_value(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn top_value(&self) -> Option<&T> {
+        self.stack.top().map(|(value, _)| value)
+    }
+
+    /// Returns a reference to the metadata of the top element in the stack.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack = TaggedStack::new();
+    /// stack.push(1, "one");
+    /// assert_eq!(stack.top_meta(), Some(&"one"));
+    /// ```
+    #[inline(always)]
+    pub fn top_meta(&self) -> Option<&M> {
+        self.stack.top().map(|(_, meta)| meta)
+    }
+
+    /// Appends an element and its metadata to the top of the stack.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack = TaggedStack::new();
+    /// stack.push(1, "one");
+    /// assert_eq!(stack.pop(), Some((1, "one")));
+    /// ```
+    #[inline(always)]
+    pub fn push(&mut self, value: T, meta: M) {
+        self.stack.push((value, meta));
+    }
+
+    /// Removes the element at the top of the stack and returns it along with its metadata, or
+    /// [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack = TaggedStack::new();
+    /// stack.push(1, "one");
+    /// assert_eq!(stack.pop(), Some((1, "one")));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<(T, M)> {
+        self.stack.pop()
+    }
+
+    /// Removes the element at the top of the stack and returns it, discarding its metadata, or
+    /// [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack = TaggedStack::new();
+    /// stack.push(1, "one");
+    /// assert_eq!(stack.pop_value(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn pop_value(&mut self) -> Option<T> {
+        self.stack.pop().map(|(value, _)| value)
+    }
+
+    /// Clears the stack, popping all values and their metadata.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the stack.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack = TaggedStack::new();
+    /// stack.push(1, "one");
+    /// stack.clear();
+    /// assert!(stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+
+    /// Returns the number of elements on the stack.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack = TaggedStack::new();
+    /// stack.push(1, "one");
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack = TaggedStack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push(1, "one");
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Returns an iterator over the elements and their metadata, from the bottom of the stack up.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack = TaggedStack::new();
+    /// stack.push(1, 'a');
+    /// stack.push(2, 'b');
+    /// let pairs: Vec<_> = stack.iter().collect();
+    /// assert_eq!(pairs, [(&1, &'a'), (&2, &'b')]);
+    /// ```
+    #[inline(always)]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&T, &M)> + ExactSizeIterator {
+        self.stack.iter().map(|(value, meta)| (value, meta))
+    }
+
+    /// Returns an iterator over the elements, from the bottom of the stack up.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack = TaggedStack::new();
+    /// stack.push(1, 'a');
+    /// stack.push(2, 'b');
+    /// assert!(stack.values().eq(&[1, 2]));
+    /// ```
+    #[inline(always)]
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.stack.iter().map(|(value, _)| value)
+    }
+
+    /// Returns an iterator over the metadata, from the bottom of the stack up.
+    /// # Example
+    /// ```
+    /// use hay::TaggedStack;
+    /// let mut stack = TaggedStack::new();
+    /// stack.push(1, 'a');
+    /// stack.push(2, 'b');
+    /// assert!(stack.metas().eq(&['a', 'b']));
+    /// ```
+    #[inline(always)]
+    pub fn metas(&self) -> impl DoubleEndedIterator<Item = &M> + ExactSizeIterator {
+        self.stack.iter().map(|(_, meta)| meta)
+    }
+}
+impl<T, M> Extend<(T, M)> for TaggedStack<T, M> {
+    /// Pushes a collection of values and their metadata onto a stack.
+    fn extend<I: IntoIterator<Item = (T, M)>>(&mut self, iter: I) {
+        self.stack.extend(iter);
+    }
+}
+#[cfg(feature = "serde")]
+impl<T: Serialize, M: Serialize> Serialize for TaggedStack<T, M> {
+    /// Serializes a stack as a sequence of `(value, meta)` pairs, from its bottom to its top.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.stack.serialize(serializer)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>, M: Deserialize<'de>> Deserialize<'de> for TaggedStack<T, M> {
+    /// Deserializes a stack from a sequence of `(value, meta)` pairs, from its bottom to its top.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Stack::deserialize(deserializer).map(|stack| Self { stack })
+    }
+}
@@ -0,0 +1,334 @@
+use crate::Stack;
+use core::ops::Sub;
+
+/// A source of timestamps for a [TimestampedStack].
+///
+/// Any `Fn() -> I` closure is a clock, so `no_std` targets can plug in a tick counter or
+/// hardware timer directly.
+/// # Example
+/// ```
+/// use hay::Clock;
+/// use core::cell::Cell;
+/// let ticks = Cell::new(0u64);
+/// let clock = || {
+///     ticks.set(ticks.get() + 1);
+///     ticks.get()
+/// };
+/// assert_eq!(clock.now(), 1);
+/// assert_eq!(clock.now(), 2);
+/// ```
+pub trait Clock {
+    /// A point in time.
+    type Instant: Copy + Ord + Sub<Output = Self::Duration>;
+    /// The span of time between two instants.
+    type Duration: Ord;
+
+    /// Returns the current instant.
+    fn now(&self) -> Self::Instant;
+}
+impl<F: Fn() -> I, I: Copy + Ord + Sub> Clock for F
+where
+    I::Output: Ord,
+{
+    type Instant = I;
+    type Duration = I::Output;
+
+    /// Calls the closure.
+    #[inline(always)]
+    fn now(&self) -> Self::Instant {
+        self()
+    }
+}
+
+/// A [Clock] backed by [std::time::Instant].
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StdClock;
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    type Instant = std::time::Instant;
+    type Duration = std::time::Duration;
+
+    /// Returns [std::time::Instant::now].
+    #[inline(always)]
+    fn now(&self) -> Self::Instant {
+        std::time::Instant::now()
+    }
+}
+
+/// A stack that records when each of its elements was pushed.
+///
+/// Because timestamps are taken as elements are pushed, they never decrease from the bottom of
+/// the stack to the top (as long as the clock doesn't run backwards).
+/// # Example
+/// ```
+/// use hay::TimestampedStack;
+/// use core::cell::Cell;
+/// let time = Cell::new(0u32);
+/// let mut stack = TimestampedStack::with_clock(|| time.get());
+/// stack.push("old");
+/// time.set(10);
+/// stack.push("new");
+/// time.set(15);
+/// assert_eq!(stack.age_of_top(), Some(5));
+/// assert!(stack.pop_older_than(10).eq(["old"]));
+/// assert_eq!(stack.len(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TimestampedStack<T, C: Clock> {
+    stack: Stack<(T, C::Instant)>,
+    clock: C,
+}
+#[cfg(feature = "std")]
+impl<T> TimestampedStack<T, StdClock> {
+    /// Constructs a new, empty `TimestampedStack<T>` timed by [StdClock].
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// let mut stack = TimestampedStack::new();
+    /// stack.push(1);
+    /// assert!(stack.age_of_top().is_some());
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self::with_clock(StdClock)
+    }
+}
+#[cfg(feature = "std")]
+impl<T> Default for TimestampedStack<T, StdClock> {
+    /// Constructs a new, empty `TimestampedStack<T>` timed by [StdClock].
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, C: Clock> TimestampedStack<T, C> {
+    /// Constructs a new, empty `TimestampedStack<T, C>` timed by `clock`.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// let mut stack: TimestampedStack<i32, _> = TimestampedStack::with_clock(|| 0u64);
+    /// ```
+    #[must_use]
+    #[inline(always)]
+    pub const fn with_clock(clock: C) -> Self {
+        Self {
+            stack: Stack::new(),
+            clock,
+        }
+    }
+
+    /// Returns a reference to the clock timing the stack.
+    #[inline(always)]
+    pub const fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Returns a reference to the top element in the stack.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// let mut stack = TimestampedStack::with_clock(|| 0u64);
+    /// stack.push(1);
+    /// assert_eq!(stack.top(), Some(&1));
+    /// ```
+    #[inline(always)]
+    pub fn top(&self) -> Option<&T> {
+        self.stack.top().map(|(value, _)| value)
+    }
+
+    /// Returns a mutable reference to the top element in the stack.
+    ///
+    /// The timestamp of the element is left untouched.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// let mut stack = TimestampedStack::with_clock(|| 0u64);
+    /// stack.push(1);
+    /// *stack.top_mut().unwrap() = 2;
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    #[inline(always)]
+    pub fn top_mut(&mut self) -> Option<&mut T> {
+        self.stack.top_mut().map(|(value, _)| value)
+    }
+
+    /// Returns the instant the top element in the stack was pushed.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// let mut stack = TimestampedStack::with_clock(|| 7u64);
+    /// stack.push(1);
+    /// assert_eq!(stack.timestamp_of_top(), Some(7));
+    /// ```
+    #[inline(always)]
+    pub fn timestamp_of_top(&self) -> Option<C::Instant> {
+        self.stack.top().map(|&(_, timestamp)| timestamp)
+    }
+
+    /// Returns how long ago the top element in the stack was pushed.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// use core::cell::Cell;
+    /// let time = Cell::new(0u64);
+    /// let mut stack = TimestampedStack::with_clock(|| time.get());
+    /// stack.push(1);
+    /// time.set(3);
+    /// assert_eq!(stack.age_of_top(), Some(3));
+    /// ```
+    #[inline(always)]
+    pub fn age_of_top(&self) -> Option<C::Duration> {
+        let timestamp = self.timestamp_of_top()?;
+        Some(self.clock.now() - timestamp)
+    }
+
+    /// Appends an element to the top of the stack, stamped with the current instant.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX`.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// let mut stack = TimestampedStack::with_clock(|| 0u64);
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn push(&mut self, value: T) {
+        let timestamp = self.clock.now();
+        self.stack.push((value, timestamp));
+    }
+
+    /// Removes the element at the top of the stack and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// let mut stack = TimestampedStack::with_clock(|| 0u64);
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        self.stack.pop().map(|(value, _)| value)
+    }
+
+    /// Removes the element at the top of the stack and returns it along with the instant it was
+    /// pushed, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// let mut stack = TimestampedStack::with_clock(|| 4u64);
+    /// stack.push(1);
+    /// assert_eq!(stack.pop_with_timestamp(), Some((1, 4)));
+    /// ```
+    #[inline(always)]
+    pub fn pop_with_timestamp(&mut self) -> Option<(T, C::Instant)> {
+        self.stack.pop()
+    }
+
+    /// Removes every element older than `age`, returning them in an iterator from the bottom of
+    /// the stack up.
+    ///
+    /// Since timestamps never decrease towards the top, the stale elements are the ones at the
+    /// bottom of the stack. The elements are removed even if the iterator is dropped before it
+    /// is fully consumed.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// use core::cell::Cell;
+    /// let time = Cell::new(0u64);
+    /// let mut stack = TimestampedStack::with_clock(|| time.get());
+    /// stack.push('a');
+    /// time.set(5);
+    /// stack.push('b');
+    /// time.set(6);
+    /// stack.push('c');
+    /// time.set(10);
+    /// assert!(stack.pop_older_than(4).eq(['a', 'b']));
+    /// assert_eq!(stack.top(), Some(&'c'));
+    /// ```
+    pub fn pop_older_than(&mut self, age: C::Duration) -> impl Iterator<Item = T> + '_ {
+        let now = self.clock.now();
+        let stale = self
+            .stack
+            .partition_point(|&(_, timestamp)| now - timestamp > age);
+        self.stack
+            .as_mut_vec()
+            .drain(..stale)
+            .map(|(value, _)| value)
+    }
+
+    /// Clears the stack, popping all values.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the stack.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// let mut stack = TimestampedStack::with_clock(|| 0u64);
+    /// stack.push(1);
+    /// stack.clear();
+    /// assert!(stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+
+    /// Returns the number of elements on the stack.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// let mut stack = TimestampedStack::with_clock(|| 0u64);
+    /// stack.push(1);
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// let mut stack = TimestampedStack::with_clock(|| 0u64);
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Returns an iterator over the elements and the instants they were pushed, from the bottom
+    /// of the stack up.
+    /// # Example
+    /// ```
+    /// use hay::TimestampedStack;
+    /// let mut stack = TimestampedStack::with_clock(|| 2u64);
+    /// stack.push('a');
+    /// stack.push('b');
+    /// assert!(stack.iter().eq([(&'a', 2), (&'b', 2)]));
+    /// ```
+    #[inline(always)]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&T, C::Instant)> + ExactSizeIterator {
+        self.stack
+            .iter()
+            .map(|(value, timestamp)| (value, *timestamp))
+    }
+}
+impl<T, C: Clock> Extend<T> for TimestampedStack<T, C> {
+    /// Pushes a collection of values onto a stack, stamping each as it is pushed.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Serializes a [Stack] from its top to its bottom, for use with `#[serde(with = "...")]`.
+//!
+//! By default stacks are serialized from the bottom up, the same as a [Vec]. This reverses the
+//! order, so readers expecting the most recent element first can consume the data directly, and
+//! reverses it back when deserializing.
+//! # Example
+//! ```
+//! use hay::Stack;
+//! use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct History {
+//!     #[serde(with = "hay::top_first")]
+//!     events: Stack<u32>,
+//! }
+//! let mut events = Stack::new();
+//! events.push(1);
+//! events.push(2);
+//! let json = serde_json::to_string(&History { events }).unwrap();
+//! assert_eq!(json, r#"{"events":[2,1]}"#);
+//! let mut history: History = serde_json::from_str(&json).unwrap();
+//! assert_eq!(history.events.pop(), Some(2));
+//! ```
+use crate::Stack;
+use alloc::vec::Vec;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serializes a stack as a sequence, from its top to its bottom.
+pub fn serialize<T: Serialize, S: Serializer>(
+    stack: &Stack<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(stack.iter().rev())
+}
+
+/// Deserializes a stack from a sequence, from its top to its bottom.
+pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Stack<T>, D::Error> {
+    let mut vec = Vec::deserialize(deserializer)?;
+    vec.reverse();
+    Ok(Stack { vec })
+}
@@ -0,0 +1,36 @@
+//! The `tracing` events emitted by the instrumented stacks, which compile to nothing without the
+//! `tracing` feature.
+#![cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+
+/// Records a push onto the stack named `kind`, and the reallocation it caused, if any.
+#[inline(always)]
+pub(crate) fn pushed(kind: &'static str, len: usize, old_capacity: usize, capacity: usize) {
+    #[cfg(feature = "tracing")]
+    {
+        tracing::trace!(target: "hay", kind, len, "push");
+        if capacity != old_capacity {
+            reallocated(kind, old_capacity, capacity);
+        }
+    }
+}
+
+/// Records a pop from the stack named `kind`.
+#[inline(always)]
+pub(crate) fn popped(kind: &'static str, len: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(target: "hay", kind, len, "pop");
+}
+
+/// Records a reallocation of the stack named `kind`.
+#[cold]
+pub(crate) fn reallocated(kind: &'static str, old_capacity: usize, capacity: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(target: "hay", kind, old_capacity, capacity, "reallocate");
+}
+
+/// Records a push rejected by the full stack named `kind`.
+#[cold]
+pub(crate) fn overflowed(kind: &'static str, capacity: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(target: "hay", kind, capacity, "overflow");
+}
@@ -0,0 +1,82 @@
+//! Runs recursive computations iteratively, keeping their frames on a [Stack] instead of the
+//! native stack.
+//!
+//! A recursive function is written as a frame type, holding its arguments and local variables,
+//! and a step function which advances a frame until it calls itself or returns, as a [Step].
+//! [run] drives the frames to completion, resuming each caller with its callee's result.
+//! # Example
+//! ```
+//! use hay::trampoline::{self, Step};
+//! // The naive recursive Fibonacci, remembering the first result while computing the second.
+//! struct Fib {
+//!     n: u64,
+//!     first: Option<u64>,
+//! }
+//! let fib = |n| Fib { n, first: None };
+//! let result = trampoline::run(fib(20), |frame, result| match (result, frame.first) {
+//!     (None, _) if frame.n < 2 => Step::Return(frame.n),
+//!     (None, _) => Step::Call(fib(frame.n - 1)),
+//!     (Some(first), None) => {
+//!         frame.first = Some(first);
+//!         Step::Call(fib(frame.n - 2))
+//!     }
+//!     (Some(second), Some(first)) => Step::Return(first + second),
+//! });
+//! assert_eq!(result, 6765);
+//! ```
+use crate::Stack;
+
+/// What a frame does next, returned by the step function given to [run].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Step<F, R> {
+    /// Calls a new frame, resuming the current one with its result.
+    Call(F),
+    /// Replaces the current frame with a new one, whose result is the current frame's result,
+    /// like a tail call.
+    Become(F),
+    /// Finishes the current frame with a result.
+    Return(R),
+}
+
+/// Runs a recursive computation from its outermost frame, returning its result.
+///
+/// `step` is called with a frame and [None] when the frame starts, and with the result of its
+/// latest callee each time the callee returns. Only the frames waiting on a callee are kept, so
+/// the depth of recursion is limited by memory rather than the native stack.
+/// # Panics
+/// Panics if the new capacity of the frame stack exceeds `isize::MAX`.
+/// # Example
+/// ```
+/// use hay::trampoline::{self, Step};
+/// // Counting down recursively, far deeper than the native stack allows.
+/// let depth = trampoline::run(1_000_000u32, |&mut n, result| match result {
+///     None if n == 0 => Step::Return(0u32),
+///     None => Step::Call(n - 1),
+///     Some(depth) => Step::Return(depth + 1),
+/// });
+/// assert_eq!(depth, 1_000_000);
+/// // Tail calls don't keep their frames at all.
+/// let gcd = trampoline::run((1071u32, 462u32), |&mut (a, b), _| match b {
+///     0 => Step::Return(a),
+///     _ => Step::Become((b, a % b)),
+/// });
+/// assert_eq!(gcd, 21);
+/// ```
+pub fn run<F, R>(frame: F, mut step: impl FnMut(&mut F, Option<R>) -> Step<F, R>) -> R {
+    let mut frames = Stack::new();
+    let mut current = frame;
+    let mut result = None;
+    loop {
+        match step(&mut current, result.take()) {
+            Step::Call(callee) => frames.push(core::mem::replace(&mut current, callee)),
+            Step::Become(callee) => current = callee,
+            Step::Return(value) => match frames.pop() {
+                Some(caller) => {
+                    current = caller;
+                    result = Some(value);
+                }
+                None => return value,
+            },
+        }
+    }
+}
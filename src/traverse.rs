@@ -0,0 +1,336 @@
+//! Traversals of trees and graphs which keep their frontier on a [Stack] instead of recursing,
+//! so arbitrarily deep structures can't overflow the native stack.
+//!
+//! Nodes can be any type, with their children or neighbors given by a closure, and a depth-first
+//! search of a graph remembers the nodes it discovered in any [VisitedSet].
+//! # Example
+//! ```
+//! use hay::traverse;
+//! // A chain far deeper than recursion could handle.
+//! let depth = traverse::preorder(0u32, |&node| (node < 1_000_000).then_some(node + 1)).count();
+//! assert_eq!(depth, 1_000_001);
+//! ```
+use crate::Stack;
+use alloc::{collections::BTreeSet, vec::Vec};
+#[cfg(feature = "std")]
+use core::hash::{BuildHasher, Hash};
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+/// Returns an iterator over the nodes of a tree in preorder, each node before its children.
+///
+/// `children` is called once per node, when the node is reached, and the children of each node
+/// are visited in the order it returns them.
+/// # Example
+/// ```
+/// use hay::traverse;
+/// // 1 has children 2 and 3, and 2 has child 4.
+/// let children = |&node: &u8| match node {
+///     1 => vec![2, 3],
+///     2 => vec![4],
+///     _ => vec![],
+/// };
+/// assert_eq!(traverse::preorder(1, children).collect::<Vec<_>>(), [1, 2, 4, 3]);
+/// ```
+#[inline(always)]
+pub fn preorder<N, I, F>(root: N, children: F) -> Preorder<N, I, F>
+where
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    Preorder {
+        root: Some(root),
+        stack: Stack::new(),
+        children,
+    }
+}
+
+/// Returns an iterator over the nodes of a tree in postorder, each node after its children.
+///
+/// `children` is called once per node, when the node is reached, and the children of each node
+/// are visited in the order it returns them.
+/// # Example
+/// ```
+/// use hay::traverse;
+/// let children = |&node: &u8| match node {
+///     1 => vec![2, 3],
+///     2 => vec![4],
+///     _ => vec![],
+/// };
+/// assert_eq!(traverse::postorder(1, children).collect::<Vec<_>>(), [4, 2, 3, 1]);
+/// ```
+#[inline(always)]
+pub fn postorder<N, I, F>(root: N, children: F) -> Postorder<N, I, F>
+where
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    Postorder {
+        root: Some(root),
+        stack: Stack::new(),
+        children,
+    }
+}
+
+/// An iterator over the nodes of a tree in preorder, returned by [preorder].
+pub struct Preorder<N, I: IntoIterator<Item = N>, F> {
+    root: Option<N>,
+    stack: Stack<I::IntoIter>,
+    children: F,
+}
+impl<N, I: IntoIterator<Item = N>, F> Preorder<N, I, F> {
+    /// Returns the depth of the node returned last, where the root is at depth zero.
+    #[inline(always)]
+    pub fn depth(&self) -> usize {
+        self.stack.len().saturating_sub(1)
+    }
+}
+impl<N, I, F> Iterator for Preorder<N, I, F>
+where
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    /// The type of the nodes.
+    type Item = N;
+
+    /// Returns the next node, reaching its children.
+    fn next(&mut self) -> Option<N> {
+        let node = match self.root.take() {
+            Some(root) => root,
+            None => loop {
+                match self.stack.top_mut()?.next() {
+                    Some(child) => break child,
+                    None => drop(self.stack.pop()),
+                }
+            },
+        };
+        self.stack.push((self.children)(&node).into_iter());
+        Some(node)
+    }
+}
+
+/// An iterator over the nodes of a tree in postorder, returned by [postorder].
+pub struct Postorder<N, I: IntoIterator<Item = N>, F> {
+    root: Option<N>,
+    stack: Stack<(N, I::IntoIter)>,
+    children: F,
+}
+impl<N, I, F> Iterator for Postorder<N, I, F>
+where
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    /// The type of the nodes.
+    type Item = N;
+
+    /// Returns the next node whose children have all been returned.
+    fn next(&mut self) -> Option<N> {
+        if let Some(root) = self.root.take() {
+            let children = (self.children)(&root).into_iter();
+            self.stack.push((root, children));
+        }
+        loop {
+            match self.stack.top_mut()?.1.next() {
+                Some(child) => {
+                    let children = (self.children)(&child).into_iter();
+                    self.stack.push((child, children));
+                }
+                None => return self.stack.pop().map(|(node, _)| node),
+            }
+        }
+    }
+}
+
+/// A set of the nodes a [Dfs] has already discovered.
+/// # Example
+/// ```
+/// use hay::traverse::{self, Event, VisitedSet};
+/// // Tracking small integer nodes in a bitmap instead of a tree.
+/// struct Bits(u64);
+/// impl VisitedSet<u8> for Bits {
+///     fn insert(&mut self, node: &u8) -> bool {
+///         let new = self.0 & 1 << node == 0;
+///         self.0 |= 1 << node;
+///         new
+///     }
+/// }
+/// let neighbors = |&node: &u8| [(node + 1) % 4, (node + 2) % 4];
+/// let discovered = traverse::dfs_with([0], neighbors, Bits(0))
+///     .filter_map(|event| match event {
+///         Event::Discover(node) => Some(node),
+///         Event::Finish(_) => None,
+///     });
+/// assert_eq!(discovered.collect::<Vec<_>>(), [0, 1, 2, 3]);
+/// ```
+pub trait VisitedSet<N> {
+    /// Marks `node` as visited, returning `true` if it wasn't already.
+    fn insert(&mut self, node: &N) -> bool;
+}
+impl<N: Ord + Clone> VisitedSet<N> for BTreeSet<N> {
+    /// Inserts a clone of `node` into the set.
+    #[inline(always)]
+    fn insert(&mut self, node: &N) -> bool {
+        !self.contains(node) && BTreeSet::insert(self, node.clone())
+    }
+}
+#[cfg(feature = "std")]
+impl<N: Hash + Eq + Clone, S: BuildHasher> VisitedSet<N> for HashSet<N, S> {
+    /// Inserts a clone of `node` into the set.
+    #[inline(always)]
+    fn insert(&mut self, node: &N) -> bool {
+        !self.contains(node) && HashSet::insert(self, node.clone())
+    }
+}
+impl<V: VisitedSet<N> + ?Sized, N> VisitedSet<N> for &mut V {
+    /// Forwards to the referenced set.
+    #[inline(always)]
+    fn insert(&mut self, node: &N) -> bool {
+        (**self).insert(node)
+    }
+}
+
+/// An event of a depth-first search.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Event<N> {
+    /// A node was reached for the first time, and its neighbors are about to be searched.
+    Discover(N),
+    /// Every neighbor of a node has been searched.
+    Finish(N),
+}
+
+/// Returns a depth-first search of a graph from `start`, remembering the nodes it discovered in
+/// a [BTreeSet].
+///
+/// `neighbors` is called once per node, when the node is discovered, and the neighbors of each
+/// node are searched in the order it returns them.
+/// # Example
+/// ```
+/// use hay::traverse::{self, Event::*};
+/// // A diamond, 0 -> 1 -> 3 and 0 -> 2 -> 3.
+/// let neighbors = |&node: &u8| match node {
+///     0 => vec![1, 2],
+///     1 | 2 => vec![3],
+///     _ => vec![],
+/// };
+/// assert_eq!(
+///     traverse::dfs(0, neighbors).collect::<Vec<_>>(),
+///     [
+///         Discover(0),
+///         Discover(1),
+///         Discover(3),
+///         Finish(3),
+///         Finish(1),
+///         Discover(2),
+///         Finish(2),
+///         Finish(0),
+///     ],
+/// );
+/// ```
+#[inline(always)]
+pub fn dfs<N, I, F>(start: N, neighbors: F) -> Dfs<N, I, F, BTreeSet<N>>
+where
+    N: Ord + Clone,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    dfs_with([start], neighbors, BTreeSet::new())
+}
+
+/// Returns a depth-first search of a graph from each of `roots` in turn, skipping the nodes
+/// already discovered, and remembering them in `visited`.
+///
+/// See [dfs].
+pub fn dfs_with<N, I, F, V>(
+    roots: impl IntoIterator<Item = N>,
+    neighbors: F,
+    visited: V,
+) -> Dfs<N, I, F, V>
+where
+    N: Clone,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+    V: VisitedSet<N>,
+{
+    let mut roots: Vec<N> = roots.into_iter().collect();
+    // Reversing the roots, so the first is popped first.
+    roots.reverse();
+    Dfs {
+        roots: Stack { vec: roots },
+        stack: Stack::new(),
+        neighbors,
+        visited,
+    }
+}
+
+/// A depth-first search of a graph, yielding an [Event] as each node is discovered and finished,
+/// returned by [dfs] and [dfs_with].
+pub struct Dfs<N, I: IntoIterator<Item = N>, F, V> {
+    roots: Stack<N>,
+    stack: Stack<(N, I::IntoIter)>,
+    neighbors: F,
+    visited: V,
+}
+impl<N, I: IntoIterator<Item = N>, F, V> Dfs<N, I, F, V> {
+    /// Returns an iterator over the path from the current root to the node discovered or
+    /// finished last, which is every node discovered but not yet finished.
+    /// # Example
+    /// ```
+    /// use hay::traverse::{self, Event};
+    /// let mut search = traverse::dfs(0u8, |&node| (node < 3).then_some(node + 1));
+    /// while let Some(Event::Discover(_)) = search.next() {}
+    /// // Node 3 was finished, so the path leads to its parent.
+    /// assert!(search.path().eq(&[0, 1, 2]));
+    /// ```
+    #[inline(always)]
+    pub fn path(&self) -> impl DoubleEndedIterator<Item = &N> + ExactSizeIterator {
+        self.stack.iter().map(|(node, _)| node)
+    }
+
+    /// Returns a reference to the set of nodes discovered so far.
+    #[inline(always)]
+    pub fn visited(&self) -> &V {
+        &self.visited
+    }
+
+    /// Returns a mutable reference to the set of nodes discovered so far, for marking nodes
+    /// which shouldn't be searched.
+    #[inline(always)]
+    pub fn visited_mut(&mut self) -> &mut V {
+        &mut self.visited
+    }
+
+    /// Consumes the search, returning the set of nodes discovered.
+    #[inline(always)]
+    pub fn into_visited(self) -> V {
+        self.visited
+    }
+}
+impl<N, I, F, V> Iterator for Dfs<N, I, F, V>
+where
+    N: Clone,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+    V: VisitedSet<N>,
+{
+    /// The events of the search.
+    type Item = Event<N>;
+
+    /// Returns the next event, discovering the next neighbor of the node on top of the stack or
+    /// finishing it.
+    fn next(&mut self) -> Option<Event<N>> {
+        loop {
+            let node = match self.stack.top_mut() {
+                Some((_, neighbors)) => match neighbors.next() {
+                    Some(neighbor) => neighbor,
+                    None => return self.stack.pop().map(|(node, _)| Event::Finish(node)),
+                },
+                None => self.roots.pop()?,
+            };
+            if self.visited.insert(&node) {
+                let neighbors = (self.neighbors)(&node).into_iter();
+                self.stack.push((node.clone(), neighbors));
+                return Some(Event::Discover(node));
+            }
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! A small stack machine, running bytecode over a [Stack] of operands and a [Stack] of return
+//! addresses.
+//!
+//! Programs are slices of [Instruction]s. Operators are anything implementing
+//! [Operator](crate::eval::Operator), like [Arithmetic], and the host can expose its own
+//! functions to programs with [Vm::register].
+//! # Example
+//! ```
+//! use hay::{
+//!     eval::Arithmetic,
+//!     vm::{Instruction::*, Vm},
+//! };
+//! // Sums the numbers from 10 down to 1, keeping the counter above the sum.
+//! let program = [
+//!     Push(0),
+//!     Push(10),
+//!     // Stopping once the counter is zero.
+//!     Dup,
+//!     JumpIfZero(8),
+//!     Call(10),
+//!     Push(1),
+//!     Op(Arithmetic::Sub),
+//!     Jump(2),
+//!     Drop,
+//!     Halt,
+//!     // Adds the counter to the sum, keeping the counter on top.
+//!     Swap,
+//!     Over,
+//!     Op(Arithmetic::Add),
+//!     Swap,
+//!     Ret,
+//! ];
+//! let mut vm = Vm::new();
+//! vm.run(&program).unwrap();
+//! assert_eq!(**vm.operands(), [55]);
+//! ```
+use crate::{
+    eval::{Arithmetic, Operator},
+    Stack,
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt::{self, Debug, Display};
+
+/// An instruction of the stack machine.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Instruction<V, O = Arithmetic> {
+    /// Pushes a constant.
+    Push(V),
+    /// Applies an operator to the operands on top of the stack, replacing them with its result.
+    Op(O),
+    /// Pushes a copy of the top operand.
+    Dup,
+    /// Swaps the top two operands.
+    Swap,
+    /// Pushes a copy of the operand below the top one.
+    Over,
+    /// Pops the top operand.
+    Drop,
+    /// Continues at the given address.
+    Jump(usize),
+    /// Pops the top operand, and continues at the given address if it is the default value, such
+    /// as zero.
+    JumpIfZero(usize),
+    /// Continues at the given address, returning to the next instruction on [Instruction::Ret].
+    Call(usize),
+    /// Returns to the instruction after the latest [Instruction::Call], or halts if there is
+    /// none.
+    Ret,
+    /// Calls the host function registered under the given index.
+    Host(usize),
+    /// Halts the machine.
+    Halt,
+}
+
+/// The error returned when a program traps.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error<E> {
+    /// An instruction needed more operands than were on the stack.
+    Underflow {
+        /// The address of the instruction.
+        pc: usize,
+    },
+    /// An operator couldn't be applied to its operands.
+    Operator {
+        /// The address of the instruction.
+        pc: usize,
+        /// The error the operator returned.
+        error: E,
+    },
+    /// No host function is registered under the index called.
+    UnknownHost {
+        /// The address of the instruction.
+        pc: usize,
+        /// The index called.
+        index: usize,
+    },
+    /// A host function failed.
+    Host {
+        /// The address of the instruction.
+        pc: usize,
+        /// The message the host function returned.
+        message: &'static str,
+    },
+    /// The program ran for as many steps as it was allowed to without halting.
+    StepLimit {
+        /// The address of the next instruction.
+        pc: usize,
+    },
+}
+impl<E: Display> Display for Error<E> {
+    /// Formats the error.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Underflow { pc } => write!(f, "instruction at {pc} underflowed the stack"),
+            Self::Operator { pc, error } => write!(f, "operator at {pc} failed: {error}"),
+            Self::UnknownHost { pc, index } => {
+                write!(
+                    f,
+                    "instruction at {pc} called unknown host function {index}"
+                )
+            }
+            Self::Host { pc, message } => {
+                write!(f, "host function called at {pc} failed: {message}")
+            }
+            Self::StepLimit { pc } => write!(f, "step limit reached before {pc}"),
+        }
+    }
+}
+impl<E: core::error::Error + 'static> core::error::Error for Error<E> {
+    /// Returns the operator's error, if any.
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Operator { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// A function the host exposes to programs, working on the operand stack directly.
+type HostFn<'a, V> = Box<dyn FnMut(&mut Stack<V>) -> Result<(), &'static str> + 'a>;
+
+/// A stack machine, holding the operand stack and the host functions between runs.
+/// # Example
+/// ```
+/// use hay::vm::{Instruction, Vm};
+/// let mut vm = Vm::new();
+/// let double = vm.register(|stack: &mut hay::Stack<i32>| {
+///     let value = stack.pop().ok_or("nothing to double")?;
+///     stack.push(value * 2);
+///     Ok(())
+/// });
+/// let program: [Instruction<i32>; 2] = [Instruction::Push(21), Instruction::Host(double)];
+/// vm.run(&program).unwrap();
+/// assert_eq!(vm.operands().top(), Some(&42));
+/// ```
+pub struct Vm<'a, V> {
+    operands: Stack<V>,
+    calls: Stack<usize>,
+    hosts: Vec<HostFn<'a, V>>,
+}
+impl<'a, V: Clone + Default + PartialEq> Vm<'a, V> {
+    /// Constructs a new `Vm<V>`, with empty stacks and no host functions.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            operands: Stack::new(),
+            calls: Stack::new(),
+            hosts: Vec::new(),
+        }
+    }
+
+    /// Registers a host function, returning the index programs call it under with
+    /// [Instruction::Host].
+    ///
+    /// The function works on the operand stack directly, and fails the program with
+    /// [Error::Host] by returning a message.
+    pub fn register(
+        &mut self,
+        function: impl FnMut(&mut Stack<V>) -> Result<(), &'static str> + 'a,
+    ) -> usize {
+        self.hosts.push(Box::new(function));
+        self.hosts.len() - 1
+    }
+
+    /// Returns a reference to the operand stack.
+    #[inline(always)]
+    pub const fn operands(&self) -> &Stack<V> {
+        &self.operands
+    }
+
+    /// Returns a mutable reference to the operand stack, for passing arguments to a program or
+    /// taking its results.
+    #[inline(always)]
+    pub fn operands_mut(&mut self) -> &mut Stack<V> {
+        &mut self.operands
+    }
+
+    /// Runs a program from its first instruction until it halts, returns from its outermost
+    /// call, or runs past its end.
+    ///
+    /// The operand stack is kept, so it holds the program's results afterwards.
+    /// # Errors
+    /// Returns an [Error] if the program traps, leaving the operand stack as it was at the time.
+    #[inline(always)]
+    pub fn run<O: Operator<V>>(
+        &mut self,
+        program: &[Instruction<V, O>],
+    ) -> Result<(), Error<O::Error>> {
+        self.run_limited(program, usize::MAX)
+    }
+
+    /// Runs a program like [Vm::run], but for at most `steps` instructions.
+    /// # Errors
+    /// Returns an [Error] if the program traps, or [Error::StepLimit] if it doesn't halt within
+    /// `steps` instructions.
+    /// # Example
+    /// ```
+    /// use hay::vm::{Error, Instruction, Vm};
+    /// let mut vm = Vm::<i32>::new();
+    /// let program: [Instruction<i32>; 1] = [Instruction::Jump(0)];
+    /// assert_eq!(vm.run_limited(&program, 100), Err(Error::StepLimit { pc: 0 }));
+    /// ```
+    pub fn run_limited<O: Operator<V>>(
+        &mut self,
+        program: &[Instruction<V, O>],
+        steps: usize,
+    ) -> Result<(), Error<O::Error>> {
+        self.calls.clear();
+        let mut pc = 0;
+        for _ in 0..steps {
+            let Some(instruction) = program.get(pc) else {
+                return Ok(());
+            };
+            let underflow = Error::Underflow { pc };
+            pc += 1;
+            match instruction {
+                Instruction::Push(value) => self.operands.push(value.clone()),
+                Instruction::Op(operator) => {
+                    let start = self
+                        .operands
+                        .len()
+                        .checked_sub(operator.arity())
+                        .ok_or(underflow)?;
+                    let value = operator
+                        .apply(&self.operands[start..])
+                        .map_err(|error| Error::Operator { pc: pc - 1, error })?;
+                    self.operands.vec.truncate(start);
+                    self.operands.push(value);
+                }
+                Instruction::Dup => {
+                    let value = self.operands.top().ok_or(underflow)?.clone();
+                    self.operands.push(value);
+                }
+                Instruction::Swap => {
+                    let len = self.operands.len();
+                    if len < 2 {
+                        return Err(underflow);
+                    }
+                    self.operands.vec.swap(len - 1, len - 2);
+                }
+                Instruction::Over => {
+                    let len = self.operands.len();
+                    if len < 2 {
+                        return Err(underflow);
+                    }
+                    let value = self.operands[len - 2].clone();
+                    self.operands.push(value);
+                }
+                Instruction::Drop => drop(self.operands.pop().ok_or(underflow)?),
+                Instruction::Jump(target) => pc = *target,
+                Instruction::JumpIfZero(target) => {
+                    if self.operands.pop().ok_or(underflow)? == V::default() {
+                        pc = *target;
+                    }
+                }
+                Instruction::Call(target) => {
+                    self.calls.push(pc);
+                    pc = *target;
+                }
+                Instruction::Ret => match self.calls.pop() {
+                    Some(address) => pc = address,
+                    None => return Ok(()),
+                },
+                Instruction::Host(index) => {
+                    let Some(function) = self.hosts.get_mut(*index) else {
+                        return Err(Error::UnknownHost {
+                            pc: pc - 1,
+                            index: *index,
+                        });
+                    };
+                    function(&mut self.operands).map_err(|message| Error::Host {
+                        pc: pc - 1,
+                        message,
+                    })?;
+                }
+                Instruction::Halt => return Ok(()),
+            }
+        }
+        Err(Error::StepLimit { pc })
+    }
+}
+impl<V: Clone + Default + PartialEq> Default for Vm<'_, V> {
+    /// Constructs a new `Vm<V>`, with empty stacks and no host functions.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<V: Debug> Debug for Vm<'_, V> {
+    /// Formats the stacks, and the number of host functions.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Vm")
+            .field("operands", &self.operands)
+            .field("calls", &self.calls)
+            .field("hosts", &self.hosts.len())
+            .finish()
+    }
+}
@@ -0,0 +1,372 @@
+use crate::sync::{fence, AtomicIsize, AtomicPtr, Ordering};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::{
+    cell::UnsafeCell,
+    fmt::{self, Debug},
+    mem::MaybeUninit,
+    ptr,
+};
+
+/// The number of slots in the first buffer of a [WorkStack].
+const MIN_CAPACITY: usize = 32;
+
+/// A circular buffer of slots, indexed modulo its power of two capacity.
+struct Buffer<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+}
+impl<T> Buffer<T> {
+    /// Allocates a new buffer with `capacity` uninitialized slots.
+    fn alloc(capacity: usize) -> *mut Self {
+        let slots = (0..capacity)
+            .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+            .collect();
+        Box::into_raw(Box::new(Self { slots }))
+    }
+
+    /// Returns the number of slots in the buffer.
+    #[inline(always)]
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns a pointer to the slot for `index`.
+    #[inline(always)]
+    fn slot(&self, index: isize) -> *mut MaybeUninit<T> {
+        self.slots[index as usize & (self.capacity() - 1)].get()
+    }
+
+    /// Copies a value into the slot for `index`.
+    /// # Safety
+    /// No other thread may keep the copy it read from this slot.
+    #[inline(always)]
+    unsafe fn write(&self, index: isize, value: MaybeUninit<T>) {
+        // Volatile, since a stealer which is about to lose its race may read the slot meanwhile.
+        ptr::write_volatile(self.slot(index), value);
+    }
+
+    /// Copies the value out of the slot for `index`, which may not be initialized.
+    /// # Safety
+    /// The copy must only be assumed initialized once taking the slot succeeded.
+    #[inline(always)]
+    unsafe fn read(&self, index: isize) -> MaybeUninit<T> {
+        ptr::read_volatile(self.slot(index))
+    }
+}
+
+/// The state shared between a [WorkStack] and its stealers.
+struct Inner<T> {
+    /// The index stealers take from, which only ever grows.
+    top: AtomicIsize,
+    /// The index one past the owner's end.
+    bottom: AtomicIsize,
+    buffer: AtomicPtr<Buffer<T>>,
+    /// Buffers replaced by a larger one, which stealers may still be reading from.
+    ///
+    /// Only touched by the owner, and freed once no stealer is left.
+    retired: UnsafeCell<Vec<*mut Buffer<T>>>,
+}
+// SAFETY: Values are moved between threads, but every slot is taken by exactly one of them.
+unsafe impl<T: Send> Send for Inner<T> {}
+// SAFETY: Values are moved between threads, but every slot is taken by exactly one of them.
+unsafe impl<T: Send> Sync for Inner<T> {}
+impl<T> Inner<T> {
+    /// Returns the number of elements at the moment it was checked.
+    #[inline(always)]
+    fn len(&self) -> usize {
+        let bottom = self.bottom.load(Ordering::Relaxed);
+        let top = self.top.load(Ordering::Relaxed);
+        usize::try_from(bottom.wrapping_sub(top)).unwrap_or(0)
+    }
+}
+impl<T> Drop for Inner<T> {
+    /// Drops every element left and frees every buffer.
+    fn drop(&mut self) {
+        let buffer = self.buffer.load(Ordering::Relaxed);
+        let top = self.top.load(Ordering::Relaxed);
+        let bottom = self.bottom.load(Ordering::Relaxed);
+        // SAFETY: Nothing else can reach the buffers anymore, and the slots between the top and
+        // bottom hold the only copies of the elements left.
+        unsafe {
+            for index in top..bottom {
+                (*(*buffer).slot(index)).assume_init_drop();
+            }
+            drop(Box::from_raw(buffer));
+            for retired in self.retired.get_mut().drain(..) {
+                drop(Box::from_raw(retired));
+            }
+        }
+    }
+}
+
+/// A work-stealing stack for task schedulers, based on the Chase-Lev deque.
+///
+/// The owning thread pushes and pops LIFO at one end, keeping its working set hot in cache,
+/// while [Stealer]s on other threads take the oldest elements from the opposite end. Neither
+/// side takes a lock.
+/// # Example
+/// ```
+/// use hay::WorkStack;
+/// let mut stack = WorkStack::new();
+/// let stealer = stack.stealer();
+/// stack.push(1);
+/// stack.push(2);
+/// stack.push(3);
+/// std::thread::scope(|scope| {
+///     scope.spawn(move || assert_eq!(stealer.steal(), Some(1)));
+/// });
+/// assert_eq!(stack.pop(), Some(3));
+/// assert_eq!(stack.pop(), Some(2));
+/// assert_eq!(stack.pop(), None);
+/// ```
+pub struct WorkStack<T> {
+    inner: Arc<Inner<T>>,
+}
+impl<T> WorkStack<T> {
+    /// Constructs a new, empty `WorkStack<T>`.
+    /// # Example
+    /// ```
+    /// use hay::WorkStack;
+    /// let stack: WorkStack<i32> = WorkStack::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                top: AtomicIsize::new(0),
+                bottom: AtomicIsize::new(0),
+                buffer: AtomicPtr::new(Buffer::alloc(MIN_CAPACITY)),
+                retired: UnsafeCell::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Returns a new handle which steals elements from the bottom of the stack.
+    /// # Example
+    /// ```
+    /// use hay::WorkStack;
+    /// let mut stack = WorkStack::new();
+    /// let stealer = stack.stealer();
+    /// stack.push(1);
+    /// assert_eq!(stealer.steal(), Some(1));
+    /// ```
+    #[inline(always)]
+    pub fn stealer(&self) -> Stealer<T> {
+        Stealer {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+
+    /// Moves the stack's elements into a buffer twice as large, returning it.
+    #[cold]
+    fn grow(&mut self, top: isize, bottom: isize, old: *mut Buffer<T>) -> *mut Buffer<T> {
+        // SAFETY: Only the owner replaces buffers, so the old one is still allocated.
+        let old_ref = unsafe { &*old };
+        let new = Buffer::alloc(old_ref.capacity() * 2);
+        for index in top..bottom {
+            // SAFETY: The new buffer isn't shared yet, and the copies in the old one are
+            // abandoned, so no value is duplicated.
+            unsafe { (*new).write(index, old_ref.read(index)) };
+        }
+        self.inner.buffer.store(new, Ordering::Release);
+        // SAFETY: Only the owner touches the retired buffers, and `&mut self` makes this the
+        // owner.
+        unsafe { (*self.inner.retired.get()).push(old) };
+        new
+    }
+
+    /// Appends an element to the owner's end of the stack.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// use hay::WorkStack;
+    /// let mut stack = WorkStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    pub fn push(&mut self, value: T) {
+        let bottom = self.inner.bottom.load(Ordering::Relaxed);
+        let top = self.inner.top.load(Ordering::Acquire);
+        let mut buffer = self.inner.buffer.load(Ordering::Relaxed);
+        // SAFETY: Only the owner replaces buffers, so the current one is still allocated.
+        if bottom.wrapping_sub(top) >= unsafe { (*buffer).capacity() } as isize {
+            buffer = self.grow(top, bottom, buffer);
+        }
+        // SAFETY: The slot is outside the range stealers can take, so none will keep its copy.
+        unsafe { (*buffer).write(bottom, MaybeUninit::new(value)) };
+        fence(Ordering::Release);
+        self.inner
+            .bottom
+            .store(bottom.wrapping_add(1), Ordering::Relaxed);
+    }
+
+    /// Removes the most recently pushed element and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::WorkStack;
+    /// let mut stack = WorkStack::new();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// assert_eq!(stack.pop(), Some(2));
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        let bottom = self.inner.bottom.load(Ordering::Relaxed).wrapping_sub(1);
+        let buffer = self.inner.buffer.load(Ordering::Relaxed);
+        self.inner.bottom.store(bottom, Ordering::Relaxed);
+        fence(Ordering::SeqCst);
+        let top = self.inner.top.load(Ordering::Relaxed);
+        if top > bottom {
+            // Empty, so the bottom goes back where it was.
+            self.inner
+                .bottom
+                .store(bottom.wrapping_add(1), Ordering::Relaxed);
+            return None;
+        }
+        // SAFETY: Only the owner replaces buffers, so the current one is still allocated.
+        let value = unsafe { (*buffer).read(bottom) };
+        if top == bottom {
+            // This is the last element, so racing stealers for it.
+            let won = self
+                .inner
+                .top
+                .compare_exchange(
+                    top,
+                    top.wrapping_add(1),
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                )
+                .is_ok();
+            self.inner
+                .bottom
+                .store(bottom.wrapping_add(1), Ordering::Relaxed);
+            if !won {
+                return None;
+            }
+        }
+        // SAFETY: The slot was taken, so this is now the only copy of its value.
+        Some(unsafe { value.assume_init() })
+    }
+
+    /// Returns the number of elements on the stack at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::WorkStack;
+    /// let mut stack = WorkStack::new();
+    /// stack.push(1);
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the stack contained no elements at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::WorkStack;
+    /// let mut stack = WorkStack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+impl<T> Default for WorkStack<T> {
+    /// Constructs a new, empty `WorkStack<T>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Debug for WorkStack<T> {
+    /// Formats a `WorkStack<T>` without its elements, which may be stolen at any moment.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkStack")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A handle which steals the oldest elements of a [WorkStack] from another thread.
+pub struct Stealer<T> {
+    inner: Arc<Inner<T>>,
+}
+impl<T> Stealer<T> {
+    /// Removes the least recently pushed element and returns it, or [None] if it is empty.
+    /// # Example
+    /// ```
+    /// use hay::WorkStack;
+    /// let mut stack = WorkStack::new();
+    /// let stealer = stack.stealer();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// assert_eq!(stealer.steal(), Some(1));
+    /// assert_eq!(stealer.steal(), Some(2));
+    /// assert_eq!(stealer.steal(), None);
+    /// ```
+    pub fn steal(&self) -> Option<T> {
+        loop {
+            let top = self.inner.top.load(Ordering::Acquire);
+            fence(Ordering::SeqCst);
+            let bottom = self.inner.bottom.load(Ordering::Acquire);
+            if top >= bottom {
+                return None;
+            }
+            let buffer = self.inner.buffer.load(Ordering::Acquire);
+            // SAFETY: Replaced buffers are only freed once every stealer is gone, and the copy is
+            // only kept if taking the slot succeeds.
+            let value = unsafe { (*buffer).read(top) };
+            if self
+                .inner
+                .top
+                .compare_exchange(
+                    top,
+                    top.wrapping_add(1),
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+            {
+                // SAFETY: The slot was taken, so this is now the only copy of its value.
+                return Some(unsafe { value.assume_init() });
+            }
+        }
+    }
+
+    /// Returns `true` if the stack contained no elements at the moment it was checked.
+    /// # Example
+    /// ```
+    /// use hay::WorkStack;
+    /// let mut stack = WorkStack::new();
+    /// let stealer = stack.stealer();
+    /// assert!(stealer.is_empty());
+    /// stack.push(1);
+    /// assert!(!stealer.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.inner.len() == 0
+    }
+}
+impl<T> Clone for Stealer<T> {
+    /// Returns another handle stealing from the same stack.
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+impl<T> Debug for Stealer<T> {
+    /// Formats a `Stealer<T>` without the stack's elements, which may change at any moment.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stealer")
+            .field("len", &self.inner.len())
+            .finish_non_exhaustive()
+    }
+}
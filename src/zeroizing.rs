@@ -0,0 +1,234 @@
+use crate::Stack;
+use alloc::vec::Vec;
+use core::{
+    fmt, mem,
+    ops::{Deref, DerefMut},
+};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+impl<T: Zeroize> Zeroize for Stack<T> {
+    /// Zeroizes every element, clears the stack, and wipes its whole buffer.
+    /// # Example
+    /// ```
+    /// use hay::Stack;
+    /// use zeroize::Zeroize;
+    /// let mut stack = Stack::new();
+    /// stack.push([0xAAu8; 32]);
+    /// stack.zeroize();
+    /// assert!(stack.is_empty());
+    /// ```
+    #[inline(always)]
+    fn zeroize(&mut self) {
+        self.vec.zeroize();
+    }
+}
+
+/// A stack for secrets, which wipes the memory of every element it pops, clears, or drops.
+///
+/// Elements left on the stack are zeroized before they are dropped, and the slots popped
+/// elements were moved out of are wiped, so no copy of a secret outlives its place on the stack.
+/// When the stack grows, its elements are moved into the new buffer and the old one is wiped
+/// before being freed, which [Vec] wouldn't do on its own.
+/// # Example
+/// ```
+/// use hay::ZeroizingStack;
+/// let mut keys = ZeroizingStack::new();
+/// keys.push([0x42u8; 32]);
+/// keys.push([0x43u8; 32]);
+/// let key = keys.pop().unwrap();
+/// assert_eq!(key, [0x43; 32]);
+/// // Dropping the stack wipes the remaining key along with the buffer.
+/// drop(keys);
+/// ```
+pub struct ZeroizingStack<T: Zeroize> {
+    vec: Vec<T>,
+}
+impl<T: Zeroize> ZeroizingStack<T> {
+    /// Constructs a new, empty `ZeroizingStack<T>`.
+    ///
+    /// The stack will not allocate until elements are pushed onto it.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self { vec: Vec::new() }
+    }
+
+    /// Constructs a new, empty `ZeroizingStack<T>` with room for at least `capacity` elements, so
+    /// it doesn't have to move its secrets until it holds more.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    #[must_use]
+    #[inline(always)]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            vec: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns a reference to the top element in the stack.
+    #[inline(always)]
+    pub const fn top(&self) -> Option<&T> {
+        self.vec.as_slice().last()
+    }
+
+    /// Returns a mutable reference to the top element in the stack.
+    #[inline(always)]
+    pub const fn top_mut(&mut self) -> Option<&mut T> {
+        self.vec.as_mut_slice().last_mut()
+    }
+
+    /// Appends an element to the top of the stack, wiping the old buffer if it has to grow.
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    #[inline(always)]
+    pub fn push(&mut self, value: T) {
+        if self.vec.len() == self.vec.capacity() {
+            self.grow();
+        }
+        self.vec.push(value);
+    }
+
+    /// Moves the elements into a buffer twice as big, and wipes the old one before freeing it.
+    #[cold]
+    fn grow(&mut self) {
+        let capacity = self
+            .vec
+            .capacity()
+            .checked_mul(2)
+            .expect("capacity overflow")
+            .max(4);
+        let mut vec = Vec::with_capacity(capacity);
+        vec.append(&mut self.vec);
+        let mut old = mem::replace(&mut self.vec, vec);
+        old.spare_capacity_mut().zeroize();
+    }
+
+    /// Removes the element at the top of the stack and returns it, wiping the slot it was in, or
+    /// returns [None] if it is empty.
+    ///
+    /// The returned element is up to the caller to zeroize.
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.vec.pop()?;
+        self.vec.spare_capacity_mut()[0].zeroize();
+        Some(value)
+    }
+
+    /// Shortens the stack to `len` elements, zeroizing and dropping the rest and wiping their
+    /// slots, or does nothing if it is already no longer than `len`.
+    /// # Example
+    /// ```
+    /// use hay::ZeroizingStack;
+    /// let mut stack = ZeroizingStack::new();
+    /// stack.extend([vec![1u8; 16], vec![2; 16], vec![3; 16]]);
+    /// stack.truncate(1);
+    /// assert_eq!(*stack, [vec![1; 16]]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        let Some(removed) = self.vec.len().checked_sub(len) else {
+            return;
+        };
+        self.vec[len..].iter_mut().zeroize();
+        self.vec.truncate(len);
+        self.vec.spare_capacity_mut()[..removed].zeroize();
+    }
+
+    /// Clears the stack, zeroizing and dropping every element and wiping their slots.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the stack.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Returns the number of elements in the stack.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns the number of elements the stack can hold without growing.
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+}
+impl<T: Zeroize> Default for ZeroizingStack<T> {
+    /// Constructs a new, empty `ZeroizingStack<T>`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Zeroize> fmt::Debug for ZeroizingStack<T> {
+    /// Formats the stack without its elements, so secrets don't end up in logs.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZeroizingStack")
+            .field("len", &self.vec.len())
+            .finish_non_exhaustive()
+    }
+}
+impl<T: Zeroize> Deref for ZeroizingStack<T> {
+    /// The slice of elements in the stack.
+    type Target = [T];
+
+    /// Returns the elements in the stack, from bottom to top.
+    #[inline(always)]
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+impl<T: Zeroize> DerefMut for ZeroizingStack<T> {
+    /// Returns the elements in the stack mutably, from bottom to top.
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.vec
+    }
+}
+impl<T: Zeroize> Drop for ZeroizingStack<T> {
+    /// Zeroizes every element before dropping it, leaving the whole buffer wiped.
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+impl<T: Zeroize> Extend<T> for ZeroizingStack<T> {
+    /// Pushes a collection of values onto a stack, wiping any buffer it outgrows.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+impl<T: Zeroize> From<Stack<T>> for ZeroizingStack<T> {
+    /// Wraps a stack, taking over its buffer and wiping its spare capacity, which may still hold
+    /// elements popped off the stack.
+    /// # Example
+    /// ```
+    /// use hay::{Stack, ZeroizingStack};
+    /// let mut stack = Stack::new();
+    /// stack.extend([[0x42u8; 32], [0x43; 32]]);
+    /// stack.pop();
+    /// let keys = ZeroizingStack::from(stack);
+    /// assert_eq!(*keys, [[0x42; 32]]);
+    /// ```
+    #[inline(always)]
+    fn from(stack: Stack<T>) -> Self {
+        let mut vec = stack.vec;
+        vec.spare_capacity_mut().zeroize();
+        Self { vec }
+    }
+}
+impl<T: Zeroize> Zeroize for ZeroizingStack<T> {
+    /// Zeroizes and drops every element, wiping their slots.
+    #[inline(always)]
+    fn zeroize(&mut self) {
+        self.clear();
+    }
+}
+impl<T: Zeroize> ZeroizeOnDrop for ZeroizingStack<T> {}
@@ -0,0 +1,120 @@
+//! Exhaustive interleaving tests for the lock-free stacks.
+//!
+//! Run with `RUSTFLAGS="--cfg loom" cargo test --test loom --release`.
+#![cfg(loom)]
+use hay::{AtomicArrayStack, BoundedStack, ConcurrentStack, WorkStack};
+use loom::{sync::Arc, thread};
+
+#[test]
+fn concurrent_pushes() {
+    loom::model(|| {
+        let stack = Arc::new(ConcurrentStack::new());
+        let pushers: Vec<_> = (0..2)
+            .map(|i| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || stack.push(i))
+            })
+            .collect();
+        for pusher in pushers {
+            pusher.join().unwrap();
+        }
+        let mut popped = [stack.pop(), stack.pop()];
+        popped.sort();
+        assert_eq!(popped, [Some(0), Some(1)]);
+        assert_eq!(stack.pop(), None);
+    });
+}
+
+#[test]
+fn concurrent_push_and_pop() {
+    loom::model(|| {
+        let stack = Arc::new(ConcurrentStack::new());
+        stack.push(0);
+        let pusher = {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || stack.push(1))
+        };
+        let first = stack.pop();
+        pusher.join().unwrap();
+        let second = stack.pop();
+        assert!(matches!(
+            (first, second),
+            (Some(0), Some(1)) | (Some(1), Some(0))
+        ));
+        assert_eq!(stack.pop(), None);
+    });
+}
+
+#[test]
+fn concurrent_pops() {
+    loom::model(|| {
+        let stack = Arc::new(ConcurrentStack::new());
+        stack.push(0);
+        stack.push(1);
+        let popper = {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || stack.pop())
+        };
+        let mine = stack.pop();
+        let theirs = popper.join().unwrap();
+        let mut popped = [mine, theirs];
+        popped.sort();
+        assert_eq!(popped, [Some(0), Some(1)]);
+    });
+}
+
+#[test]
+fn bounded_pushes() {
+    loom::model(|| {
+        let stack = Arc::new(BoundedStack::new(1));
+        let pusher = {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || stack.try_push(0).is_ok())
+        };
+        let mine = stack.try_push(1).is_ok();
+        let theirs = pusher.join().unwrap();
+        assert!(mine != theirs);
+        assert_eq!(stack.len(), 1);
+    });
+}
+
+#[test]
+fn atomic_array_producer_and_consumer() {
+    loom::model(|| {
+        let stack: &'static mut AtomicArrayStack<usize, 2> =
+            Box::leak(Box::new(AtomicArrayStack::new()));
+        let (mut producer, mut consumer) = stack.split();
+        let pusher = thread::spawn(move || {
+            producer.push(0).unwrap();
+            producer.push(1).unwrap();
+        });
+        let mut popped = Vec::new();
+        while popped.len() < 2 {
+            match consumer.pop() {
+                Some(value) => popped.push(value),
+                None => thread::yield_now(),
+            }
+        }
+        pusher.join().unwrap();
+        popped.sort();
+        assert_eq!(popped, [0, 1]);
+    });
+}
+
+#[test]
+fn work_stack_pop_and_steal() {
+    loom::model(|| {
+        let mut stack = WorkStack::new();
+        let stealer = stack.stealer();
+        stack.push(0);
+        stack.push(1);
+        let thief = thread::spawn(move || stealer.steal());
+        let mut popped = Vec::new();
+        while let Some(value) = stack.pop() {
+            popped.push(value);
+        }
+        popped.extend(thief.join().unwrap());
+        popped.sort();
+        assert_eq!(popped, [0, 1]);
+    });
+}